@@ -0,0 +1,343 @@
+//! Blocking Object Transfer Service (OTS) client
+//!
+//! This is a synchronous facade over asynchronous [`OtsClient`](crate::OtsClient)
+//! which drives the D-Bus session using internal single-threaded runtime
+//! and transfers object data through blocking L2CAP socket. Other [`Gatt`]
+//! transports can be used by [`OtsClient::with_gatt`].
+//!
+//! ```no_run
+//! use bluez_async_ots::blocking::{OtsClient, Session};
+//!
+//! # fn main() -> bluez_async_ots::Result<()> {
+//! let session = Session::new()?;
+//! let devices = session.block_on(session.get_devices())?;
+//!
+//! let ots = OtsClient::new(&session, &devices[0].id, &Default::default())?;
+//!
+//! ots.first()?;
+//! println!("{:?}", ots.metadata()?);
+//! let data = ots.read(0, None)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{
+    ActionFeature, BluezGatt, Characteristics, ClientConfig, DateTime, ExecuteCommand, Gatt,
+    ListFeature, Metadata, Property, Result, Sizes, SortOrder, WriteMode,
+};
+use bluez_async::{AdapterInfo, BluetoothSession, DeviceId, DeviceInfo, ServiceId, ServiceInfo};
+use ots_core::l2cap::L2capSocket as Socket;
+use std::{
+    io::{Read, Write},
+    sync::Arc,
+};
+use tokio::runtime::Runtime;
+use uuid::Uuid;
+
+/// Blocking bluetooth session
+///
+/// Holds D-Bus session together with runtime which drives it.
+#[derive(Clone)]
+pub struct Session {
+    runtime: Arc<Runtime>,
+    session: BluetoothSession,
+}
+
+impl core::ops::Deref for Session {
+    type Target = BluetoothSession;
+
+    fn deref(&self) -> &Self::Target {
+        &self.session
+    }
+}
+
+impl AsRef<BluetoothSession> for Session {
+    fn as_ref(&self) -> &BluetoothSession {
+        &self.session
+    }
+}
+
+impl core::fmt::Debug for Session {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_struct("Session").finish()
+    }
+}
+
+impl Session {
+    /// Establish new D-Bus connection to communicate with BlueZ
+    pub fn new() -> Result<Self> {
+        let runtime = runtime()?;
+
+        let (_, session) = runtime.block_on(BluetoothSession::new())?;
+
+        Ok(Self {
+            runtime: Arc::new(runtime),
+            session,
+        })
+    }
+
+    /// Run future to completion
+    ///
+    /// Use it to call asynchronous [`BluetoothSession`] methods
+    /// like device discovery or connection.
+    pub fn block_on<F: core::future::Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+}
+
+fn runtime() -> Result<Runtime> {
+    Ok(tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?)
+}
+
+/// Blocking Object Transfer Service (OTS) client
+pub struct OtsClient<G: Gatt = BluezGatt> {
+    runtime: Arc<Runtime>,
+    client: crate::OtsClient<G>,
+}
+
+impl<G: Gatt> AsRef<crate::OtsClient<G>> for OtsClient<G> {
+    fn as_ref(&self) -> &crate::OtsClient<G> {
+        &self.client
+    }
+}
+
+impl<G: Gatt + core::fmt::Debug> core::fmt::Debug for OtsClient<G> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_struct("OtsClient")
+            .field("client", &self.client)
+            .finish()
+    }
+}
+
+macro_rules! blocking_fns {
+    ($($(#[$($meta:meta)*])* $func:ident ( $($arg_name:ident: $arg_type:ty),* ) -> $res_type:ty;)*) => {
+        $(
+            $(#[$($meta)*])*
+            pub fn $func(&self $(, $arg_name: $arg_type)*) -> Result<$res_type> {
+                self.runtime.block_on(self.client.$func($($arg_name),*))
+            }
+        )*
+    };
+}
+
+impl OtsClient {
+    /// Get all OTS service instances of device
+    pub fn services(session: &Session, device_id: &DeviceId) -> Result<Vec<ServiceInfo>> {
+        session.block_on(crate::OtsClient::services(session, device_id))
+    }
+
+    /// Create new client instance
+    pub fn new(session: &Session, device_id: &DeviceId, config: &ClientConfig) -> Result<Self> {
+        let client = session.block_on(crate::OtsClient::new(session, device_id, config))?;
+        Ok(Self::with_client(session, client))
+    }
+
+    /// Create new client instance using known service identifier
+    pub fn with_service(
+        session: &Session,
+        service_id: &ServiceId,
+        config: &ClientConfig,
+    ) -> Result<Self> {
+        let client =
+            session.block_on(crate::OtsClient::with_service(session, service_id, config))?;
+        Ok(Self::with_client(session, client))
+    }
+
+    /// Create new client instance using known adapter and device info
+    pub fn with_info(
+        session: &Session,
+        adapter_info: &AdapterInfo,
+        device_info: &DeviceInfo,
+        config: &ClientConfig,
+    ) -> Result<Self> {
+        let client = session.block_on(crate::OtsClient::with_info(
+            session,
+            adapter_info,
+            device_info,
+            config,
+        ))?;
+        Ok(Self::with_client(session, client))
+    }
+
+    /// Create new client instance using already discovered characteristics
+    pub fn with_characteristics(
+        session: &Session,
+        chrs: Characteristics,
+        config: &ClientConfig,
+    ) -> Result<Self> {
+        let client = session.block_on(crate::OtsClient::with_characteristics(
+            session, chrs, config,
+        ))?;
+        Ok(Self::with_client(session, client))
+    }
+
+    fn with_client(session: &Session, client: crate::OtsClient) -> Self {
+        Self {
+            runtime: session.runtime.clone(),
+            client,
+        }
+    }
+
+    /// Get discovered characteristics
+    pub fn characteristics(&self) -> &Characteristics {
+        self.client.characteristics()
+    }
+}
+
+impl<G: Gatt> OtsClient<G> {
+    /// Create new client instance using GATT transport
+    ///
+    /// Transport is driven by own runtime of client.
+    pub fn with_gatt(gatt: G) -> Result<Self> {
+        let runtime = runtime()?;
+        let client = runtime.block_on(crate::OtsClient::with_gatt(gatt))?;
+        Ok(Self {
+            runtime: Arc::new(runtime),
+            client,
+        })
+    }
+
+    /// Get object action feature flags
+    pub fn action_features(&self) -> &ActionFeature {
+        self.client.action_features()
+    }
+
+    /// Get object list feature flags
+    pub fn list_features(&self) -> &ListFeature {
+        self.client.list_features()
+    }
+
+    blocking_fns! {
+        /// Get current object identifier
+        id() -> Option<u64>;
+        /// Get current object name
+        name() -> String;
+        /// Set current object name
+        set_name(name: &str) -> ();
+        /// Get current object type
+        type_() -> Uuid;
+        /// Get sizes of current object
+        size() -> Sizes;
+        /// Get first created time for selected object
+        first_created() -> Option<DateTime>;
+        /// Set first created time for selected object
+        set_first_created(time: &DateTime) -> ();
+        /// Get last modified time for selected object
+        last_modified() -> Option<DateTime>;
+        /// Set last modified time for selected object
+        set_last_modified(time: &DateTime) -> ();
+        /// Get current object properties
+        properties() -> Property;
+        /// Set current object properties
+        set_properties(properties: Property) -> ();
+        /// Get current object metadata
+        metadata() -> Metadata;
+        /// Select first object in a list
+        first() -> ();
+        /// Select last object in a list
+        last() -> ();
+        /// Select previous object
+        ///
+        /// Returns `false` if current object is first.
+        previous() -> bool;
+        /// Select next object
+        ///
+        /// Returns `false` if current object is last.
+        next() -> bool;
+        /// Select object by identifier
+        ///
+        /// Returns `false` if object nor found.
+        go_to(id: u64) -> bool;
+        /// Change objects order in a list
+        order(order: SortOrder) -> ();
+        /// Get number of objects in a list
+        number_of() -> u32;
+        /// Clear objects mark
+        clear_mark() -> ();
+        /// Create new object
+        create(size: usize, type_: Uuid) -> ();
+        /// Delete selected object
+        delete() -> ();
+        /// Calculate checksum using selected object data
+        check_sum(offset: usize, length: usize) -> u32;
+        /// Execute selected object
+        execute(param: Vec<u8>) -> Vec<u8>;
+        /// Abort operation
+        abort() -> ();
+    }
+
+    /// Open data channel
+    ///
+    /// Returns blocking socket with its send MTU.
+    fn channel(&self) -> Result<(Socket, usize)> {
+        let stream = self.runtime.block_on(self.client.gatt.open_channel())?;
+        let send_mtu = stream.max_send_sdu();
+        Ok((stream.into_blocking()?, send_mtu))
+    }
+
+    /// Execute selected object using typed command
+    pub fn execute_typed<C: ExecuteCommand>(&self, param: &C::Param) -> Result<C::Response> {
+        self.runtime.block_on(self.client.execute_typed::<C>(param))
+    }
+
+    /// Read object data
+    pub fn read(&self, offset: usize, length: Option<usize>) -> Result<Vec<u8>> {
+        let length = if let Some(length) = length {
+            length
+        } else {
+            self.size()?.current
+        };
+
+        let mut buffer = vec![0; length];
+
+        let socket = self.read_base(offset, length)?;
+
+        (&*socket).read_exact(&mut buffer[..length])?;
+
+        Ok(buffer)
+    }
+
+    /// Read object data
+    pub fn read_to(&self, offset: usize, buffer: &mut [u8]) -> Result<usize> {
+        let size = self.size()?.current;
+
+        // length cannot exceeds available length from offset to end
+        let length = buffer.len().min(size.saturating_sub(offset));
+
+        let socket = self.read_base(offset, length)?;
+
+        (&*socket).read_exact(&mut buffer[..length])?;
+
+        Ok(length)
+    }
+
+    fn read_base(&self, offset: usize, length: usize) -> Result<Socket> {
+        let (socket, _) = self.channel()?;
+
+        self.runtime.block_on(self.client.do_read(offset, length))?;
+
+        Ok(socket)
+    }
+
+    /// Write object data
+    pub fn write(&self, offset: usize, buffer: &[u8], mode: WriteMode) -> Result<usize> {
+        let size = self.size()?.allocated;
+
+        // length cannot exceeds available length from offset to end
+        let length = buffer.len().min(size.saturating_sub(offset));
+
+        let (socket, send_mtu) = self.channel()?;
+
+        self.runtime
+            .block_on(self.client.do_write(offset, length, mode))?;
+
+        // single send cannot exceed MTU so split data to SDUs
+        for sdu in buffer[..length].chunks(send_mtu.max(1)) {
+            (&*socket).write_all(sdu)?;
+        }
+
+        Ok(length)
+    }
+}
@@ -0,0 +1,273 @@
+//! GATT transport abstraction
+//!
+//! [`OtsClient`](crate::OtsClient) accesses OTS characteristics and object
+//! data channels through the [`Gatt`] trait. The [`BluezGatt`] implements it
+//! using BlueZ D-Bus session and L2CAP sockets, while alternative
+//! implementations can be used for testing without real hardware.
+
+#[cfg(all(feature = "log", not(feature = "tracing")))]
+use log::{debug, trace};
+
+#[cfg(feature = "tracing")]
+use tracing::{debug, trace};
+
+use crate::{rt, Error, L2capSocket, L2capStream, Result, Security, CONNECT_TIMEOUT};
+use bluez_async::{
+    AdapterId, BluetoothError, BluetoothEvent, BluetoothSession, CharacteristicEvent,
+    CharacteristicId, DeviceId, ServiceId,
+};
+use core::future::Future;
+use futures_util::stream::{BoxStream, StreamExt};
+use ots_core::{
+    ids,
+    l2cap::{L2capSockAddr as SocketAddr, SocketType},
+};
+use uuid::Uuid;
+
+/// GATT transport of OTS client
+///
+/// Characteristics are identified by UUIDs from [`ids::characteristic`].
+pub trait Gatt: Send + Sync {
+    /// Check that characteristic is present
+    fn has_characteristic(&self, uuid: &Uuid) -> bool;
+
+    /// Read characteristic value
+    fn read_characteristic(&self, uuid: &Uuid) -> impl Future<Output = Result<Vec<u8>>> + Send;
+
+    /// Write characteristic value
+    fn write_characteristic(
+        &self,
+        uuid: &Uuid,
+        value: Vec<u8>,
+    ) -> impl Future<Output = Result<()>> + Send;
+
+    /// Enable indications and get stream of indicated values
+    fn subscribe(
+        &self,
+        uuid: &Uuid,
+    ) -> impl Future<Output = Result<BoxStream<'static, Vec<u8>>>> + Send;
+
+    /// Disable indications
+    fn unsubscribe(&self, uuid: &Uuid) -> impl Future<Output = Result<()>> + Send;
+
+    /// Open object data channel
+    fn open_channel(&self) -> impl Future<Output = Result<L2capStream>> + Send;
+}
+
+/// Object Transfer Service (OTS) characteristics
+///
+/// Identifiers of discovered characteristics can be reused to create
+/// client again without full GATT discovery (see [`OtsClient::with_characteristics`](crate::OtsClient::with_characteristics)).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Characteristics {
+    /// OTS service
+    pub service: ServiceId,
+    /// OTS Feature
+    pub feature: CharacteristicId,
+    /// Object Action Control Point
+    pub oacp: CharacteristicId,
+    /// Object List Control Point
+    pub olcp: Option<CharacteristicId>,
+    /// Object ID
+    pub id: Option<CharacteristicId>,
+    /// Object Name
+    pub name: CharacteristicId,
+    /// Object Type
+    pub type_: CharacteristicId,
+    /// Object Size
+    pub size: CharacteristicId,
+    /// Object Properties
+    pub properties: CharacteristicId,
+    /// Object First-Created
+    pub first_created: Option<CharacteristicId>,
+    /// Object Last-Modified
+    pub last_modified: Option<CharacteristicId>,
+    /// Object Changed
+    pub object_changed: Option<CharacteristicId>,
+}
+
+impl Characteristics {
+    /// Discover characteristics of service
+    pub async fn discover(session: &BluetoothSession, service_id: &ServiceId) -> Result<Self> {
+        let chrs = session.get_characteristics(service_id).await?;
+        trace!("Characteristics: {chrs:#?}");
+
+        let optional = |uuid| {
+            chrs.iter()
+                .find(|chr| chr.uuid == uuid)
+                .map(|chr| chr.id.clone())
+        };
+        let mandatory = |uuid| optional(uuid).ok_or(BluetoothError::UuidNotFound { uuid });
+
+        let chrs = Self {
+            service: service_id.clone(),
+            feature: mandatory(ids::characteristic::ots_feature)?,
+            oacp: mandatory(ids::characteristic::object_action_control_point)?,
+            olcp: optional(ids::characteristic::object_list_control_point),
+            id: optional(ids::characteristic::object_id),
+            name: mandatory(ids::characteristic::object_name)?,
+            type_: mandatory(ids::characteristic::object_type)?,
+            size: mandatory(ids::characteristic::object_size)?,
+            properties: mandatory(ids::characteristic::object_properties)?,
+            first_created: optional(ids::characteristic::object_first_created),
+            last_modified: optional(ids::characteristic::object_last_modified),
+            object_changed: optional(ids::characteristic::object_changed),
+        };
+        debug!("OTS Chars: {chrs:#?}");
+
+        Ok(chrs)
+    }
+
+    /// Get characteristic identifier by UUID
+    pub fn get(&self, uuid: &Uuid) -> Option<&CharacteristicId> {
+        use ids::characteristic as chr;
+
+        [
+            (chr::ots_feature, Some(&self.feature)),
+            (chr::object_action_control_point, Some(&self.oacp)),
+            (chr::object_list_control_point, self.olcp.as_ref()),
+            (chr::object_id, self.id.as_ref()),
+            (chr::object_name, Some(&self.name)),
+            (chr::object_type, Some(&self.type_)),
+            (chr::object_size, Some(&self.size)),
+            (chr::object_properties, Some(&self.properties)),
+            (chr::object_first_created, self.first_created.as_ref()),
+            (chr::object_last_modified, self.last_modified.as_ref()),
+            (chr::object_changed, self.object_changed.as_ref()),
+        ]
+        .into_iter()
+        .find(|(id, _)| id == uuid)
+        .and_then(|(_, chr)| chr)
+    }
+
+    fn get_or_err(&self, uuid: &Uuid) -> Result<&CharacteristicId> {
+        self.get(uuid).ok_or(Error::NotSupported)
+    }
+}
+
+/// BlueZ GATT transport
+///
+/// Uses D-Bus session to access characteristics and L2CAP sockets for object data.
+pub struct BluezGatt {
+    pub(crate) session: BluetoothSession,
+    pub(crate) adapter_id: AdapterId,
+    pub(crate) device_id: DeviceId,
+    pub(crate) adapter_addr: SocketAddr,
+    pub(crate) device_addr: SocketAddr,
+    pub(crate) sock_security: Option<Security>,
+    pub(crate) chrs: Characteristics,
+}
+
+impl core::fmt::Debug for BluezGatt {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_struct("BluezGatt")
+            .field("device", &self.device_id)
+            .finish()
+    }
+}
+
+impl AsRef<BluetoothSession> for BluezGatt {
+    fn as_ref(&self) -> &BluetoothSession {
+        &self.session
+    }
+}
+
+impl AsRef<AdapterId> for BluezGatt {
+    fn as_ref(&self) -> &AdapterId {
+        &self.adapter_id
+    }
+}
+
+impl AsRef<DeviceId> for BluezGatt {
+    fn as_ref(&self) -> &DeviceId {
+        &self.device_id
+    }
+}
+
+impl AsRef<ServiceId> for BluezGatt {
+    fn as_ref(&self) -> &ServiceId {
+        &self.chrs.service
+    }
+}
+
+impl AsRef<Characteristics> for BluezGatt {
+    fn as_ref(&self) -> &Characteristics {
+        &self.chrs
+    }
+}
+
+impl Gatt for BluezGatt {
+    fn has_characteristic(&self, uuid: &Uuid) -> bool {
+        self.chrs.get(uuid).is_some()
+    }
+
+    async fn read_characteristic(&self, uuid: &Uuid) -> Result<Vec<u8>> {
+        let chr = self.chrs.get_or_err(uuid)?;
+        Ok(self.session.read_characteristic_value(chr).await?)
+    }
+
+    async fn write_characteristic(&self, uuid: &Uuid, value: Vec<u8>) -> Result<()> {
+        let chr = self.chrs.get_or_err(uuid)?;
+        Ok(self.session.write_characteristic_value(chr, value).await?)
+    }
+
+    async fn subscribe(&self, uuid: &Uuid) -> Result<BoxStream<'static, Vec<u8>>> {
+        let chr = self.chrs.get_or_err(uuid)?.clone();
+
+        self.session.start_notify(&chr).await?;
+
+        Ok(self
+            .session
+            .device_event_stream(&self.device_id)
+            .await?
+            .filter_map(move |event| {
+                trace!("Evt: {event:?}");
+                core::future::ready(
+                    if let BluetoothEvent::Characteristic {
+                        id,
+                        event: CharacteristicEvent::Value { value },
+                    } = event
+                    {
+                        if id == chr {
+                            Some(value)
+                        } else {
+                            None
+                        }
+                    } else {
+                        None
+                    },
+                )
+            })
+            .boxed())
+    }
+
+    async fn unsubscribe(&self, uuid: &Uuid) -> Result<()> {
+        let chr = self.chrs.get_or_err(uuid)?;
+        Ok(self.session.stop_notify(chr).await?)
+    }
+
+    async fn open_channel(&self) -> Result<L2capStream> {
+        let socket = L2capSocket::new(SocketType::SEQPACKET)?;
+        if let Some(security) = self.sock_security.as_ref() {
+            socket.set_security(security)?;
+        }
+        debug!("Bind to {:?}", self.adapter_addr);
+        socket.bind(&self.adapter_addr)?;
+        debug!("Connect to {:?}", self.device_addr);
+        let stream = rt::timeout(CONNECT_TIMEOUT, socket.connect(&self.device_addr))
+            .await
+            .map_err(|_| Error::Timeout)??;
+        debug!(
+            "Local/Peer Address: {:?}/{:?}",
+            stream.local_addr()?,
+            stream.peer_addr()?
+        );
+        debug!(
+            "Send/Recv MTU: {}/{}",
+            stream.max_send_sdu(),
+            stream.max_recv_sdu()
+        );
+        debug!("Security: {:?}", stream.security()?);
+        Ok(stream)
+    }
+}
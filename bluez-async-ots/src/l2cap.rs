@@ -1,14 +1,16 @@
+use crate::rt::AsyncFd;
 use core::{
     pin::Pin,
     task::{Context, Poll},
 };
-use futures_util::ready;
+use futures_util::{ready, Sink, Stream};
 use ots_core::l2cap::{self, L2capSockAddr};
 use std::{
-    io::Result,
-    os::fd::{AsRawFd, RawFd},
+    io::{Error, ErrorKind, Result},
+    os::fd::{AsFd, AsRawFd, BorrowedFd, RawFd},
 };
 
+/// Asynchronous L2CAP socket
 pub struct L2capSocket {
     inner: l2cap::L2capSocket,
 }
@@ -28,14 +30,16 @@ impl core::ops::DerefMut for L2capSocket {
 }
 
 impl L2capSocket {
+    /// Create new L2CAP socket
     pub fn new(type_: l2cap::SocketType) -> Result<Self> {
         l2cap::L2capSocket::new(type_).map(|inner| Self { inner })
     }
 
+    /// Connect socket to peer
     pub async fn connect(self, sockaddr: &L2capSockAddr) -> Result<L2capStream> {
         self.inner.connect(sockaddr)?;
         self.inner.set_nonblocking(true)?;
-        let inner = tokio::io::unix::AsyncFd::new(self)?;
+        let inner = AsyncFd::new(self)?;
 
         // Once we've connected, wait for the stream to be writable as
         // that's when the actual connection has been initiated. Once we're
@@ -43,13 +47,27 @@ impl L2capSocket {
         // actually hit an error or not.
         //
         // If all that succeeded then we ship everything on up.
-        let _ = core::future::poll_fn(|cx| inner.poll_write_ready(cx)).await?;
+        core::future::poll_fn(|cx| inner.poll_writable(cx)).await?;
 
         if let Some(e) = inner.get_ref().inner.take_error()? {
             return Err(e);
         }
 
-        Ok(L2capStream { inner })
+        let send_mtu = inner.get_ref().send_mtu()?;
+        let recv_mtu = inner.get_ref().recv_mtu()?;
+
+        Ok(L2capStream {
+            inner,
+            send_mtu,
+            recv_mtu,
+            pending: None,
+        })
+    }
+}
+
+impl From<l2cap::L2capSocket> for L2capSocket {
+    fn from(inner: l2cap::L2capSocket) -> Self {
+        Self { inner }
     }
 }
 
@@ -59,8 +77,24 @@ impl AsRawFd for L2capSocket {
     }
 }
 
+impl AsFd for L2capSocket {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.inner.as_fd()
+    }
+}
+
+/// Connected L2CAP stream
+///
+/// Besides of byte-stream `AsyncRead` and `AsyncWrite` (from both
+/// [`futures::io`](futures_util::io) and [`tokio::io`](https://docs.rs/tokio/latest/tokio/io/))
+/// it provides packet-level access which preserves SDU boundaries:
+/// [`send_sdu`](Self::send_sdu), [`recv_sdu`](Self::recv_sdu) and
+/// [`Sink`]/[`Stream`] of SDUs.
 pub struct L2capStream {
-    inner: tokio::io::unix::AsyncFd<L2capSocket>,
+    inner: AsyncFd<L2capSocket>,
+    send_mtu: usize,
+    recv_mtu: usize,
+    pending: Option<Vec<u8>>,
 }
 
 impl core::ops::Deref for L2capStream {
@@ -70,49 +104,169 @@ impl core::ops::Deref for L2capStream {
     }
 }
 
+impl L2capStream {
+    /// Create stream using already connected socket
+    ///
+    /// Unlike [`L2capSocket::connect`] the MTUs are not queried from socket,
+    /// so it can be used with socket pairs which emulates L2CAP channels.
+    pub fn with_mtu(socket: L2capSocket, send_mtu: usize, recv_mtu: usize) -> Result<Self> {
+        socket.inner.set_nonblocking(true)?;
+        let inner = AsyncFd::new(socket)?;
+
+        Ok(Self {
+            inner,
+            send_mtu,
+            recv_mtu,
+            pending: None,
+        })
+    }
+
+    /// Get maximum size of sent SDU
+    ///
+    /// Unlike send MTU socket option it returns value cached when stream was created.
+    pub fn max_send_sdu(&self) -> usize {
+        self.send_mtu
+    }
+
+    /// Get maximum size of received SDU
+    ///
+    /// Unlike receive MTU socket option it returns value cached when stream was created.
+    pub fn max_recv_sdu(&self) -> usize {
+        self.recv_mtu
+    }
+
+    /// Convert into blocking socket
+    ///
+    /// Socket is deregistered from runtime and switched back to blocking mode.
+    pub fn into_blocking(self) -> Result<l2cap::L2capSocket> {
+        let socket = self.inner.into_inner()?.inner;
+        socket.set_nonblocking(false)?;
+        Ok(socket)
+    }
+
+    /// Try send single SDU
+    ///
+    /// SDU which exceeds send MTU will be rejected with [`ErrorKind::InvalidInput`].
+    pub fn poll_send_sdu(&self, cx: &mut Context<'_>, sdu: &[u8]) -> Poll<Result<()>> {
+        if sdu.len() > self.send_mtu {
+            return Poll::Ready(Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("SDU exceeds send MTU ({} > {})", sdu.len(), self.send_mtu),
+            )));
+        }
+
+        let len = ready!(self.poll_send(cx, sdu))?;
+
+        Poll::Ready(if len < sdu.len() {
+            Err(Error::new(ErrorKind::WriteZero, "SDU partially sent"))
+        } else {
+            Ok(())
+        })
+    }
+
+    /// Try receive single SDU into buffer
+    ///
+    /// Returns the size of received SDU. Zero means that peer closed connection.
+    /// Buffer should be at least receive MTU long to avoid SDU truncation.
+    pub fn poll_recv_sdu(&self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<Result<usize>> {
+        self.inner.poll_read_io(cx, |inner| {
+            inner.inner.recv(unsafe { &mut *(buf as *mut _ as *mut _) })
+        })
+    }
+
+    /// Send single SDU
+    pub async fn send_sdu(&self, sdu: &[u8]) -> Result<()> {
+        core::future::poll_fn(|cx| self.poll_send_sdu(cx, sdu)).await
+    }
+
+    /// Receive single SDU
+    ///
+    /// Returns empty SDU when peer closed connection.
+    pub async fn recv_sdu(&self) -> Result<Vec<u8>> {
+        let mut sdu = vec![0; self.recv_mtu];
+        let len = core::future::poll_fn(|cx| self.poll_recv_sdu(cx, &mut sdu)).await?;
+        sdu.truncate(len);
+        Ok(sdu)
+    }
+
+    fn poll_send(&self, cx: &mut Context<'_>, buf: &[u8]) -> Poll<Result<usize>> {
+        self.inner.poll_write_io(cx, |inner| inner.inner.send(buf))
+    }
+
+    fn poll_send_pending(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        if let Some(sdu) = &self.pending {
+            let result = ready!(self.poll_send_sdu(cx, sdu));
+            self.pending = None;
+            result?;
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_write_data(&self, cx: &mut Context<'_>, buf: &[u8]) -> Poll<Result<usize>> {
+        // single send cannot exceed MTU so split data to SDUs
+        let len = buf.len().min(self.send_mtu);
+        self.poll_send(cx, &buf[..len])
+    }
+
+    fn shutdown(&self) -> Result<()> {
+        self.inner
+            .get_ref()
+            .inner
+            .shutdown(std::net::Shutdown::Write)
+    }
+}
+
+impl futures_util::io::AsyncRead for L2capStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<std::io::Result<usize>> {
+        self.poll_recv_sdu(cx, buf)
+    }
+}
+
+impl futures_util::io::AsyncWrite for L2capStream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        self.poll_write_data(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        // tcp flush is a no-op
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Poll::Ready(self.shutdown())
+    }
+}
+
+#[cfg(feature = "tokio")]
 impl tokio::io::AsyncRead for L2capStream {
     fn poll_read(
-        mut self: Pin<&mut Self>,
+        self: Pin<&mut Self>,
         cx: &mut Context<'_>,
         buf: &mut tokio::io::ReadBuf<'_>,
     ) -> Poll<std::io::Result<()>> {
-        use std::io::Read;
-
-        loop {
-            let mut guard = ready!(self.inner.poll_read_ready_mut(cx)?);
-
-            let unfilled = buf.initialize_unfilled();
-            match guard.try_io(|inner| {
-                inner
-                    .get_mut()
-                    .inner
-                    .read(unsafe { &mut *(unfilled as *mut _ as *mut _) })
-            }) {
-                Ok(Ok(len)) => {
-                    buf.advance(len);
-                    return Poll::Ready(Ok(()));
-                }
-                Ok(Err(err)) => return Poll::Ready(Err(err)),
-                Err(_would_block) => continue,
-            }
-        }
+        let unfilled = buf.initialize_unfilled();
+        let len = ready!(self.poll_recv_sdu(cx, unfilled))?;
+        buf.advance(len);
+        Poll::Ready(Ok(()))
     }
 }
 
+#[cfg(feature = "tokio")]
 impl tokio::io::AsyncWrite for L2capStream {
     fn poll_write(
         self: Pin<&mut Self>,
         cx: &mut Context<'_>,
         buf: &[u8],
     ) -> Poll<std::io::Result<usize>> {
-        loop {
-            let mut guard = ready!(self.inner.poll_write_ready(cx))?;
-
-            match guard.try_io(|inner| inner.get_ref().inner.send(buf)) {
-                Ok(result) => return Poll::Ready(result),
-                Err(_would_block) => continue,
-            }
-        }
+        self.poll_write_data(cx, buf)
     }
 
     fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
@@ -121,10 +275,47 @@ impl tokio::io::AsyncWrite for L2capStream {
     }
 
     fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
-        self.inner
-            .get_ref()
-            .inner
-            .shutdown(std::net::Shutdown::Write)?;
-        Poll::Ready(Ok(()))
+        Poll::Ready(self.shutdown())
+    }
+}
+
+impl Stream for L2capStream {
+    type Item = Result<Vec<u8>>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let mut sdu = vec![0; this.recv_mtu];
+        Poll::Ready(match ready!(this.poll_recv_sdu(cx, &mut sdu)) {
+            // zero length means end of stream
+            Ok(0) => None,
+            Ok(len) => {
+                sdu.truncate(len);
+                Some(Ok(sdu))
+            }
+            Err(error) => Some(Err(error)),
+        })
+    }
+}
+
+impl Sink<Vec<u8>> for L2capStream {
+    type Error = Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.get_mut().poll_send_pending(cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, sdu: Vec<u8>) -> Result<()> {
+        self.get_mut().pending = Some(sdu);
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.get_mut().poll_send_pending(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        let this = self.get_mut();
+        ready!(this.poll_send_pending(cx))?;
+        Poll::Ready(this.shutdown())
     }
 }
@@ -22,29 +22,40 @@ mod log_stub {
     }
 }
 
+mod gatt;
 mod l2cap;
+mod rt;
+
+#[cfg(feature = "blocking")]
+pub mod blocking;
 
 use ots_core::{
     ids,
-    l2cap::{AddressType, L2capSockAddr as SocketAddr, Psm, SocketType},
+    l2cap::{AddressType, L2capSockAddr as SocketAddr, Psm},
     types, Sizes,
 };
 
 use bluez_async::{
-    AdapterId, BluetoothError, BluetoothEvent, BluetoothSession, CharacteristicEvent,
-    CharacteristicId, DeviceId,
+    AdapterId, AdapterInfo, BluetoothError, BluetoothSession, DeviceId, DeviceInfo, ServiceId,
+    ServiceInfo,
+};
+use futures_util::{
+    pin_mut,
+    stream::{BoxStream, StreamExt},
 };
-use futures_util::{pin_mut, stream::StreamExt};
 use uuid::Uuid;
 
-use l2cap::{L2capSocket as Socket, L2capStream as Stream};
+use l2cap::L2capStream as Stream;
 use types::{ActionReq, ActionRes, ListReq, ListRes, Ule48};
 
+pub use gatt::{BluezGatt, Characteristics, Gatt};
+pub use l2cap::{L2capSocket, L2capStream};
 pub use ots_core::{
+    execute::{ExecuteCommand, ExecuteDecode, ExecuteEncode},
     l2cap::{Security, SecurityLevel},
     types::{
-        ActionFeature, ActionRc, DateTime, DirEntries, ListFeature, ListRc, Metadata, Property,
-        SortOrder, WriteMode,
+        ActionFeature, ActionRc, ChangeFlag, DateTime, DirEntries, ListFeature, ListRc, Metadata,
+        ObjectChanged, Property, SortOrder, WriteMode,
     },
     Error as CoreError,
 };
@@ -99,6 +110,9 @@ impl From<std::string::FromUtf8Error> for Error {
     }
 }
 
+/// Timeout of object channel connection
+const CONNECT_TIMEOUT: core::time::Duration = core::time::Duration::from_secs(5);
+
 /// Object Transfer Service (OTS) client configuration
 #[derive(Debug, Clone, Default)]
 pub struct ClientConfig {
@@ -112,66 +126,90 @@ pub struct ClientConfig {
 }
 
 /// Object Transfer Service (OTS) client
-pub struct OtsClient {
-    session: BluetoothSession,
-    adapter_id: AdapterId,
-    device_id: DeviceId,
-    adapter_addr: SocketAddr,
-    device_addr: SocketAddr,
-    sock_security: Option<Security>,
+///
+/// Client is generic over [`Gatt`] transport which defaults to [`BluezGatt`].
+pub struct OtsClient<G: Gatt = BluezGatt> {
+    gatt: G,
     action_features: ActionFeature,
     list_features: ListFeature,
-    oacp_chr: CharacteristicId,
-    olcp_chr: Option<CharacteristicId>,
-    id_chr: Option<CharacteristicId>,
-    name_chr: CharacteristicId,
-    type_chr: CharacteristicId,
-    size_chr: CharacteristicId,
-    prop_chr: CharacteristicId,
-    crt_chr: Option<CharacteristicId>,
-    mod_chr: Option<CharacteristicId>,
 }
 
 impl AsRef<BluetoothSession> for OtsClient {
     fn as_ref(&self) -> &BluetoothSession {
-        &self.session
+        self.gatt.as_ref()
     }
 }
 
 impl AsRef<AdapterId> for OtsClient {
     fn as_ref(&self) -> &AdapterId {
-        &self.adapter_id
+        self.gatt.as_ref()
     }
 }
 
 impl AsRef<DeviceId> for OtsClient {
     fn as_ref(&self) -> &DeviceId {
-        &self.device_id
+        self.gatt.as_ref()
+    }
+}
+
+impl AsRef<ServiceId> for OtsClient {
+    fn as_ref(&self) -> &ServiceId {
+        self.gatt.as_ref()
     }
 }
 
-impl AsRef<ActionFeature> for OtsClient {
+impl AsRef<Characteristics> for OtsClient {
+    fn as_ref(&self) -> &Characteristics {
+        self.gatt.as_ref()
+    }
+}
+
+impl<G: Gatt> AsRef<ActionFeature> for OtsClient<G> {
     fn as_ref(&self) -> &ActionFeature {
         &self.action_features
     }
 }
 
-impl AsRef<ListFeature> for OtsClient {
+impl<G: Gatt> AsRef<ListFeature> for OtsClient<G> {
     fn as_ref(&self) -> &ListFeature {
         &self.list_features
     }
 }
 
-impl core::fmt::Debug for OtsClient {
+impl<G: Gatt + core::fmt::Debug> core::fmt::Debug for OtsClient<G> {
     fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         f.debug_struct("OtsClient")
-            .field("device", &self.device_id)
+            .field("gatt", &self.gatt)
             .finish()
     }
 }
 
 impl OtsClient {
+    /// Get all OTS service instances of device
+    ///
+    /// Besides primary services the result includes secondary OTS instances
+    /// which are included by other services (like MCS or ESL).
+    /// Use [`with_service`](Self::with_service) to create client for specific instance.
+    pub async fn services(
+        session: &BluetoothSession,
+        device_id: &DeviceId,
+    ) -> Result<Vec<ServiceInfo>> {
+        let services = session
+            .get_services(device_id)
+            .await?
+            .into_iter()
+            .filter(|service| service.uuid == ids::service::object_transfer)
+            .collect::<Vec<_>>();
+        debug!("Services: {services:#?}");
+        Ok(services)
+    }
+
     /// Create new client instance
+    ///
+    /// Finds OTS service of device, discovers its characteristics and
+    /// gets addresses of device and adapter.
+    ///
+    /// When device has several OTS instances the first found will be used.
     pub async fn new(
         session: &BluetoothSession,
         device_id: &DeviceId,
@@ -182,125 +220,74 @@ impl OtsClient {
             .await?;
         debug!("Service: {ots_srv:#?}");
 
-        let _ots_chrs = session.get_characteristics(&ots_srv.id).await?;
-        trace!("Characteristics: {_ots_chrs:#?}");
-
-        let ots_feature_chr = session
-            .get_characteristic_by_uuid(&ots_srv.id, ids::characteristic::ots_feature)
-            .await?;
-        debug!("Feature Char: {ots_feature_chr:#?}");
+        Self::with_service(session, &ots_srv.id, config).await
+    }
 
-        let ots_feature_val = session
-            .read_characteristic_value(&ots_feature_chr.id)
-            .await?;
-        trace!("Feature Raw: {ots_feature_val:?}");
+    /// Create new client instance using known service identifier
+    ///
+    /// Discovers characteristics of service and gets addresses of device and adapter.
+    pub async fn with_service(
+        session: &BluetoothSession,
+        service_id: &ServiceId,
+        config: &ClientConfig,
+    ) -> Result<Self> {
+        let chrs = Characteristics::discover(session, service_id).await?;
 
-        let action_features = (&ots_feature_val[0..4]).try_into()?;
-        let list_features = (&ots_feature_val[4..8]).try_into()?;
-        info!("OTS Feature: {action_features:?} {list_features:?}");
+        Self::with_characteristics(session, chrs, config).await
+    }
 
-        let oacp_chr = session
-            .get_characteristic_by_uuid(
-                &ots_srv.id,
-                ids::characteristic::object_action_control_point,
-            )
+    /// Create new client instance using known adapter and device info
+    ///
+    /// Finds OTS service of device and discovers its characteristics.
+    pub async fn with_info(
+        session: &BluetoothSession,
+        adapter_info: &AdapterInfo,
+        device_info: &DeviceInfo,
+        config: &ClientConfig,
+    ) -> Result<Self> {
+        let ots_srv = session
+            .get_service_by_uuid(&device_info.id, ids::service::object_transfer)
             .await?;
-        trace!("OACP Char: {oacp_chr:#?}");
-        let oacp_chr = oacp_chr.id;
-
-        let olcp_chr = session
-            .get_characteristic_by_uuid(&ots_srv.id, ids::characteristic::object_list_control_point)
-            .await
-            .map(Some)
-            .or_else(|error| {
-                if matches!(error, BluetoothError::UuidNotFound { .. }) {
-                    Ok(None)
-                } else {
-                    Err(error)
-                }
-            })?;
-        trace!("OLCP Char: {olcp_chr:#?}");
-        let olcp_chr = olcp_chr.map(|chr| chr.id);
-
-        let id_chr = session
-            .get_characteristic_by_uuid(&ots_srv.id, ids::characteristic::object_id)
-            .await
-            .map(Some)
-            .or_else(|error| {
-                if matches!(error, BluetoothError::UuidNotFound { .. }) {
-                    Ok(None)
-                } else {
-                    Err(error)
-                }
-            })?;
-        trace!("Id Char: {id_chr:#?}");
-        let id_chr = id_chr.map(|chr| chr.id);
+        debug!("Service: {ots_srv:#?}");
 
-        let name_chr = session
-            .get_characteristic_by_uuid(&ots_srv.id, ids::characteristic::object_name)
-            .await?;
-        trace!("Name Char: {name_chr:#?}");
-        let name_chr = name_chr.id;
+        let chrs = Characteristics::discover(session, &ots_srv.id).await?;
 
-        let type_chr = session
-            .get_characteristic_by_uuid(&ots_srv.id, ids::characteristic::object_type)
-            .await?;
-        trace!("Type Char: {type_chr:#?}");
-        let type_chr = type_chr.id;
+        Self::with_parts(session, chrs, Some(adapter_info), device_info, config).await
+    }
 
-        let size_chr = session
-            .get_characteristic_by_uuid(&ots_srv.id, ids::characteristic::object_size)
-            .await?;
-        trace!("Size Char: {size_chr:#?}");
-        let size_chr = size_chr.id;
+    /// Create new client instance using already discovered characteristics
+    ///
+    /// Use it to connect again without full GATT discovery
+    /// (see [`characteristics`](Self::characteristics)).
+    pub async fn with_characteristics(
+        session: &BluetoothSession,
+        chrs: Characteristics,
+        config: &ClientConfig,
+    ) -> Result<Self> {
+        let device_id = chrs.service.device();
 
-        let prop_chr = session
-            .get_characteristic_by_uuid(&ots_srv.id, ids::characteristic::object_properties)
-            .await?;
-        trace!("Prop Char: {prop_chr:#?}");
-        let prop_chr = prop_chr.id;
+        let device_info = session.get_device_info(&device_id).await?;
+        trace!("Device: {device_info:#?}");
 
-        let crt_chr = session
-            .get_characteristic_by_uuid(&ots_srv.id, ids::characteristic::object_first_created)
-            .await
-            .map(Some)
-            .or_else(|error| {
-                if matches!(error, BluetoothError::UuidNotFound { .. }) {
-                    Ok(None)
-                } else {
-                    Err(error)
-                }
-            })?;
-        trace!("Crt Char: {crt_chr:#?}");
-        let crt_chr = crt_chr.map(|chr| chr.id);
+        // adapter address is not used in privileged mode
+        let adapter_info = if config.privileged {
+            None
+        } else {
+            let adapter_info = session.get_adapter_info(&device_id.adapter()).await?;
+            trace!("Adapter: {adapter_info:#?}");
+            Some(adapter_info)
+        };
 
-        let mod_chr = session
-            .get_characteristic_by_uuid(&ots_srv.id, ids::characteristic::object_last_modified)
-            .await
-            .map(Some)
-            .or_else(|error| {
-                if matches!(error, BluetoothError::UuidNotFound { .. }) {
-                    Ok(None)
-                } else {
-                    Err(error)
-                }
-            })?;
-        trace!("Mod Char: {mod_chr:#?}");
-        let mod_chr = mod_chr.map(|chr| chr.id);
-
-        let mut adapter_and_device_info = None;
-        for adapter_info in session.get_adapters().await? {
-            if let Some(device_info) = session
-                .get_devices_on_adapter(&adapter_info.id)
-                .await?
-                .into_iter()
-                .find(|device_info| &device_info.id == device_id)
-            {
-                adapter_and_device_info = Some((adapter_info, device_info));
-            }
-        }
-        let (adapter_info, device_info) = adapter_and_device_info.ok_or_else(|| Error::NotFound)?;
+        Self::with_parts(session, chrs, adapter_info.as_ref(), &device_info, config).await
+    }
 
+    async fn with_parts(
+        session: &BluetoothSession,
+        chrs: Characteristics,
+        adapter_info: Option<&AdapterInfo>,
+        device_info: &DeviceInfo,
+        config: &ClientConfig,
+    ) -> Result<Self> {
         fn socketaddr_new(
             mac: bluez_async::MacAddress,
             type_: bluez_async::AddressType,
@@ -315,18 +302,17 @@ impl OtsClient {
             SocketAddr::new(mac.into(), type_, psm)
         }
 
-        let adapter_addr = if config.privileged {
-            socketaddr_new(
-                [0, 0, 0, 0, 0, 0].into(),
-                bluez_async::AddressType::Random,
-                Psm::L2CapLeCidOts,
-            )
-        } else {
-            socketaddr_new(
+        let adapter_addr = match adapter_info {
+            Some(adapter_info) if !config.privileged => socketaddr_new(
                 adapter_info.mac_address,
                 adapter_info.address_type,
                 Psm::L2CapLeDynStart,
-            )
+            ),
+            _ => socketaddr_new(
+                [0, 0, 0, 0, 0, 0].into(),
+                bluez_async::AddressType::Random,
+                Psm::L2CapLeCidOts,
+            ),
         };
 
         let device_addr = socketaddr_new(
@@ -335,27 +321,62 @@ impl OtsClient {
             Psm::L2CapLeCidOts,
         );
 
-        Ok(Self {
+        Self::with_gatt(BluezGatt {
             session: session.clone(),
-            adapter_id: adapter_info.id,
-            device_id: device_id.clone(),
+            adapter_id: device_info.id.adapter(),
+            device_id: device_info.id.clone(),
             adapter_addr,
             device_addr,
             sock_security: config.security,
+            chrs,
+        })
+        .await
+    }
+
+    /// Get discovered characteristics
+    pub fn characteristics(&self) -> &Characteristics {
+        &self.gatt.chrs
+    }
+}
+
+impl<G: Gatt> OtsClient<G> {
+    /// Create new client instance using custom GATT transport
+    ///
+    /// Reads OTS features using transport.
+    pub async fn with_gatt(gatt: G) -> Result<Self> {
+        let ots_feature_val = gatt
+            .read_characteristic(&ids::characteristic::ots_feature)
+            .await?;
+        trace!("Feature Raw: {ots_feature_val:?}");
+
+        CoreError::check_len(ots_feature_val.len(), 8)?;
+        let action_features = (&ots_feature_val[0..4]).try_into()?;
+        let list_features = (&ots_feature_val[4..8]).try_into()?;
+        info!("OTS Feature: {action_features:?} {list_features:?}");
+
+        Ok(Self {
+            gatt,
             action_features,
             list_features,
-            oacp_chr,
-            olcp_chr,
-            id_chr,
-            name_chr,
-            type_chr,
-            size_chr,
-            prop_chr,
-            crt_chr,
-            mod_chr,
         })
     }
 
+    /// Get GATT transport
+    pub fn gatt(&self) -> &G {
+        &self.gatt
+    }
+
+    /// Convert GATT transport keeping features
+    ///
+    /// Useful to wrap transport, e.g. to select it at runtime.
+    pub fn map_gatt<T: Gatt>(self, map: impl FnOnce(G) -> T) -> OtsClient<T> {
+        OtsClient {
+            gatt: map(self.gatt),
+            action_features: self.action_features,
+            list_features: self.list_features,
+        }
+    }
+
     /// Get object action feature flags
     pub fn action_features(&self) -> &ActionFeature {
         &self.action_features
@@ -368,8 +389,14 @@ impl OtsClient {
 
     /// Get current object identifier
     pub async fn id(&self) -> Result<Option<u64>> {
-        if let Some(chr) = &self.id_chr {
-            let raw = self.session.read_characteristic_value(chr).await?;
+        if self
+            .gatt
+            .has_characteristic(&ids::characteristic::object_id)
+        {
+            let raw = self
+                .gatt
+                .read_characteristic(&ids::characteristic::object_id)
+                .await?;
             Ok(Some(Ule48::try_from(&raw[..])?.into()))
         } else {
             Ok(None)
@@ -379,17 +406,24 @@ impl OtsClient {
     /// Get current object name
     pub async fn name(&self) -> Result<String> {
         Ok(String::from_utf8(
-            self.session
-                .read_characteristic_value(&self.name_chr)
+            self.gatt
+                .read_characteristic(&ids::characteristic::object_name)
                 .await?,
         )?)
     }
 
+    /// Set current object name
+    pub async fn set_name(&self, name: &str) -> Result<()> {
+        self.gatt
+            .write_characteristic(&ids::characteristic::object_name, name.as_bytes().into())
+            .await
+    }
+
     /// Get current object type
     pub async fn type_(&self) -> Result<Uuid> {
         let raw = self
-            .session
-            .read_characteristic_value(&self.type_chr)
+            .gatt
+            .read_characteristic(&ids::characteristic::object_type)
             .await?;
         Ok(types::uuid_from_raw(&raw[..])?)
     }
@@ -397,41 +431,87 @@ impl OtsClient {
     /// Get sizes of current object
     pub async fn size(&self) -> Result<Sizes> {
         let raw = self
-            .session
-            .read_characteristic_value(&self.size_chr)
+            .gatt
+            .read_characteristic(&ids::characteristic::object_size)
             .await?;
         Ok(raw[..].try_into()?)
     }
 
     /// Get first created time for selected object
     pub async fn first_created(&self) -> Result<Option<DateTime>> {
-        Ok(if let Some(chr) = &self.crt_chr {
-            let raw = self.session.read_characteristic_value(chr).await?;
-            DateTime::try_from(raw.as_slice()).map(Some)?
-        } else {
-            None
-        })
+        Ok(
+            if self
+                .gatt
+                .has_characteristic(&ids::characteristic::object_first_created)
+            {
+                let raw = self
+                    .gatt
+                    .read_characteristic(&ids::characteristic::object_first_created)
+                    .await?;
+                DateTime::try_from(raw.as_slice()).map(Some)?
+            } else {
+                None
+            },
+        )
+    }
+
+    /// Set first created time for selected object
+    pub async fn set_first_created(&self, time: &DateTime) -> Result<()> {
+        self.gatt
+            .write_characteristic(
+                &ids::characteristic::object_first_created,
+                <[u8; 7]>::from(time).into(),
+            )
+            .await
     }
 
     /// Get last modified time for selected object
     pub async fn last_modified(&self) -> Result<Option<DateTime>> {
-        Ok(if let Some(chr) = &self.mod_chr {
-            let raw = self.session.read_characteristic_value(chr).await?;
-            DateTime::try_from(raw.as_slice()).map(Some)?
-        } else {
-            None
-        })
+        Ok(
+            if self
+                .gatt
+                .has_characteristic(&ids::characteristic::object_last_modified)
+            {
+                let raw = self
+                    .gatt
+                    .read_characteristic(&ids::characteristic::object_last_modified)
+                    .await?;
+                DateTime::try_from(raw.as_slice()).map(Some)?
+            } else {
+                None
+            },
+        )
+    }
+
+    /// Set last modified time for selected object
+    pub async fn set_last_modified(&self, time: &DateTime) -> Result<()> {
+        self.gatt
+            .write_characteristic(
+                &ids::characteristic::object_last_modified,
+                <[u8; 7]>::from(time).into(),
+            )
+            .await
     }
 
     /// Get current object properties
     pub async fn properties(&self) -> Result<Property> {
         let raw = self
-            .session
-            .read_characteristic_value(&self.prop_chr)
+            .gatt
+            .read_characteristic(&ids::characteristic::object_properties)
             .await?;
         Ok(Property::try_from(&raw[..])?)
     }
 
+    /// Set current object properties
+    pub async fn set_properties(&self, properties: Property) -> Result<()> {
+        self.gatt
+            .write_characteristic(
+                &ids::characteristic::object_properties,
+                <[u8; 4]>::from(properties).into(),
+            )
+            .await
+    }
+
     /// Get current object metadata
     pub async fn metadata(&self) -> Result<Metadata> {
         let id = self.id().await?;
@@ -491,37 +571,53 @@ impl OtsClient {
         }
     }
 
-    async fn socket(&self) -> Result<Stream> {
-        let socket = Socket::new(SocketType::SEQPACKET)?;
-        if let Some(security) = self.sock_security.as_ref() {
-            socket.set_security(security)?;
+    /// Subscribe to Object Changed indications
+    ///
+    /// Stream yields changes of objects made by server or other clients.
+    /// Indications which cannot be decoded are skipped.
+    pub async fn subscribe_changes(&self) -> Result<BoxStream<'static, ObjectChanged>> {
+        if !self
+            .gatt
+            .has_characteristic(&ids::characteristic::object_changed)
+        {
+            return Err(Error::NotSupported);
         }
-        debug!("Bind to {:?}", self.adapter_addr);
-        socket.bind(&self.adapter_addr)?;
-        debug!("Connect to {:?}", self.device_addr);
-        let stream = tokio::time::timeout(
-            core::time::Duration::from_secs(5),
-            socket.connect(&self.device_addr),
-        )
-        .await
-        .map_err(|_| Error::Timeout)??;
-        debug!(
-            "Local/Peer Address: {:?}/{:?}",
-            stream.local_addr()?,
-            stream.peer_addr()?
-        );
-        debug!(
-            "Send/Recv MTU: {:?}/{}",
-            stream.send_mtu(),
-            stream.recv_mtu()?
-        );
-        debug!("Security: {:?}", stream.security()?);
-        Ok(stream)
+        Ok(self
+            .gatt
+            .subscribe(&ids::characteristic::object_changed)
+            .await?
+            .filter_map(|value| {
+                let changed = ObjectChanged::try_from(value.as_slice());
+                if let Err(_error) = &changed {
+                    debug!("Bad Object Changed value {value:?}: {_error}");
+                }
+                core::future::ready(changed.ok())
+            })
+            .boxed())
+    }
+
+    /// Unsubscribe from Object Changed indications
+    pub async fn unsubscribe_changes(&self) -> Result<()> {
+        self.gatt
+            .unsubscribe(&ids::characteristic::object_changed)
+            .await
+    }
+
+    /// Execute selected object using typed command
+    ///
+    /// Objects which type differs from command object type are refused
+    /// without sending request.
+    pub async fn execute_typed<C: ExecuteCommand>(&self, param: &C::Param) -> Result<C::Response> {
+        if self.type_().await? != C::OBJECT_TYPE {
+            return Err(CoreError::ActionError(ActionRc::UnsupportedType).into());
+        }
+        let response = self.execute(param.encode()).await?;
+        Ok(C::Response::decode(&response)?)
     }
 
     /// Read object data
     pub async fn read(&self, offset: usize, length: Option<usize>) -> Result<Vec<u8>> {
-        use tokio::io::AsyncReadExt;
+        use futures_util::io::AsyncReadExt;
 
         let length = if let Some(length) = length {
             length
@@ -544,12 +640,12 @@ impl OtsClient {
 
     /// Read object data
     pub async fn read_to(&self, offset: usize, buffer: &mut [u8]) -> Result<usize> {
-        use tokio::io::AsyncReadExt;
+        use futures_util::io::AsyncReadExt;
 
         let size = self.size().await?.current;
 
         // length cannot exceeds available length from offset to end
-        let length = buffer.len().min(size - offset);
+        let length = buffer.len().min(size.saturating_sub(offset));
 
         let mut stm = self.read_base(offset, length).await?;
 
@@ -563,29 +659,27 @@ impl OtsClient {
         let size = self.size().await?.current;
 
         // length cannot exceeds available length from offset to end
-        let length = length.unwrap_or(size).min(size - offset);
+        let length = length.unwrap_or(size).min(size.saturating_sub(offset));
 
         self.read_base(offset, length).await
     }
 
     async fn read_base(&self, offset: usize, length: usize) -> Result<Stream> {
-        let stm = self.socket().await?;
+        let stm = self.gatt.open_channel().await?;
 
         self.do_read(offset, length).await?;
 
-        debug!("recv/send mtu: {}/{}", stm.recv_mtu()?, stm.send_mtu()?);
-
         Ok(stm)
     }
 
     /// Write object data
     pub async fn write(&self, offset: usize, buffer: &[u8], mode: WriteMode) -> Result<usize> {
-        use tokio::io::AsyncWriteExt;
+        use futures_util::io::AsyncWriteExt;
 
         let size = self.size().await?.allocated;
 
         // length cannot exceeds available length from offset to end
-        let length = buffer.len().min(size - offset);
+        let length = buffer.len().min(size.saturating_sub(offset));
 
         let mut stm = self.write_base(offset, length, mode).await?;
 
@@ -604,62 +698,46 @@ impl OtsClient {
         let size = self.size().await?.allocated;
 
         // length cannot exceeds available length from offset to end
-        let length = length.unwrap_or(size).min(size - offset);
+        let length = length.unwrap_or(size).min(size.saturating_sub(offset));
 
         self.write_base(offset, length, mode).await
     }
 
     async fn write_base(&self, offset: usize, length: usize, mode: WriteMode) -> Result<Stream> {
-        let stm = self.socket().await?;
+        let stm = self.gatt.open_channel().await?;
 
         self.do_write(offset, length, mode).await?;
 
         Ok(stm)
     }
 
-    #[cfg_attr(feature = "tracing", tracing::instrument)]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     async fn request(
         &self,
-        chr: &CharacteristicId,
+        chr: &Uuid,
         req: impl Into<Vec<u8>> + core::fmt::Debug,
     ) -> Result<Vec<u8>> {
-        self.session.start_notify(chr).await?;
+        let req = req.into();
+        let opcode = req.first().copied();
 
+        // responses to requests of other clients may be indicated too
         let resps = self
-            .session
-            .device_event_stream(&self.device_id)
+            .gatt
+            .subscribe(chr)
             .await?
-            .filter_map(|event| {
-                trace!("Evt: {event:?}");
-                core::future::ready(
-                    if let BluetoothEvent::Characteristic {
-                        id,
-                        event: CharacteristicEvent::Value { value },
-                    } = event
-                    {
-                        if &id == chr {
-                            Some(value)
-                        } else {
-                            None
-                        }
-                    } else {
-                        None
-                    },
-                )
-            })
+            .filter(move |res| core::future::ready(res.get(1).copied() == opcode))
             .take(1)
-            .take_until(tokio::time::sleep(core::time::Duration::from_secs(1)));
+            .take_until(rt::sleep(core::time::Duration::from_secs(1)));
         pin_mut!(resps);
 
-        let req = req.into();
         trace!("Req: {req:?}");
 
-        self.session.write_characteristic_value(chr, req).await?;
+        self.gatt.write_characteristic(chr, req).await?;
 
         let res = resps.next().await.ok_or_else(|| Error::NoResponse)?;
         trace!("Res: {res:?}");
 
-        self.session.stop_notify(chr).await?;
+        self.gatt.unsubscribe(chr).await?;
 
         Ok(res)
     }
@@ -705,17 +783,21 @@ macro_rules! impl_fns {
     };
 
     (# $self:ident . $char_field:ident) => {
-        &$self.$char_field
+        &ids::characteristic::$char_field
     };
 
     (# $self:ident . $char_field:ident: Option) => {
-        $self.$char_field.as_ref().ok_or_else(|| Error::NotSupported)?
+        if $self.gatt.has_characteristic(&ids::characteristic::$char_field) {
+            &ids::characteristic::$char_field
+        } else {
+            return Err(Error::NotSupported);
+        }
     };
 }
 
-impl OtsClient {
+impl<G: Gatt> OtsClient<G> {
     impl_fns! {
-        action_request: ActionReq => ActionRes [oacp_chr, action_features: ActionFeature] {
+        action_request: ActionReq => ActionRes [object_action_control_point, action_features: ActionFeature] {
             /// Create new object
             pub create: Create { size: usize, type_: Uuid } => None [Create],
             /// Delete selected object
@@ -729,7 +811,7 @@ impl OtsClient {
             /// Abort operation
             pub abort: Abort => None [Abort],
         }
-        list_request: ListReq => ListRes [olcp_chr: Option, list_features: ListFeature] {
+        list_request: ListReq => ListRes [object_list_control_point: Option, list_features: ListFeature] {
             /// Select first object in a list
             pub first: First => None,
             /// Select last object in a list
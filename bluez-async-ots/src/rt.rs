@@ -0,0 +1,172 @@
+//! Async runtime abstraction
+//!
+//! The runtime-specific parts (readiness of file descriptors and timers)
+//! are selected at compile time using `tokio` or `async-io` features.
+//! When both features are enabled the `tokio` runtime takes precedence.
+
+use core::{
+    future::Future,
+    task::{Context, Poll},
+    time::Duration,
+};
+use std::io::Result;
+
+#[cfg(not(any(feature = "tokio", feature = "async-io")))]
+compile_error!("Either `tokio` or `async-io` feature should be enabled");
+
+/// Timeout reached
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Elapsed;
+
+#[cfg(feature = "tokio")]
+mod imp {
+    use super::*;
+    use futures_util::ready;
+    use std::os::fd::AsRawFd;
+
+    pub struct AsyncFd<T: AsRawFd> {
+        inner: tokio::io::unix::AsyncFd<T>,
+    }
+
+    impl<T: AsRawFd> AsyncFd<T> {
+        pub fn new(inner: T) -> Result<Self> {
+            tokio::io::unix::AsyncFd::new(inner).map(|inner| Self { inner })
+        }
+
+        pub fn get_ref(&self) -> &T {
+            self.inner.get_ref()
+        }
+
+        pub fn into_inner(self) -> Result<T> {
+            Ok(self.inner.into_inner())
+        }
+
+        pub fn poll_writable(&self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+            ready!(self.inner.poll_write_ready(cx))?.retain_ready();
+            Poll::Ready(Ok(()))
+        }
+
+        pub fn poll_read_io<R>(
+            &self,
+            cx: &mut Context<'_>,
+            mut f: impl FnMut(&T) -> Result<R>,
+        ) -> Poll<Result<R>> {
+            loop {
+                let mut guard = ready!(self.inner.poll_read_ready(cx))?;
+
+                match guard.try_io(|inner| f(inner.get_ref())) {
+                    Ok(result) => return Poll::Ready(result),
+                    Err(_would_block) => continue,
+                }
+            }
+        }
+
+        pub fn poll_write_io<R>(
+            &self,
+            cx: &mut Context<'_>,
+            mut f: impl FnMut(&T) -> Result<R>,
+        ) -> Poll<Result<R>> {
+            loop {
+                let mut guard = ready!(self.inner.poll_write_ready(cx))?;
+
+                match guard.try_io(|inner| f(inner.get_ref())) {
+                    Ok(result) => return Poll::Ready(result),
+                    Err(_would_block) => continue,
+                }
+            }
+        }
+    }
+
+    pub async fn sleep(duration: Duration) {
+        tokio::time::sleep(duration).await
+    }
+
+    pub async fn timeout<F: Future>(
+        duration: Duration,
+        future: F,
+    ) -> core::result::Result<F::Output, Elapsed> {
+        tokio::time::timeout(duration, future)
+            .await
+            .map_err(|_| Elapsed)
+    }
+}
+
+#[cfg(all(feature = "async-io", not(feature = "tokio")))]
+mod imp {
+    use super::*;
+    use futures_util::{
+        future::{select, Either},
+        pin_mut, ready,
+    };
+    use std::{io::ErrorKind, os::fd::AsFd};
+
+    pub struct AsyncFd<T: AsFd> {
+        inner: async_io::Async<T>,
+    }
+
+    impl<T: AsFd> AsyncFd<T> {
+        pub fn new(inner: T) -> Result<Self> {
+            async_io::Async::new(inner).map(|inner| Self { inner })
+        }
+
+        pub fn get_ref(&self) -> &T {
+            self.inner.get_ref()
+        }
+
+        pub fn into_inner(self) -> Result<T> {
+            self.inner.into_inner()
+        }
+
+        pub fn poll_writable(&self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+            self.inner.poll_writable(cx)
+        }
+
+        pub fn poll_read_io<R>(
+            &self,
+            cx: &mut Context<'_>,
+            mut f: impl FnMut(&T) -> Result<R>,
+        ) -> Poll<Result<R>> {
+            loop {
+                match f(self.inner.get_ref()) {
+                    Err(error) if error.kind() == ErrorKind::WouldBlock => {
+                        ready!(self.inner.poll_readable(cx))?
+                    }
+                    result => return Poll::Ready(result),
+                }
+            }
+        }
+
+        pub fn poll_write_io<R>(
+            &self,
+            cx: &mut Context<'_>,
+            mut f: impl FnMut(&T) -> Result<R>,
+        ) -> Poll<Result<R>> {
+            loop {
+                match f(self.inner.get_ref()) {
+                    Err(error) if error.kind() == ErrorKind::WouldBlock => {
+                        ready!(self.inner.poll_writable(cx))?
+                    }
+                    result => return Poll::Ready(result),
+                }
+            }
+        }
+    }
+
+    pub async fn sleep(duration: Duration) {
+        async_io::Timer::after(duration).await;
+    }
+
+    pub async fn timeout<F: Future>(
+        duration: Duration,
+        future: F,
+    ) -> core::result::Result<F::Output, Elapsed> {
+        pin_mut!(future);
+        match select(future, async_io::Timer::after(duration)).await {
+            Either::Left((output, _)) => Ok(output),
+            Either::Right(_) => Err(Elapsed),
+        }
+    }
+}
+
+#[cfg(any(feature = "tokio", feature = "async-io"))]
+pub use imp::*;
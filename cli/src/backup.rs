@@ -0,0 +1,374 @@
+//! Backup and restore of objects
+//!
+//! Data of objects is written to `objects/<index>` entries as it is read
+//! and archive ends with `manifest.json` which keeps full metadata of
+//! objects. Restore finds manifest first and then reads data entries one
+//! by one in order of objects. Identifiers of objects are assigned by
+//! device, so restored objects get new identifiers which are reported.
+
+use crate::{
+    cli::{BackupArgs, Format, ObjSel, RestoreArgs},
+    output::RecordStream,
+    read_objects, system_time,
+    transport::Client,
+    Error, Result,
+};
+use bluez_async_ots::{Metadata, Property, WriteMode};
+use serde::{Deserialize, Serialize};
+use std::{
+    io::{Read, Seek},
+    time::UNIX_EPOCH,
+};
+
+/// Name of manifest entry
+const MANIFEST: &str = "manifest.json";
+
+/// Archive manifest
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Manifest {
+    objects: Vec<Entry>,
+}
+
+/// Archived object
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Entry {
+    /// Path of data entry
+    ///
+    /// Not set when data could not be read.
+    file: Option<String>,
+    #[serde(flatten)]
+    metadata: Metadata,
+}
+
+/// Backup report of object
+#[derive(Serialize)]
+struct Saved<'a> {
+    name: &'a str,
+    id: Option<u64>,
+    size: Option<usize>,
+    status: &'static str,
+    problem: String,
+}
+
+/// Restore report of object
+#[derive(Serialize)]
+struct Restored<'a> {
+    name: &'a str,
+    id: Option<u64>,
+    new_id: Option<u64>,
+    status: &'static str,
+    problems: String,
+}
+
+impl BackupArgs {
+    pub async fn run(&self, ots: &Client, format: Format) -> Result<()> {
+        let mut out = RecordStream::new(format);
+        let mut builder = tar::Builder::new(std::fs::File::create(&self.output)?);
+        let mut objects = Vec::new();
+
+        for (index, metadata) in read_objects(ots).await?.into_iter().enumerate() {
+            if metadata.id == Some(0) {
+                out.push(&Saved {
+                    name: &metadata.name,
+                    id: metadata.id,
+                    size: metadata.current_size,
+                    status: "skipped",
+                    problem: "directory listing object".into(),
+                })?;
+                continue;
+            }
+
+            let (file, status, problem) = match read_data(ots, index, &metadata).await {
+                Ok(bytes) => {
+                    let file = format!("objects/{index}");
+                    let modified = metadata
+                        .last_modified
+                        .clone()
+                        .and_then(system_time)
+                        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                        .map(|time| time.as_secs());
+                    append(&mut builder, &file, &bytes, modified)?;
+                    (Some(file), "saved", String::new())
+                }
+                Err(error) => (None, "metadata-only", error.to_string()),
+            };
+            out.push(&Saved {
+                name: &metadata.name,
+                id: metadata.id,
+                size: metadata.current_size,
+                status,
+                problem,
+            })?;
+            objects.push(Entry { file, metadata });
+        }
+
+        // manifest is known when all objects are read
+        append(
+            &mut builder,
+            MANIFEST,
+            &serde_json::to_vec_pretty(&Manifest { objects })?,
+            None,
+        )?;
+        builder.into_inner()?.sync_all()?;
+
+        Ok(())
+    }
+}
+
+impl RestoreArgs {
+    pub async fn run(&self, ots: &Client, format: Format) -> Result<()> {
+        let manifest = read_manifest(std::fs::File::open(&self.input)?)?;
+        let mut archive = tar::Archive::new(std::fs::File::open(&self.input)?);
+        let mut entries = archive.entries()?;
+        let existing = read_objects(ots).await?;
+
+        let mut out = RecordStream::new(format);
+        let mut failed = 0;
+
+        for entry in manifest.objects {
+            let metadata = &entry.metadata;
+            let data = match &entry.file {
+                Some(file) => read_entry(&mut entries, file)?,
+                None => None,
+            };
+
+            let mut problems = Vec::new();
+            let (status, new_id) = match self
+                .restore(ots, &existing, metadata, data, &mut problems)
+                .await
+            {
+                Ok(new_id) if problems.is_empty() => ("restored", new_id),
+                Ok(new_id) => ("partial", new_id),
+                Err(error) => {
+                    problems.insert(0, error.to_string());
+                    failed += 1;
+                    ("failed", None)
+                }
+            };
+            out.push(&Restored {
+                name: &metadata.name,
+                id: metadata.id,
+                new_id,
+                status,
+                problems: problems.join("; "),
+            })?;
+        }
+
+        if failed > 0 {
+            return Err(Error::RestoreFailed(failed));
+        }
+        Ok(())
+    }
+
+    /// Restore object
+    ///
+    /// Metadata which cannot be set is reported in problems.
+    async fn restore(
+        &self,
+        ots: &Client,
+        existing: &[Metadata],
+        metadata: &Metadata,
+        data: Option<Vec<u8>>,
+        problems: &mut Vec<String>,
+    ) -> Result<Option<u64>> {
+        if let Some((index, other)) = existing
+            .iter()
+            .enumerate()
+            .find(|(_, other)| other.name == metadata.name)
+        {
+            if !self.replace {
+                return Err(Error::ObjectExists);
+            }
+            obj_sel(index, other).select(ots).await?;
+        } else {
+            let size = metadata
+                .allocated_size
+                .or(metadata.current_size)
+                .unwrap_or_default()
+                .max(data.as_ref().map(Vec::len).unwrap_or_default());
+            ots.create(size, metadata.type_).await?;
+            if let Err(error) = ots.set_name(&metadata.name).await {
+                // do not leave unnamed object
+                let _ = ots.delete().await;
+                return Err(error.into());
+            }
+        }
+
+        match &data {
+            Some(data) if !data.is_empty() => {
+                let written = ots.write(0, data, WriteMode::Truncate).await?;
+                if written < data.len() {
+                    let length = data.len();
+                    problems.push(Error::ShortWrite { written, length }.to_string());
+                }
+            }
+            Some(_) => {}
+            None => problems.push("no data in archive".into()),
+        }
+
+        // times and properties are set after data, because writing
+        // updates modification time and may be prohibited by properties
+        if let Some(time) = &metadata.first_created {
+            if let Err(error) = ots.set_first_created(time).await {
+                problems.push(format!("first created time: {error}"));
+            }
+        }
+        if let Some(time) = &metadata.last_modified {
+            if let Err(error) = ots.set_last_modified(time).await {
+                problems.push(format!("last modified time: {error}"));
+            }
+        }
+        if ots.properties().await.ok() != Some(metadata.properties) {
+            if let Err(error) = ots.set_properties(metadata.properties).await {
+                problems.push(format!("properties: {error}"));
+            }
+        }
+
+        Ok(ots.id().await.ok().flatten())
+    }
+}
+
+/// Get selection of object by identifier or list index
+fn obj_sel(index: usize, metadata: &Metadata) -> ObjSel {
+    ObjSel {
+        index: metadata.id.is_none().then_some(index),
+        id: metadata.id,
+        name: None,
+    }
+}
+
+/// Read data of object
+async fn read_data(ots: &Client, index: usize, metadata: &Metadata) -> Result<Vec<u8>> {
+    if !metadata.properties.contains(Property::Read) {
+        return Err(Error::NotReadable);
+    }
+    obj_sel(index, metadata).select(ots).await?;
+    Ok(ots.read(0, None).await?)
+}
+
+fn append<W: std::io::Write>(
+    builder: &mut tar::Builder<W>,
+    path: &str,
+    data: &[u8],
+    modified: Option<u64>,
+) -> Result<()> {
+    let mut header = tar::Header::new_ustar();
+    header.set_size(data.len() as _);
+    header.set_mode(0o644);
+    header.set_mtime(modified.unwrap_or_default());
+    builder.append_data(&mut header, path, data)?;
+    Ok(())
+}
+
+/// Find manifest in archive
+fn read_manifest<R: Read + Seek>(reader: R) -> Result<Manifest> {
+    let mut archive = tar::Archive::new(reader);
+    for entry in archive.entries_with_seek()? {
+        let entry = entry?;
+        if entry.path()?.as_ref() == std::path::Path::new(MANIFEST) {
+            return Ok(serde_json::from_reader(entry)?);
+        }
+    }
+    Err(Error::NoManifest)
+}
+
+/// Read data of next entry with path
+///
+/// Data entries follow in order of objects, so preceding entries are skipped.
+fn read_entry<R: Read>(entries: &mut tar::Entries<R>, path: &str) -> Result<Option<Vec<u8>>> {
+    for entry in entries {
+        let mut entry = entry?;
+        if entry.path()?.as_ref() == std::path::Path::new(path) {
+            let mut data = Vec::new();
+            entry.read_to_end(&mut data)?;
+            return Ok(Some(data));
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bluez_async_ots::DateTime;
+    use std::io::Cursor;
+
+    fn manifest() -> Manifest {
+        let metadata = |id, name: &str, size| Metadata {
+            id: Some(id),
+            name: name.into(),
+            type_: uuid::Uuid::from_u128(0x00002aca_0000_1000_8000_00805f9b34fb),
+            current_size: Some(size),
+            allocated_size: Some(size * 2),
+            first_created: None,
+            last_modified: Some(DateTime {
+                year: 2024,
+                month: 2,
+                day: 29,
+                hour: 23,
+                minute: 59,
+                second: 58,
+            }),
+            properties: Property::Read | Property::Write | Property::Mark,
+        };
+        Manifest {
+            objects: vec![
+                Entry {
+                    file: Some("objects/1".into()),
+                    metadata: metadata(0x100, "log.txt", 3),
+                },
+                Entry {
+                    file: None,
+                    metadata: metadata(0x101, "secret", 0),
+                },
+                Entry {
+                    file: Some("objects/3".into()),
+                    metadata: metadata(0x102, "empty", 0),
+                },
+            ],
+        }
+    }
+
+    /// Write archive with data entries and manifest
+    fn archive(manifest: Option<&Manifest>, files: &[(&str, &[u8])]) -> Cursor<Vec<u8>> {
+        let mut builder = tar::Builder::new(Vec::new());
+        for (path, data) in files {
+            append(&mut builder, path, data, Some(1_700_000_000)).unwrap();
+        }
+        if let Some(manifest) = manifest {
+            let data = serde_json::to_vec_pretty(manifest).unwrap();
+            append(&mut builder, MANIFEST, &data, None).unwrap();
+        }
+        Cursor::new(builder.into_inner().unwrap())
+    }
+
+    #[test]
+    fn archive_round_trip() {
+        let manifest = manifest();
+        let files: &[(&str, &[u8])] = &[("objects/1", b"abc"), ("objects/3", b"")];
+        let read = read_manifest(archive(Some(&manifest), files)).unwrap();
+        assert_eq!(read, manifest);
+
+        let mut archive = tar::Archive::new(archive(Some(&manifest), files));
+        let mut entries = archive.entries().unwrap();
+        let data: Vec<_> = read
+            .objects
+            .iter()
+            .map(|entry| match &entry.file {
+                Some(file) => read_entry(&mut entries, file).unwrap(),
+                None => None,
+            })
+            .collect();
+        assert_eq!(data, [Some(b"abc".to_vec()), None, Some(Vec::new())]);
+        // manifest is not data entry
+        assert_eq!(read_entry(&mut entries, "objects/1").unwrap(), None);
+    }
+
+    #[test]
+    fn archive_without_manifest() {
+        assert!(matches!(
+            read_manifest(archive(None, &[("objects/1", b"abc")])),
+            Err(Error::NoManifest)
+        ));
+    }
+}
@@ -1,7 +1,8 @@
 use bluez_async::MacAddress;
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 use either::Either;
 use std::path::PathBuf;
+use uuid::Uuid;
 
 /// Object Transfer Service client
 #[derive(Parser, Debug)]
@@ -16,13 +17,41 @@ pub struct Args {
     pub adapter: Option<Either<MacAddress, String>>,
 
     /// Device name or address to connect to
+    ///
+    /// When not set the only device in range which advertises
+    /// Object Transfer service is used.
     #[arg(short, long, value_parser = mac_or_name)]
-    pub device: Either<MacAddress, String>,
+    pub device: Option<Either<MacAddress, String>>,
+
+    /// OTS service instance index
+    ///
+    /// Device may have several OTS instances (i.e. included by other services).
+    /// By default first found instance will be used.
+    #[arg(short, long)]
+    pub service: Option<usize>,
 
     /// Use privileged mode for sockets
     #[arg(short, long)]
     pub privileged: bool,
 
+    /// Connect to mock server instead of device
+    ///
+    /// Server started by `ots-server --mock` is accessed through session
+    /// bus and object channels are connected to its Unix socket.
+    #[arg(
+        long,
+        value_name = "SOCKET",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = crate::mock::SOCKET_PATH,
+        conflicts_with_all = ["disco", "adapter", "device", "service"],
+    )]
+    pub mock: Option<PathBuf>,
+
+    /// Output format
+    #[arg(short = 'F', long, global = true, value_enum, default_value_t)]
+    pub format: Format,
+
     /// Client action to do
     #[command(subcommand)]
     pub action: Action,
@@ -36,6 +65,18 @@ fn mac_or_name(val: &str) -> Result<Either<MacAddress, String>, String> {
         .unwrap_or_else(|| Either::Right(val.into())))
 }
 
+/// Output format
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    /// Tab-separated text for humans
+    #[default]
+    Table,
+    /// JSON document
+    Json,
+    /// Comma-separated values with header
+    Csv,
+}
+
 #[derive(Subcommand, Debug)]
 pub enum Action {
     /// Get service info
@@ -53,6 +94,48 @@ pub enum Action {
     /// Write object data
     #[command(alias = "w")]
     Write(WriteArgs),
+
+    /// Create new object
+    #[command(alias = "c")]
+    Create(CreateArgs),
+
+    /// Delete object
+    #[command(alias = "d")]
+    Delete(DeleteArgs),
+
+    /// Calculate checksum of object data
+    #[command(name = "checksum", alias = "crc")]
+    CheckSum(CheckSumArgs),
+
+    /// Execute object
+    #[command(alias = "x")]
+    Execute(ExecuteArgs),
+
+    /// Abort current operation
+    Abort,
+
+    /// Run interactive shell
+    #[command(alias = "sh")]
+    Shell(ShellArgs),
+
+    /// Discover devices and mark ones with Object Transfer service
+    Scan(ScanArgs),
+
+    /// Synchronize objects with local directory
+    Sync(SyncArgs),
+
+    /// Save all objects with metadata to archive
+    Backup(BackupArgs),
+
+    /// Recreate objects from archive
+    Restore(RestoreArgs),
+
+    /// Print object changes indicated by server
+    Watch(WatchArgs),
+
+    /// Browse objects in full-screen terminal UI
+    #[command(alias = "browse")]
+    Tui(TuiArgs),
 }
 
 /// Service info args
@@ -152,7 +235,6 @@ arg_flags! {
         any_size: cur_size alloc_size size full;
         crt_time: crt_time time;
         mod_time: mod_time time;
-        any_time: crt_time mod_time time;
         props: props full;
     }
 }
@@ -198,10 +280,38 @@ pub struct ReadArgs {
 
     /// File to output data
     ///
-    /// If file is set the binary data will be written to.
-    /// Otherwise the hex data will be printed to stdout.
+    /// If file is set the data will be written to.
+    /// Otherwise the data will be printed to stdout.
     #[arg(short, long)]
     pub file: Option<PathBuf>,
+
+    /// Output data format
+    ///
+    /// By default raw data is written to file or piped stdout
+    /// and hex dump is printed to terminal.
+    #[arg(short = 'O', long, value_enum)]
+    pub output_format: Option<OutputFormat>,
+
+    /// Name of array for C array output
+    #[arg(long, value_parser = c_identifier, default_value = "ots_object")]
+    pub symbol: String,
+}
+
+/// Read output format
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Binary data
+    Raw,
+    /// Hex dump with addresses and ASCII
+    Hexdump,
+    /// Hexadecimal digits
+    Hex,
+    /// Base64 encoded data
+    Base64,
+    /// C source with array of bytes
+    CArray,
+    /// Intel HEX image
+    Ihex,
 }
 
 /// Object write options
@@ -221,8 +331,266 @@ pub struct WriteArgs {
 
     /// File to input data
     ///
-    /// If file is set the binary data will be read from.
-    /// Otherwise the hex data will be read from stdin.
+    /// If file is set the data will be read from.
+    /// Otherwise the data will be read from stdin.
+    #[arg(short, long)]
+    pub file: Option<PathBuf>,
+
+    /// Input data format [default: raw for file, hex for stdin]
+    #[arg(short = 'I', long, value_enum)]
+    pub input_format: Option<InputFormat>,
+
+    /// Image address which corresponds to offset [default: lowest address]
+    ///
+    /// Used for Intel HEX and S-record images only.
+    #[arg(short, long, value_parser = number)]
+    pub base: Option<u64>,
+
+    /// Byte to fill gaps between image records
+    #[arg(long, value_parser = byte, default_value = "0xff")]
+    pub fill: u8,
+}
+
+/// Write input format
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputFormat {
+    /// Binary data
+    Raw,
+    /// Hexadecimal digits with optional whitespaces
+    Hex,
+    /// Base64 encoded data
+    Base64,
+    /// Intel HEX image
+    Ihex,
+    /// Motorola S-record image
+    Srec,
+}
+
+/// Object create options
+#[derive(Parser, Debug)]
+pub struct CreateArgs {
+    /// Size to allocate in bytes
+    #[arg(short, long)]
+    pub size: usize,
+
+    /// Object type
+    ///
+    /// Either name of known type (unspecified, directory),
+    /// 16-bit assigned number in hex or full UUID.
+    #[arg(short, long = "type", value_parser = object_type, default_value = "unspecified")]
+    pub type_: Uuid,
+}
+
+/// Object delete options
+#[derive(Parser, Debug)]
+pub struct DeleteArgs {
+    /// Object to delete
+    #[command(flatten)]
+    pub object: ObjSel,
+}
+
+/// Object checksum options
+#[derive(Parser, Debug)]
+pub struct CheckSumArgs {
+    /// Object to calculate checksum of
+    #[command(flatten)]
+    pub object: ObjSel,
+
+    /// Data slice to calculate checksum of
+    #[command(flatten)]
+    pub range: RangeSel,
+}
+
+/// Object execute options
+#[derive(Parser, Debug)]
+pub struct ExecuteArgs {
+    /// Object to execute
+    #[command(flatten)]
+    pub object: ObjSel,
+
+    /// Parameter in hex
+    #[arg(short, long, conflicts_with = "file")]
+    pub param: Option<String>,
+
+    /// File to read parameter from
+    ///
+    /// Use `-` to read binary parameter from stdin.
     #[arg(short, long)]
     pub file: Option<PathBuf>,
 }
+
+/// Interactive shell options
+#[derive(Parser, Debug)]
+pub struct ShellArgs {
+    /// File to keep command history in
+    ///
+    /// Defaults to `.ots_history` in home directory.
+    #[arg(long)]
+    pub history: Option<PathBuf>,
+}
+
+/// Terminal UI options
+#[derive(Parser, Debug)]
+pub struct TuiArgs {
+    /// Use directory object
+    #[arg(short, long)]
+    pub dir: bool,
+}
+
+/// Device scan options
+#[derive(Parser, Debug)]
+pub struct ScanArgs {
+    /// Stop scanning after N seconds
+    ///
+    /// By default scan runs until interrupted.
+    #[arg(short, long)]
+    pub timeout: Option<u32>,
+
+    /// Show only devices with Object Transfer service
+    #[arg(short, long)]
+    pub ots: bool,
+}
+
+/// Directory synchronization options
+#[derive(Parser, Debug)]
+pub struct SyncArgs {
+    /// Local directory
+    pub dir: PathBuf,
+
+    /// Synchronization direction
+    #[arg(short, long, value_enum, default_value_t)]
+    pub mode: SyncMode,
+
+    /// Compare data checksums of files and objects of same size
+    ///
+    /// Otherwise modification times are compared.
+    #[arg(short, long)]
+    pub checksum: bool,
+
+    /// Delete files or objects which are missing on other side
+    #[arg(short = 'D', long)]
+    pub delete: bool,
+
+    /// Print planned steps without doing them
+    #[arg(short = 'n', long)]
+    pub dry_run: bool,
+
+    /// Synchronization state file
+    ///
+    /// Defaults to `.ots-sync.json` in local directory.
+    #[arg(short, long)]
+    pub state: Option<PathBuf>,
+
+    /// Type of created objects
+    ///
+    /// Either name of known type (unspecified, directory),
+    /// 16-bit assigned number in hex or full UUID.
+    #[arg(short, long = "type", value_parser = object_type, default_value = "unspecified")]
+    pub type_: Uuid,
+}
+
+/// Synchronization direction
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SyncMode {
+    /// Download objects to directory
+    #[default]
+    Pull,
+    /// Upload files to device
+    Push,
+    /// Copy changes both ways, newer side wins on conflict
+    Mirror,
+}
+
+/// Backup options
+#[derive(Parser, Debug)]
+pub struct BackupArgs {
+    /// Tar archive to write
+    #[arg(short, long)]
+    pub output: PathBuf,
+}
+
+/// Restore options
+#[derive(Parser, Debug)]
+pub struct RestoreArgs {
+    /// Tar archive to read
+    #[arg(short, long)]
+    pub input: PathBuf,
+
+    /// Overwrite data of objects with same names
+    ///
+    /// Otherwise such objects are not restored.
+    #[arg(short, long)]
+    pub replace: bool,
+}
+
+/// Object changes watching options
+#[derive(Parser, Debug)]
+pub struct WatchArgs {
+    /// Shell command to run on each change
+    ///
+    /// Change is passed in `OTS_OBJECT_ID`, `OTS_OBJECT_NAME`,
+    /// `OTS_CHANGE_FLAGS` and `OTS_CHANGE_TIME` environment variables.
+    #[arg(short, long)]
+    pub exec: Option<String>,
+}
+
+/// Known object types
+const OBJECT_TYPES: &[(&str, u16)] = &[("unspecified", 0x2aca), ("directory", 0x2acb)];
+
+/// Get name of known object type
+pub fn type_name(type_: &Uuid) -> Option<&'static str> {
+    OBJECT_TYPES
+        .iter()
+        .map(|(name, _)| *name)
+        .find(|name| object_type(name).as_ref() == Ok(type_))
+}
+
+/// Parse decimal number or hexadecimal number with `0x` prefix
+fn number(val: &str) -> Result<u64, String> {
+    if let Some(hex) = val.strip_prefix("0x") {
+        u64::from_str_radix(hex, 16)
+    } else {
+        val.parse()
+    }
+    .map_err(|error| error.to_string())
+}
+
+fn byte(val: &str) -> Result<u8, String> {
+    number(val)?
+        .try_into()
+        .map_err(|error: core::num::TryFromIntError| error.to_string())
+}
+
+fn c_identifier(val: &str) -> Result<String, String> {
+    let mut chars = val.chars();
+    if chars
+        .next()
+        .is_some_and(|chr| chr.is_ascii_alphabetic() || chr == '_')
+        && chars.all(|chr| chr.is_ascii_alphanumeric() || chr == '_')
+    {
+        Ok(val.into())
+    } else {
+        Err("not a C identifier".into())
+    }
+}
+
+fn object_type(val: &str) -> Result<Uuid, String> {
+    let short = |num: u16| {
+        Uuid::from_fields(
+            num as _,
+            0x0,
+            0x1000,
+            &[0x80, 0x00, 0x00, 0x80, 0x5f, 0x9b, 0x34, 0xfb],
+        )
+    };
+    if let Some((_, num)) = OBJECT_TYPES.iter().find(|(name, _)| *name == val) {
+        return Ok(short(*num));
+    }
+    let hex = val.trim_start_matches("0x");
+    if hex.len() <= 4 {
+        return u16::from_str_radix(hex, 16)
+            .map(short)
+            .map_err(|error| error.to_string());
+    }
+    val.parse().map_err(|error: uuid::Error| error.to_string())
+}
@@ -0,0 +1,536 @@
+//! Data encodings
+//!
+//! Input data is decoded fully, while output data is encoded by lines
+//! as it is read.
+//!
+//! Intel HEX and Motorola S-record images consist of records with data
+//! at absolute addresses, which are mapped to object offsets relative to
+//! base address (lowest address of image by default). Written images
+//! use object offsets as addresses.
+
+use crate::{
+    cli::{InputFormat, OutputFormat},
+    hex, parse_hex, Error, Result,
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use std::{
+    io::{self, Write},
+    ops::Range,
+};
+
+/// Data at address of image
+#[derive(Debug)]
+pub struct Segment {
+    pub address: u64,
+    pub data: Vec<u8>,
+}
+
+/// Contiguous data of image
+#[derive(Debug, Default)]
+pub struct Image {
+    /// Offset of data relative to base address
+    pub offset: usize,
+    pub data: Vec<u8>,
+    /// Address ranges without data which are filled
+    pub gaps: Vec<Range<u64>>,
+}
+
+/// Decode input data
+///
+/// Data of images is placed at offset of its first address
+/// relative to base address.
+pub fn decode(format: InputFormat, input: &[u8], base: Option<u64>, fill: u8) -> Result<Image> {
+    let segments = match format {
+        InputFormat::Raw => return Ok(Image::from(input.to_vec())),
+        InputFormat::Hex => return Ok(Image::from(parse_hex(core::str::from_utf8(input)?)?)),
+        InputFormat::Base64 => {
+            let input: Vec<u8> = input
+                .iter()
+                .copied()
+                .filter(|byte| !byte.is_ascii_whitespace())
+                .collect();
+            return Ok(Image::from(STANDARD.decode(input)?));
+        }
+        InputFormat::Ihex => parse_ihex(core::str::from_utf8(input)?)?,
+        InputFormat::Srec => parse_srec(core::str::from_utf8(input)?)?,
+    };
+    flatten(segments, base, fill)
+}
+
+impl From<Vec<u8>> for Image {
+    fn from(data: Vec<u8>) -> Self {
+        Self {
+            data,
+            ..Default::default()
+        }
+    }
+}
+
+/// Parse Intel HEX records
+pub fn parse_ihex(text: &str) -> Result<Vec<Segment>> {
+    let mut segments = Vec::new();
+    // upper part of address set by extended address records
+    let mut upper = 0u64;
+
+    for (line, record) in records(text) {
+        let bad = |reason| Error::RecordError { line, reason };
+        let record = record.strip_prefix(':').ok_or(bad("missing start code"))?;
+        let raw = parse_hex(record).map_err(|_| bad("bad hexadecimal data"))?;
+        if raw.len() < 5 || raw.len() != raw[0] as usize + 5 {
+            return Err(bad("bad record length"));
+        }
+        if raw.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte)) != 0 {
+            return Err(bad("bad checksum"));
+        }
+        let address = u16::from_be_bytes([raw[1], raw[2]]) as u64;
+        let data = &raw[4..raw.len() - 1];
+        let value = || {
+            data.try_into()
+                .map(u16::from_be_bytes)
+                .map_err(|_| bad("bad address record"))
+        };
+
+        match raw[3] {
+            0x00 => push(&mut segments, upper + address, data),
+            0x01 => break,
+            // extended segment address
+            0x02 => upper = (value()? as u64) << 4,
+            // extended linear address
+            0x04 => upper = (value()? as u64) << 16,
+            // start addresses
+            0x03 | 0x05 => {}
+            _ => return Err(bad("unknown record type")),
+        }
+    }
+
+    Ok(segments)
+}
+
+/// Parse Motorola S-records
+pub fn parse_srec(text: &str) -> Result<Vec<Segment>> {
+    let mut segments = Vec::new();
+
+    for (line, record) in records(text) {
+        let bad = |reason| Error::RecordError { line, reason };
+        let record = record.strip_prefix('S').ok_or(bad("missing start code"))?;
+        let kind = record.chars().next().ok_or(bad("missing record type"))?;
+        let raw = parse_hex(&record[kind.len_utf8()..]).map_err(|_| bad("bad hexadecimal data"))?;
+        if raw.is_empty() || raw.len() != raw[0] as usize + 1 {
+            return Err(bad("bad record length"));
+        }
+        if raw.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte)) != 0xff {
+            return Err(bad("bad checksum"));
+        }
+
+        let address_len = match kind {
+            '1' => 2,
+            '2' => 3,
+            '3' => 4,
+            // header and record counts
+            '0' | '5' | '6' => continue,
+            // termination records
+            '7' | '8' | '9' => break,
+            _ => return Err(bad("unknown record type")),
+        };
+        if raw.len() < address_len + 2 {
+            return Err(bad("bad record length"));
+        }
+        let address = raw[1..1 + address_len]
+            .iter()
+            .fold(0u64, |address, byte| address << 8 | *byte as u64);
+        push(&mut segments, address, &raw[1 + address_len..raw.len() - 1]);
+    }
+
+    Ok(segments)
+}
+
+/// Non-empty lines with numbers
+fn records(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty())
+}
+
+/// Append data to last segment when contiguous
+fn push(segments: &mut Vec<Segment>, address: u64, data: &[u8]) {
+    match segments.last_mut() {
+        Some(last) if last.address + last.data.len() as u64 == address => {
+            last.data.extend_from_slice(data)
+        }
+        _ => segments.push(Segment {
+            address,
+            data: data.into(),
+        }),
+    }
+}
+
+/// Merge segments into contiguous data
+///
+/// Gaps between segments are filled with fill byte. Data of overlapped
+/// segments is taken from segment with higher address.
+pub fn flatten(mut segments: Vec<Segment>, base: Option<u64>, fill: u8) -> Result<Image> {
+    segments.sort_by_key(|segment| segment.address);
+    let Some(start) = segments.first().map(|segment| segment.address) else {
+        return Ok(Image::default());
+    };
+    let base = base.unwrap_or(start);
+    if start < base {
+        return Err(Error::AddressError(start));
+    }
+
+    let mut image = Image {
+        offset: (start - base) as _,
+        ..Default::default()
+    };
+    for segment in segments {
+        let begin = (segment.address - start) as usize;
+        let end = begin + segment.data.len();
+        if begin > image.data.len() {
+            image
+                .gaps
+                .push(start + image.data.len() as u64..segment.address);
+        }
+        if end > image.data.len() {
+            image.data.resize(end, fill);
+        }
+        image.data[begin..end].copy_from_slice(&segment.data);
+    }
+
+    Ok(image)
+}
+
+/// Streaming encoder of output data
+///
+/// Data is written by lines, so only incomplete line is kept.
+pub struct Encoder<W> {
+    format: OutputFormat,
+    out: W,
+    /// Address of first byte
+    start: usize,
+    /// Address of pending data
+    address: usize,
+    pending: Vec<u8>,
+    /// Upper part of last Intel HEX address
+    upper: u16,
+    symbol: String,
+}
+
+impl<W: Write> Encoder<W> {
+    /// Create encoder and write header
+    ///
+    /// Address of first byte is used by hexdump and Intel HEX.
+    pub fn new(
+        format: OutputFormat,
+        mut out: W,
+        address: usize,
+        length: usize,
+        symbol: &str,
+    ) -> io::Result<Self> {
+        match format {
+            OutputFormat::Hexdump => writeln!(out, "Length: {length} (0x{length:x})")?,
+            OutputFormat::CArray => writeln!(out, "const unsigned char {symbol}[] = {{")?,
+            _ => {}
+        }
+        Ok(Self {
+            format,
+            out,
+            start: address,
+            address,
+            pending: Vec::new(),
+            upper: 0,
+            symbol: symbol.into(),
+        })
+    }
+
+    /// Encode next part of data
+    pub fn push(&mut self, data: &[u8]) -> io::Result<()> {
+        if self.format == OutputFormat::Raw {
+            self.address += data.len();
+            return self.out.write_all(data);
+        }
+        self.pending.extend_from_slice(data);
+        let width = self.width();
+        let rest = self.pending.split_off(self.pending.len() / width * width);
+        let lines = core::mem::replace(&mut self.pending, rest);
+        for line in lines.chunks(width) {
+            self.line(line)?;
+        }
+        Ok(())
+    }
+
+    /// Encode remaining data and write trailer
+    pub fn finish(mut self) -> io::Result<W> {
+        let rest = core::mem::take(&mut self.pending);
+        if !rest.is_empty() {
+            self.line(&rest)?;
+        }
+        match self.format {
+            OutputFormat::CArray => {
+                writeln!(self.out, "}};")?;
+                writeln!(
+                    self.out,
+                    "const unsigned int {}_len = {};",
+                    self.symbol,
+                    self.address - self.start
+                )?;
+            }
+            OutputFormat::Ihex => self.ihex_record(0x01, 0, &[])?,
+            _ => {}
+        }
+        self.out.flush()?;
+        Ok(self.out)
+    }
+
+    /// Bytes per line
+    fn width(&self) -> usize {
+        match self.format {
+            OutputFormat::Raw => 1,
+            OutputFormat::Hexdump | OutputFormat::Ihex => 16,
+            OutputFormat::Hex => 32,
+            // 76 characters per line
+            OutputFormat::Base64 => 57,
+            OutputFormat::CArray => 12,
+        }
+    }
+
+    fn line(&mut self, data: &[u8]) -> io::Result<()> {
+        match self.format {
+            OutputFormat::Raw => self.out.write_all(data)?,
+            OutputFormat::Hexdump => {
+                let mut text = String::new();
+                let config = hex_pp::HexConfig {
+                    title: false,
+                    display_offset: self.address,
+                    ..Default::default()
+                };
+                hex_pp::hex_write(&mut text, data, config).map_err(io::Error::other)?;
+                writeln!(self.out, "{text}")?;
+            }
+            OutputFormat::Hex => writeln!(self.out, "{}", hex(data))?,
+            OutputFormat::Base64 => writeln!(self.out, "{}", STANDARD.encode(data))?,
+            OutputFormat::CArray => {
+                write!(self.out, "   ")?;
+                for byte in data {
+                    write!(self.out, " 0x{byte:02x},")?;
+                }
+                writeln!(self.out)?;
+            }
+            OutputFormat::Ihex => {
+                // records cannot cross 64K boundary
+                let room = 0x10000 - (self.address & 0xffff);
+                let (head, tail) = data.split_at(data.len().min(room));
+                self.ihex_data(self.address, head)?;
+                if !tail.is_empty() {
+                    self.ihex_data(self.address + head.len(), tail)?;
+                }
+            }
+        }
+        self.address += data.len();
+        Ok(())
+    }
+
+    fn ihex_data(&mut self, address: usize, data: &[u8]) -> io::Result<()> {
+        let upper = (address >> 16) as u16;
+        if upper != self.upper {
+            // extended linear address
+            self.ihex_record(0x04, 0, &upper.to_be_bytes())?;
+            self.upper = upper;
+        }
+        self.ihex_record(0x00, address as u16, data)
+    }
+
+    fn ihex_record(&mut self, kind: u8, address: u16, data: &[u8]) -> io::Result<()> {
+        let mut raw = vec![data.len() as u8];
+        raw.extend_from_slice(&address.to_be_bytes());
+        raw.push(kind);
+        raw.extend_from_slice(data);
+        raw.push(
+            raw.iter()
+                .fold(0u8, |sum, byte| sum.wrapping_add(*byte))
+                .wrapping_neg(),
+        );
+        write!(self.out, ":")?;
+        for byte in raw {
+            write!(self.out, "{byte:02X}")?;
+        }
+        writeln!(self.out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record_error(result: Result<Vec<Segment>>) -> (usize, &'static str) {
+        match result {
+            Err(Error::RecordError { line, reason }) => (line, reason),
+            other => panic!("Unexpected result: {other:?}"),
+        }
+    }
+
+    fn segments(segments: &[(u64, &[u8])]) -> Vec<Segment> {
+        segments
+            .iter()
+            .map(|(address, data)| Segment {
+                address: *address,
+                data: data.to_vec(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn ihex_records() {
+        let image = decode(
+            InputFormat::Ihex,
+            b":0401000001020304F1\n\n:020104000506EE\n:00000001FF\n:0000000AF6\n",
+            None,
+            0xff,
+        )
+        .unwrap();
+        assert_eq!(image.offset, 0);
+        assert_eq!(image.data, [1, 2, 3, 4, 5, 6]);
+        assert!(image.gaps.is_empty());
+    }
+
+    #[test]
+    fn ihex_bad_records() {
+        let bad = |text| record_error(parse_ihex(text));
+        assert_eq!(bad(":0401000001020304F2"), (1, "bad checksum"));
+        assert_eq!(bad(":0501000001020304F0"), (1, "bad record length"));
+        assert_eq!(bad(":000001"), (1, "bad record length"));
+        assert_eq!(bad(":0401000001020304F"), (1, "bad hexadecimal data"));
+        assert_eq!(bad("0401000001020304F1"), (1, "missing start code"));
+        assert_eq!(
+            bad(":020104000506EE\n\n:0000000AF6"),
+            (3, "unknown record type")
+        );
+        assert_eq!(bad(":0100000408F3"), (1, "bad address record"));
+    }
+
+    #[test]
+    fn ihex_extended_addresses() {
+        let segments = parse_ihex(":020000040800F2\n:02001000AABB89\n").unwrap();
+        assert_eq!(segments[0].address, 0x0800_0010);
+        assert_eq!(segments[0].data, [0xaa, 0xbb]);
+
+        let segments = parse_ihex(":020000021000EC\n:02001000AABB89\n").unwrap();
+        assert_eq!(segments[0].address, 0x1_0010);
+    }
+
+    #[test]
+    fn srec_records() {
+        let segments = parse_srec(
+            "S0060000686472BB\nS10510000102E7\nS206123456030456\n\
+             S307080000000506E5\nS5030001FB\nS9030000FC\nS10510000102E7\n",
+        )
+        .unwrap();
+        assert_eq!(segments.len(), 3);
+        assert_eq!(segments[0].address, 0x1000);
+        assert_eq!(segments[0].data, [1, 2]);
+        assert_eq!(segments[1].address, 0x12_3456);
+        assert_eq!(segments[1].data, [3, 4]);
+        assert_eq!(segments[2].address, 0x0800_0000);
+        assert_eq!(segments[2].data, [5, 6]);
+    }
+
+    #[test]
+    fn srec_bad_records() {
+        let bad = |text| record_error(parse_srec(text));
+        assert_eq!(bad("S10510000102E8"), (1, "bad checksum"));
+        assert_eq!(bad("S10610000102E6"), (1, "bad record length"));
+        assert_eq!(bad("S1"), (1, "bad record length"));
+        // checksum is valid but address is truncated
+        assert_eq!(bad("S30210ED"), (1, "bad record length"));
+        assert_eq!(bad("S1051000010"), (1, "bad hexadecimal data"));
+        assert_eq!(bad("10510000102E7"), (1, "missing start code"));
+        assert_eq!(bad("S"), (1, "missing record type"));
+        assert_eq!(
+            bad("S0060000686472BB\nS4030000FC"),
+            (2, "unknown record type")
+        );
+    }
+
+    #[test]
+    fn flatten_gaps() {
+        let image = flatten(segments(&[(0x14, &[3]), (0x10, &[1, 2])]), None, 0xff).unwrap();
+        assert_eq!(image.offset, 0);
+        assert_eq!(image.data, [1, 2, 0xff, 0xff, 3]);
+        assert_eq!(image.gaps.len(), 1);
+        assert_eq!(image.gaps[0], 0x12..0x14);
+    }
+
+    #[test]
+    fn flatten_overlaps() {
+        let image = flatten(
+            segments(&[(0x12, &[5, 6, 7]), (0x10, &[1, 2, 3, 4])]),
+            None,
+            0,
+        )
+        .unwrap();
+        assert_eq!(image.data, [1, 2, 5, 6, 7]);
+        assert!(image.gaps.is_empty());
+    }
+
+    #[test]
+    fn flatten_base() {
+        let image = flatten(segments(&[(0x0800_0010, &[1])]), Some(0x0800_0000), 0).unwrap();
+        assert_eq!(image.offset, 0x10);
+        assert_eq!(image.data, [1]);
+
+        assert!(matches!(
+            flatten(segments(&[(0x0800_0010, &[1])]), Some(0x0800_0020), 0),
+            Err(Error::AddressError(0x0800_0010))
+        ));
+        assert!(flatten(Vec::new(), Some(1), 0).unwrap().data.is_empty());
+    }
+
+    fn encode(format: OutputFormat, address: usize, data: &[u8]) -> Vec<u8> {
+        let mut encoder = Encoder::new(format, Vec::new(), address, data.len(), "data").unwrap();
+        // odd chunks to split lines
+        for chunk in data.chunks(7) {
+            encoder.push(chunk).unwrap();
+        }
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn encode_ihex() {
+        let data: Vec<u8> = (0..40).collect();
+        let text = String::from_utf8(encode(OutputFormat::Ihex, 0xfff8, &data)).unwrap();
+        let lines: Vec<_> = text.lines().collect();
+        // first line is split at 64K boundary
+        assert_eq!(lines[0], ":08FFF8000001020304050607E5");
+        assert_eq!(lines[1], ":020000040001F9");
+        assert!(lines[2].starts_with(":08000000"));
+        assert_eq!(lines.last(), Some(&":00000001FF"));
+
+        let image = decode(InputFormat::Ihex, text.as_bytes(), Some(0), 0).unwrap();
+        assert_eq!(image.offset, 0xfff8);
+        assert_eq!(image.data, data);
+        assert!(image.gaps.is_empty());
+    }
+
+    #[test]
+    fn encode_text() {
+        let data: Vec<u8> = (0..=255).collect();
+        for format in [OutputFormat::Hex, OutputFormat::Base64] {
+            let input = match format {
+                OutputFormat::Hex => InputFormat::Hex,
+                _ => InputFormat::Base64,
+            };
+            let text = encode(format, 0, &data);
+            assert_eq!(decode(input, &text, None, 0).unwrap().data, data);
+        }
+        assert_eq!(encode(OutputFormat::Raw, 0, &data), data);
+    }
+
+    #[test]
+    fn encode_c_array() {
+        let text = encode(OutputFormat::CArray, 0, &[0, 1, 0xff]);
+        assert_eq!(
+            String::from_utf8(text).unwrap(),
+            "const unsigned char data[] = {\n    0x00, 0x01, 0xff,\n};\nconst unsigned int data_len = 3;\n"
+        );
+    }
+}
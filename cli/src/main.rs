@@ -1,8 +1,17 @@
-use bluez_async::BluetoothSession;
-use bluez_async_ots::{ClientConfig, CoreError, DirEntries, OtsClient};
+use bluez_async::{AdapterId, BluetoothSession};
+use bluez_async_ots::{
+    ActionFeature, ActionRc, ClientConfig, CoreError, DateTime, DirEntries, ListFeature, ListRc,
+    Metadata, OtsClient,
+};
+use cli::Format;
 use core::time::Duration;
 use either::Either;
+use mock::MockGatt;
+use output::{print_json, print_record, Records};
+use serde::Serialize;
+use std::process::ExitCode;
 use tokio::{io::AsyncReadExt, time::sleep};
+use transport::{Client, Transport};
 
 #[cfg(all(feature = "log", not(feature = "tracing")))]
 use log::{debug, info, trace, warn};
@@ -27,7 +36,17 @@ mod log_stub {
     }
 }
 
+mod backup;
 mod cli;
+mod codec;
+mod mock;
+mod output;
+mod scan;
+mod shell;
+mod sync;
+mod transport;
+mod tui;
+mod watch;
 
 /// OTS command result
 pub type Result<T> = core::result::Result<T, Error>;
@@ -47,12 +66,40 @@ pub enum Error {
     NoAdapter,
     #[error("No device found")]
     NoDevice,
+    #[error("Several OTS devices found: {0}")]
+    ManyDevices(String),
+    #[error("No service found")]
+    NoService,
     #[error("No object found")]
     NoObject,
     #[error("Need any of index, id or name to select object to read")]
     ObjIdError,
     #[error("Bad hexadecimal data")]
     HexError,
+    #[error("Bad base64 data: {0}")]
+    Base64Error(#[from] base64::DecodeError),
+    #[error("Bad record at line {line}: {reason}")]
+    RecordError { line: usize, reason: &'static str },
+    #[error("Image address {0:#x} is below base address")]
+    AddressError(u64),
+    #[error("JSON Error: {0}")]
+    JsonError(#[from] serde_json::Error),
+    #[error("CSV Error: {0}")]
+    CsvError(#[from] csv::Error),
+    #[error("Object is not readable")]
+    NotReadable,
+    #[error("Object with same name exists")]
+    ObjectExists,
+    #[error("Only {written} of {length} bytes written, object is too small")]
+    ShortWrite { written: usize, length: usize },
+    #[error("Object name {0:?} cannot be used as file name")]
+    BadFileName(String),
+    #[error("No manifest found in archive")]
+    NoManifest,
+    #[error("Unable to restore {0} objects")]
+    RestoreFailed(usize),
+    #[error("Bad arguments, usage: {0}")]
+    UsageError(&'static str),
 }
 
 impl From<std::string::FromUtf8Error> for Error {
@@ -67,8 +114,27 @@ impl From<CoreError> for Error {
     }
 }
 
+impl Error {
+    /// Get process exit code
+    ///
+    /// Rejected object actions exit with 10 plus action result code,
+    /// so scripts can distinguish them.
+    pub fn exit_code(&self) -> u8 {
+        use bluez_async_ots::Error as OtsError;
+        match self {
+            Error::OtsError(OtsError::Core(CoreError::ActionError(rc))) => 10 + *rc as u8,
+            Error::OtsError(OtsError::NotSupported) => 10 + ActionRc::OperationNotSupported as u8,
+            Error::NoObject | Error::OtsError(OtsError::NotFound) => 3,
+            _ => 1,
+        }
+    }
+}
+
+/// Discovery time in seconds when device is picked automatically
+const AUTO_DISCO_SECS: u32 = 5;
+
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> ExitCode {
     #[cfg(feature = "pretty_env_logger")]
     pretty_env_logger::init();
 
@@ -79,11 +145,29 @@ async fn main() -> Result<()> {
 
     let args = <cli::Args as clap::Parser>::parse();
 
+    match run(args).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("Error: {error}");
+            ExitCode::from(error.exit_code())
+        }
+    }
+}
+
+async fn run(args: cli::Args) -> Result<()> {
     let config = ClientConfig {
         privileged: args.privileged,
         ..Default::default()
     };
 
+    if let Some(socket) = args.mock {
+        if let cli::Action::Scan(_) = &args.action {
+            return Err(Error::NoAdapter);
+        }
+        let ots = OtsClient::with_gatt(Transport::Mock(MockGatt::connect(&socket).await?)).await?;
+        return args.action.run(&ots, args.format).await;
+    }
+
     let (_, bs) = BluetoothSession::new().await?;
 
     let adapter_id = if let Some(mac_or_name) = &args.adapter {
@@ -109,34 +193,43 @@ async fn main() -> Result<()> {
         None
     };
 
-    if let Some(secs) = &args.disco {
-        if let Some(id) = &adapter_id {
-            info!("Start discovery on {id:?}");
-            bs.start_discovery_on_adapter(id).await?;
-        } else {
-            info!("Start discovery");
-            bs.start_discovery().await?;
-        }
-        sleep(Duration::from_secs(*secs as _)).await;
-        if let Some(id) = &adapter_id {
-            info!("Stop discovery on {id:?}");
-            bs.stop_discovery_on_adapter(id).await?;
-        } else {
-            info!("Start discovery");
-            bs.stop_discovery().await?;
-        }
+    if let cli::Action::Scan(scan) = &args.action {
+        return scan.run(&bs, adapter_id.as_ref(), args.format).await;
     }
 
-    let devs = bs.get_devices().await?;
+    if let Some(secs) = args.disco {
+        discover(&bs, adapter_id.as_ref(), secs).await?;
+    }
 
-    let dev = devs
-        .into_iter()
-        .find(|dev| match (&args.device, &dev.mac_address, &dev.name) {
-            (Either::Left(req_addr), dev_addr, _) if req_addr == dev_addr => true,
-            (Either::Right(req_name), _, Some(dev_name)) if req_name == dev_name => true,
-            _ => false,
-        })
-        .ok_or_else(|| Error::NoDevice)?;
+    let dev = if let Some(device) = &args.device {
+        bs.get_devices()
+            .await?
+            .into_iter()
+            .find(|dev| match (device, &dev.mac_address, &dev.name) {
+                (Either::Left(req_addr), dev_addr, _) if req_addr == dev_addr => true,
+                (Either::Right(req_name), _, Some(dev_name)) if req_name == dev_name => true,
+                _ => false,
+            })
+            .ok_or_else(|| Error::NoDevice)?
+    } else {
+        let mut devs = scan::ots_devices(&bs, adapter_id.as_ref()).await?;
+        if devs.is_empty() && args.disco.is_none() {
+            discover(&bs, adapter_id.as_ref(), AUTO_DISCO_SECS).await?;
+            devs = scan::ots_devices(&bs, adapter_id.as_ref()).await?;
+        }
+        if devs.len() > 1 {
+            return Err(Error::ManyDevices(
+                devs.iter()
+                    .map(|dev| match &dev.name {
+                        Some(name) => format!("{} ({name})", dev.mac_address),
+                        None => dev.mac_address.to_string(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ));
+        }
+        devs.pop().ok_or_else(|| Error::NoDevice)?
+    };
     debug!("Device: {dev:#?}");
 
     let dev_id = dev.id.clone();
@@ -150,15 +243,21 @@ async fn main() -> Result<()> {
             .await?;
     }
 
-    let ots = OtsClient::new(&bs, &dev_id, &config).await?;
+    let ots = if let Some(index) = args.service {
+        let services = OtsClient::services(&bs, &dev_id).await?;
+        let service = services.get(index).ok_or_else(|| Error::NoService)?;
+        info!("Service: {:?}", service.id);
+        OtsClient::with_service(&bs, &service.id, &config).await?
+    } else {
+        OtsClient::new(&bs, &dev_id, &config).await?
+    };
 
-    use cli::Action::*;
-    match args.action {
-        Info(args) => args.run(&ots).await?,
-        List(args) => args.run(&ots).await?,
-        Read(args) => args.run(&ots).await?,
-        Write(args) => args.run(&ots).await?,
-    }
+    args.action
+        .run(
+            &ots.map_gatt(|gatt| Transport::Bluez(gatt.into())),
+            args.format,
+        )
+        .await?;
 
     if !connected {
         info!("Disconnect from device");
@@ -168,209 +267,332 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
-impl cli::InfoArgs {
-    pub async fn run(&self, ots: &OtsClient) -> Result<()> {
-        if self.action() {
-            println!("Object action features: {}", ots.action_features());
-        }
-        if self.list() {
-            println!("Object list features: {}", ots.list_features());
+impl cli::Action {
+    /// Run action using connected client
+    pub async fn run(self, ots: &Client, format: Format) -> Result<()> {
+        use cli::Action::*;
+        match self {
+            Info(args) => args.run(ots, format).await,
+            List(args) => args.run(ots, format).await,
+            Read(args) => args.run(ots, format).await,
+            Write(args) => args.run(ots, format).await,
+            Create(args) => args.run(ots, format).await,
+            Delete(args) => args.run(ots, format).await,
+            CheckSum(args) => args.run(ots, format).await,
+            Execute(args) => args.run(ots, format).await,
+            Abort => abort(ots, format).await,
+            Shell(args) => args.run(ots).await,
+            Tui(args) => args.run(ots).await,
+            Watch(args) => args.run(ots, format).await,
+            Sync(args) => args.run(ots, format).await,
+            Backup(args) => args.run(ots, format).await,
+            Restore(args) => args.run(ots, format).await,
+            // handled before connecting to device
+            Scan(_) => unreachable!(),
         }
-        Ok(())
     }
 }
 
-impl cli::ListArgs {
-    pub async fn run(&self, ots: &OtsClient) -> Result<()> {
-        self.print_header();
-
-        // try read special directory object first
-        if self.dir && ots.go_to(0).await.is_ok() {
-            match ots.read(0, None).await {
-                Ok(data) => {
-                    debug!("Directory data size: {}", data.len());
-                    return self.print_directory_data(&data, ots).await;
+impl cli::InfoArgs {
+    pub async fn run(&self, ots: &Client, format: Format) -> Result<()> {
+        match format {
+            Format::Table => {
+                if self.action() {
+                    println!("Object action features: {}", ots.action_features());
                 }
-                Err(_error) => {
-                    warn!("Unable to read directory data due to: {_error:?}");
+                if self.list() {
+                    println!("Object list features: {}", ots.list_features());
                 }
             }
-        }
+            _ => {
+                #[derive(Serialize)]
+                struct Info<'a> {
+                    #[serde(skip_serializing_if = "Option::is_none")]
+                    action_features: Option<&'a ActionFeature>,
+                    #[serde(skip_serializing_if = "Option::is_none")]
+                    list_features: Option<&'a ListFeature>,
+                }
 
-        self.print_directory_iter(ots).await
+                print_record(
+                    format,
+                    &Info {
+                        action_features: self.action().then(|| ots.action_features()),
+                        list_features: self.list().then(|| ots.list_features()),
+                    },
+                )?;
+            }
+        }
+        Ok(())
     }
+}
 
-    fn print_header(&self) {
-        print!("index");
-        if self.id() {
-            print!("\tid");
-        }
-        if self.name() {
-            print!("\tname");
-        }
-        if self.type_() {
-            print!("\ttype");
+/// Column of object list
+#[derive(Debug, Clone, Copy)]
+enum Column {
+    Index,
+    Id,
+    Name,
+    Type,
+    CurSize,
+    AllocSize,
+    CrtTime,
+    ModTime,
+    Props,
+}
+
+impl Column {
+    fn name(&self) -> &'static str {
+        match self {
+            Column::Index => "index",
+            Column::Id => "id",
+            Column::Name => "name",
+            Column::Type => "type",
+            Column::CurSize => "current_size",
+            Column::AllocSize => "allocated_size",
+            Column::CrtTime => "first_created",
+            Column::ModTime => "last_modified",
+            Column::Props => "properties",
         }
-        if self.any_size() {
-            print!("\tsize ");
-            if self.cur_size() {
-                print!("current");
-            }
-            if self.alloc_size() {
-                if self.cur_size() {
-                    print!("/");
-                }
-                print!("allocated");
-            }
+    }
+
+    fn value(&self, index: usize, meta: &Metadata) -> String {
+        fn opt<T: ToString>(value: &Option<T>) -> String {
+            value.as_ref().map(T::to_string).unwrap_or_default()
         }
-        if self.any_time() {
-            println!("\ttime ");
-            if self.crt_time() {
-                print!("created");
-            }
-            if self.mod_time() {
-                if self.mod_time() {
-                    print!("/");
-                }
-                print!("modified");
-            }
+
+        match self {
+            Column::Index => index.to_string(),
+            Column::Id => opt(&meta.id),
+            Column::Name => meta.name.clone(),
+            Column::Type => meta.type_.to_string(),
+            Column::CurSize => opt(&meta.current_size),
+            Column::AllocSize => opt(&meta.allocated_size),
+            Column::CrtTime => opt(&meta.first_created),
+            Column::ModTime => opt(&meta.last_modified),
+            Column::Props => meta.properties.to_string(),
         }
-        if self.props() {
-            print!("\tprops");
+    }
+
+    fn compare(&self, a: &Metadata, b: &Metadata) -> core::cmp::Ordering {
+        match self {
+            Column::Index => core::cmp::Ordering::Equal,
+            Column::Id => a.id.cmp(&b.id),
+            Column::Name => a.name.cmp(&b.name),
+            Column::Type => a.type_.cmp(&b.type_),
+            Column::CurSize => a.current_size.cmp(&b.current_size),
+            Column::AllocSize => a.allocated_size.cmp(&b.allocated_size),
+            Column::CrtTime => a.first_created.cmp(&b.first_created),
+            Column::ModTime => a.last_modified.cmp(&b.last_modified),
+            Column::Props => a.properties.bits().cmp(&b.properties.bits()),
         }
-        println!();
     }
+}
 
-    async fn print_directory_iter(&self, ots: &OtsClient) -> Result<()> {
-        ots.first().await?;
-        for index in 0.. {
-            print!("{index}");
-            if self.id() {
-                if let Some(id) = ots.id().await? {
-                    print!("\t{}", id);
-                }
-            }
-            if self.name() {
-                print!("\t{:?}", ots.name().await?);
-            }
-            if self.type_() {
-                print!("\t{}", ots.type_().await?);
-            }
-            if self.any_size() {
-                let size = ots.size().await?;
-                print!("\t");
-                if self.cur_size() {
-                    print!("{}", size.current);
-                }
-                if self.alloc_size() {
-                    if self.cur_size() {
-                        print!("/");
-                    }
-                    print!("{}", size.allocated);
-                }
-            }
-            if self.any_time() {
-                print!("\t");
-                if self.crt_time() {
-                    if let Some(dt) = ots.first_created().await? {
-                        print!("{dt}");
-                    }
-                }
-                if self.mod_time() {
-                    if let Some(dt) = ots.last_modified().await? {
-                        if self.crt_time() {
-                            print!("/");
-                        }
-                        print!("{dt}");
-                    }
-                }
-            }
-            if self.props() {
-                print!("\t{}", ots.properties().await?);
+impl cli::ListArgs {
+    pub async fn run(&self, ots: &Client, format: Format) -> Result<()> {
+        // JSON output always contains full metadata
+        let objects = self.objects(ots, format == Format::Json).await?;
+
+        if format == Format::Json {
+            #[derive(Serialize)]
+            struct Entry<'a> {
+                index: usize,
+                #[serde(flatten)]
+                metadata: &'a Metadata,
             }
-            println!();
 
-            if !ots.next().await? {
-                break;
+            print_json(
+                &objects
+                    .iter()
+                    .enumerate()
+                    .map(|(index, metadata)| Entry { index, metadata })
+                    .collect::<Vec<_>>(),
+            )
+        } else {
+            let columns = self.columns();
+            let mut records = Records::new(
+                columns
+                    .iter()
+                    .map(|column| column.name().to_string())
+                    .collect(),
+            );
+            for (index, meta) in objects.iter().enumerate() {
+                records.push(
+                    columns
+                        .iter()
+                        .map(|column| column.value(index, meta))
+                        .collect(),
+                );
             }
+            records.print(format)
         }
-        Ok(())
     }
 
-    async fn print_directory_data(
-        &self,
-        data: &[u8],
-        _ots: &bluez_async_ots::OtsClient,
-    ) -> Result<()> {
-        //println!("{}", HexDump(data));
-
-        for (index, ent) in DirEntries::from(data).enumerate() {
-            let meta = ent?;
+    fn columns(&self) -> Vec<Column> {
+        [
+            (true, Column::Index),
+            (self.id(), Column::Id),
+            (self.name(), Column::Name),
+            (self.type_(), Column::Type),
+            (self.cur_size(), Column::CurSize),
+            (self.alloc_size(), Column::AllocSize),
+            (self.crt_time(), Column::CrtTime),
+            (self.mod_time(), Column::ModTime),
+            (self.props(), Column::Props),
+        ]
+        .into_iter()
+        .filter_map(|(show, column)| show.then_some(column))
+        .collect()
+    }
 
-            /*
-            if self.cur_size() && meta.current_size.is_none()
-                || self.alloc_size() && meta.allocated_size.is_none()
-            {
-                // try fill sizes from meta
-                if let Some(id) = meta.id {
-                    ots.go_to(id).await?;
-                }
-                let size = ots.size().await?;
-                if meta.current_size.is_none() {
-                    meta.current_size = size.current.into();
-                }
-                if meta.allocated_size.is_none() {
-                    meta.allocated_size = size.allocated.into();
-                }
+    /// Get metadata of objects
+    async fn objects(&self, ots: &Client, full: bool) -> Result<Vec<Metadata>> {
+        // try read special directory object first
+        if self.dir {
+            if let Some(objects) = read_directory(ots).await? {
+                return Ok(objects);
             }
-            */
+        }
 
-            print!("{index}");
-            if self.id() {
-                if let Some(id) = &meta.id {
-                    print!("\t{}", id);
-                }
-            }
-            if self.name() {
-                print!("\t{:?}", meta.name);
-            }
-            if self.type_() {
-                print!("\t{}", meta.type_);
-            }
-            if self.any_size() {
-                print!("\t");
-                if self.cur_size() && meta.current_size.is_some() {
-                    print!("{}", meta.current_size.unwrap());
-                }
-                if self.alloc_size() && meta.allocated_size.is_some() {
-                    if self.cur_size() {
-                        print!("/");
-                    }
-                    print!("{}", meta.allocated_size.unwrap());
-                }
-            }
-            if self.any_time() {
-                print!("\t");
-            }
-            if self.props() {
-                print!("\t{}", meta.properties);
+        let mut objects = Vec::new();
+        ots.first().await?;
+        loop {
+            objects.push(if full {
+                ots.metadata().await?
+            } else {
+                self.metadata(ots).await?
+            });
+            if !ots.next().await? {
+                break;
             }
-            println!();
         }
-        Ok(())
+        Ok(objects)
     }
+
+    /// Read shown metadata of current object
+    async fn metadata(&self, ots: &Client) -> Result<Metadata> {
+        let size = if self.any_size() {
+            Some(ots.size().await?)
+        } else {
+            None
+        };
+        Ok(Metadata {
+            id: if self.id() { ots.id().await? } else { None },
+            name: if self.name() {
+                ots.name().await?
+            } else {
+                Default::default()
+            },
+            type_: if self.type_() {
+                ots.type_().await?
+            } else {
+                Default::default()
+            },
+            current_size: size.as_ref().map(|size| size.current as _),
+            allocated_size: size.as_ref().map(|size| size.allocated as _),
+            first_created: if self.crt_time() {
+                ots.first_created().await?
+            } else {
+                None
+            },
+            last_modified: if self.mod_time() {
+                ots.last_modified().await?
+            } else {
+                None
+            },
+            properties: if self.props() {
+                ots.properties().await?
+            } else {
+                Default::default()
+            },
+        })
+    }
+}
+
+/// Summary of data transfer
+#[derive(Serialize)]
+struct Transfer<'a> {
+    offset: usize,
+    length: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file: Option<&'a std::path::Path>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    data: Option<String>,
 }
 
 impl cli::ReadArgs {
-    pub async fn run(&self, ots: &OtsClient) -> Result<()> {
+    pub async fn run(&self, ots: &Client, format: Format) -> Result<()> {
+        use std::io::{IsTerminal, Write};
+
         self.object.select(ots).await?;
 
-        let data = ots.read(self.range.offset, self.range.length).await?;
+        if self.file.is_none() && self.output_format.is_none() && format != Format::Table {
+            // data is put into record
+            let data = ots.read(self.range.offset, self.range.length).await?;
+            return print_record(
+                format,
+                &Transfer {
+                    offset: self.range.offset,
+                    length: data.len(),
+                    file: None,
+                    data: Some(hex(&data)),
+                },
+            );
+        }
 
-        if let Some(file) = &self.file {
-            tokio::fs::write(file, data).await?;
+        let output_format = self.output_format.unwrap_or(
+            if self.file.is_some() || !std::io::stdout().is_terminal() {
+                cli::OutputFormat::Raw
+            } else {
+                cli::OutputFormat::Hexdump
+            },
+        );
+        let out: Box<dyn Write> = if let Some(file) = &self.file {
+            Box::new(std::io::BufWriter::new(std::fs::File::create(file)?))
         } else {
-            print!("{}", HexDump(&data));
+            Box::new(std::io::BufWriter::new(std::io::stdout()))
+        };
+
+        let size = ots.size().await?.current;
+        let length = self
+            .range
+            .length
+            .unwrap_or(size)
+            .min(size.saturating_sub(self.range.offset));
+        let mut encoder =
+            codec::Encoder::new(output_format, out, self.range.offset, length, &self.symbol)?;
+
+        let mut stream = ots
+            .read_stream(self.range.offset, Some(length))
+            .await?
+            .take(length as _);
+        let mut buffer = vec![0; 4096];
+        let mut total = 0;
+        loop {
+            let len = stream.read(&mut buffer).await?;
+            if len == 0 {
+                break;
+            }
+            encoder.push(&buffer[..len])?;
+            total += len;
+        }
+        encoder.finish()?;
+
+        if total < length {
+            return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+        }
+
+        // summary would be mixed with data written to stdout
+        if format != Format::Table && self.file.is_some() {
+            print_record(
+                format,
+                &Transfer {
+                    offset: self.range.offset,
+                    length: total,
+                    file: self.file.as_deref(),
+                    data: None,
+                },
+            )?;
         }
 
         Ok(())
@@ -378,44 +600,31 @@ impl cli::ReadArgs {
 }
 
 impl cli::WriteArgs {
-    pub async fn run(&self, ots: &OtsClient) -> Result<()> {
+    pub async fn run(&self, ots: &Client, format: Format) -> Result<()> {
         self.object.select(ots).await?;
 
-        let data = if let Some(file) = &self.file {
-            if file == std::path::Path::new("-") {
-                // read from stdin
-                let mut data = Vec::new();
-                tokio::io::stdin().read_to_end(&mut data).await?;
-                data
-            } else {
-                // read from file
-                tokio::fs::read(file).await?
-            }
+        let (input, default_format) = if let Some(file) = &self.file {
+            (read_file(file).await?, cli::InputFormat::Raw)
         } else {
             // read from stdin
             let mut data = Vec::new();
             tokio::io::stdin().read_to_end(&mut data).await?;
-            let chars = core::str::from_utf8(&data)?.chars();
-            let mut data = Vec::with_capacity(data.len() / 2);
-            let mut half = None;
-            for chr in chars {
-                if chr.is_whitespace() {
-                    // skip spaces
-                    continue;
-                }
-                if let Some(dig) = chr.to_digit(16) {
-                    let dig = dig as u8;
-                    if let Some(half) = half.take() {
-                        data.push(half | dig);
-                    } else {
-                        half = Some(dig << 4);
-                    }
-                } else {
-                    return Err(Error::HexError);
-                }
-            }
-            data
+            (data, cli::InputFormat::Hex)
         };
+        let image = codec::decode(
+            self.input_format.unwrap_or(default_format),
+            &input,
+            self.base,
+            self.fill,
+        )?;
+        for gap in &image.gaps {
+            eprintln!(
+                "Gap in image at {:#x}..{:#x} will be filled with {:#04x}",
+                gap.start, gap.end, self.fill
+            );
+        }
+        let offset = self.range.offset + image.offset;
+        let data = image.data;
 
         let data = if let Some(len) = self.range.length {
             if data.len() > len {
@@ -438,14 +647,161 @@ impl cli::WriteArgs {
             bluez_async_ots::WriteMode::default()
         };
 
-        ots.write(self.range.offset, data, mode).await?;
+        let written = ots.write(offset, data, mode).await?;
+
+        if format != Format::Table {
+            print_record(
+                format,
+                &Transfer {
+                    offset,
+                    length: written,
+                    file: self.file.as_deref(),
+                    data: None,
+                },
+            )?;
+        }
+
+        if written < data.len() {
+            return Err(Error::ShortWrite {
+                written,
+                length: data.len(),
+            });
+        }
 
         Ok(())
     }
 }
 
+/// Summary of object creation
+#[derive(Serialize)]
+struct Created {
+    id: Option<u64>,
+    #[serde(rename = "type")]
+    type_: uuid::Uuid,
+    size: usize,
+}
+
+impl cli::CreateArgs {
+    pub async fn run(&self, ots: &Client, format: Format) -> Result<()> {
+        ots.create(self.size, self.type_).await?;
+
+        // created object becomes current
+        print_record(
+            format,
+            &Created {
+                id: ots.id().await?,
+                type_: self.type_,
+                size: self.size,
+            },
+        )
+    }
+}
+
+/// Selected object
+#[derive(Serialize)]
+struct Selected {
+    id: Option<u64>,
+    name: String,
+}
+
+impl Selected {
+    async fn get(ots: &Client) -> Result<Self> {
+        Ok(Self {
+            id: ots.id().await?,
+            name: ots.name().await?,
+        })
+    }
+}
+
+impl cli::DeleteArgs {
+    pub async fn run(&self, ots: &Client, format: Format) -> Result<()> {
+        self.object.select(ots).await?;
+
+        let object = Selected::get(ots).await?;
+        ots.delete().await?;
+
+        print_record(format, &object)
+    }
+}
+
+impl cli::CheckSumArgs {
+    pub async fn run(&self, ots: &Client, format: Format) -> Result<()> {
+        #[derive(Serialize)]
+        struct CheckSum {
+            #[serde(flatten)]
+            object: Selected,
+            offset: usize,
+            length: usize,
+            checksum: String,
+        }
+
+        self.object.select(ots).await?;
+
+        let object = Selected::get(ots).await?;
+        let length = if let Some(length) = self.range.length {
+            length
+        } else {
+            (ots.size().await?.current as usize).saturating_sub(self.range.offset)
+        };
+        let value = ots.check_sum(self.range.offset, length).await?;
+
+        print_record(
+            format,
+            &CheckSum {
+                object,
+                offset: self.range.offset,
+                length,
+                checksum: format!("{value:08x}"),
+            },
+        )
+    }
+}
+
+impl cli::ExecuteArgs {
+    pub async fn run(&self, ots: &Client, format: Format) -> Result<()> {
+        #[derive(Serialize)]
+        struct Executed {
+            #[serde(flatten)]
+            object: Selected,
+            response: String,
+        }
+
+        self.object.select(ots).await?;
+
+        let param = if let Some(param) = &self.param {
+            parse_hex(param)?
+        } else if let Some(file) = &self.file {
+            read_file(file).await?
+        } else {
+            Vec::new()
+        };
+
+        let object = Selected::get(ots).await?;
+        let response = ots.execute(param).await?;
+
+        print_record(
+            format,
+            &Executed {
+                object,
+                response: hex(&response),
+            },
+        )
+    }
+}
+
+async fn abort(ots: &Client, format: Format) -> Result<()> {
+    #[derive(Serialize)]
+    struct Aborted {
+        aborted: bool,
+    }
+
+    ots.abort().await?;
+
+    print_record(format, &Aborted { aborted: true })
+}
+
 impl cli::ObjSel {
-    pub async fn select(&self, ots: &OtsClient) -> Result<()> {
+    pub async fn select(&self, ots: &Client) -> Result<()> {
         if let Some(req_index) = self.index {
             ots.first().await?;
             for _ in 0..req_index {
@@ -455,7 +811,7 @@ impl cli::ObjSel {
             }
         } else if let Some(req_id) = self.id {
             if !ots.go_to(req_id).await? {
-                return Err(Error::ObjIdError);
+                return Err(Error::NoObject);
             }
         } else if let Some(req_name) = &self.name {
             ots.first().await?;
@@ -474,6 +830,133 @@ impl cli::ObjSel {
     }
 }
 
+/// Start discovery on adapter or on all adapters
+async fn start_discovery(bs: &BluetoothSession, adapter_id: Option<&AdapterId>) -> Result<()> {
+    if let Some(id) = adapter_id {
+        info!("Start discovery on {id:?}");
+        bs.start_discovery_on_adapter(id).await?;
+    } else {
+        info!("Start discovery");
+        bs.start_discovery().await?;
+    }
+    Ok(())
+}
+
+/// Stop discovery on adapter or on all adapters
+async fn stop_discovery(bs: &BluetoothSession, adapter_id: Option<&AdapterId>) -> Result<()> {
+    if let Some(id) = adapter_id {
+        info!("Stop discovery on {id:?}");
+        bs.stop_discovery_on_adapter(id).await?;
+    } else {
+        info!("Stop discovery");
+        bs.stop_discovery().await?;
+    }
+    Ok(())
+}
+
+/// Discover devices for specified number of seconds
+async fn discover(bs: &BluetoothSession, adapter_id: Option<&AdapterId>, secs: u32) -> Result<()> {
+    start_discovery(bs, adapter_id).await?;
+    sleep(Duration::from_secs(secs as _)).await;
+    stop_discovery(bs, adapter_id).await
+}
+
+/// Read full metadata of objects in list order
+async fn read_objects(ots: &Client) -> Result<Vec<Metadata>> {
+    let mut objects = Vec::new();
+    match ots.first().await {
+        Ok(()) => {}
+        // empty list
+        Err(bluez_async_ots::Error::Core(CoreError::ListError(ListRc::NoObject))) => {
+            return Ok(objects)
+        }
+        Err(error) => return Err(error.into()),
+    }
+    loop {
+        objects.push(ots.metadata().await?);
+        if !ots.next().await? {
+            break;
+        }
+    }
+    Ok(objects)
+}
+
+/// Check that object name can be used as file name
+fn valid_name(name: &str) -> bool {
+    !name.is_empty() && name != "." && name != ".." && !name.contains(['/', '\0'])
+}
+
+/// Convert object time to system time
+///
+/// Object times are treated as UTC.
+fn system_time(time: DateTime) -> Option<std::time::SystemTime> {
+    let time = time::PrimitiveDateTime::try_from(time).ok()?;
+    Some(time.assume_utc().into())
+}
+
+/// Read metadata of objects from special directory object
+///
+/// Returns `None` when directory object cannot be read.
+async fn read_directory(ots: &Client) -> Result<Option<Vec<Metadata>>> {
+    if !matches!(ots.go_to(0).await, Ok(true)) {
+        return Ok(None);
+    }
+    match ots.read(0, None).await {
+        Ok(data) => {
+            debug!("Directory data size: {}", data.len());
+            Ok(Some(
+                DirEntries::from(data.as_slice()).collect::<core::result::Result<_, _>>()?,
+            ))
+        }
+        Err(_error) => {
+            warn!("Unable to read directory data due to: {_error:?}");
+            Ok(None)
+        }
+    }
+}
+
+/// Read binary data from file or stdin when file is `-`
+async fn read_file(file: &std::path::Path) -> Result<Vec<u8>> {
+    Ok(if file == std::path::Path::new("-") {
+        let mut data = Vec::new();
+        tokio::io::stdin().read_to_end(&mut data).await?;
+        data
+    } else {
+        tokio::fs::read(file).await?
+    })
+}
+
+/// Decode hex string skipping whitespaces
+fn parse_hex(chars: &str) -> Result<Vec<u8>> {
+    let mut data = Vec::with_capacity(chars.len() / 2);
+    let mut half = None;
+    for chr in chars.chars() {
+        if chr.is_whitespace() {
+            // skip spaces
+            continue;
+        }
+        if let Some(dig) = chr.to_digit(16) {
+            let dig = dig as u8;
+            if let Some(half) = half.take() {
+                data.push(half | dig);
+            } else {
+                half = Some(dig << 4);
+            }
+        } else {
+            return Err(Error::HexError);
+        }
+    }
+    if half.is_some() {
+        return Err(Error::HexError);
+    }
+    Ok(data)
+}
+
+/// Encode data to hex string
+fn hex(data: &[u8]) -> String {
+    data.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
 struct HexDump<T>(T);
 
 impl<T: AsRef<[u8]>> core::fmt::Display for HexDump<T> {
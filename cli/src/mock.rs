@@ -0,0 +1,201 @@
+//! Local test transport
+//!
+//! Counterpart of mock mode of `ots-server`: characteristics are accessed
+//! through application objects which server registers on session bus
+//! under `org.bluez` name, and object channels are connected to Unix
+//! sequential packet socket. Requests carry no device, so server handles
+//! them as requests of single default client.
+
+use bluez_async::BluetoothError;
+use bluez_async_ots::{Error as OtsError, Gatt, L2capStream, Result as OtsResult};
+use dbus::{
+    arg::{PropMap, RefArg},
+    message::SignalArgs,
+    nonblock::{
+        stdintf::org_freedesktop_dbus::{ObjectManager, PropertiesPropertiesChanged},
+        MsgMatch, Proxy, SyncConnection,
+    },
+    Path,
+};
+use futures_util::stream::{BoxStream, StreamExt};
+use socket2::{Domain, SockAddr, Socket, Type};
+use std::{
+    collections::HashMap,
+    path::{Path as FsPath, PathBuf},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use uuid::Uuid;
+
+#[cfg(all(feature = "log", not(feature = "tracing")))]
+use log::{debug, warn};
+
+#[cfg(feature = "tracing")]
+use tracing::{debug, warn};
+
+const SERVICE_NAME: &str = "org.bluez";
+const GATT_CHARACTERISTIC_INTERFACE: &str = "org.bluez.GattCharacteristic1";
+/// Default D-Bus path of server application
+const APP_PATH: &str = "/io/github/katyo/ots";
+const TIMEOUT: Duration = Duration::from_secs(30);
+/// MTU of object channels
+const MTU: usize = 512;
+
+/// Default socket of mock server
+pub const SOCKET_PATH: &str = "/tmp/ots-server.sock";
+
+/// GATT transport to mock server
+pub struct MockGatt {
+    conn: Arc<SyncConnection>,
+    chrs: HashMap<Uuid, Path<'static>>,
+    socket: PathBuf,
+    /// Signal matches of subscribed characteristics
+    matches: Mutex<HashMap<Uuid, MsgMatch>>,
+}
+
+impl core::fmt::Debug for MockGatt {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_struct("MockGatt")
+            .field("socket", &self.socket)
+            .finish()
+    }
+}
+
+impl MockGatt {
+    /// Connect to session bus and discover characteristics
+    ///
+    /// Must be called within Tokio runtime.
+    pub async fn connect(socket: &FsPath) -> OtsResult<Self> {
+        let (resource, conn) = dbus_tokio::connection::new_session_sync().map_err(dbus_err)?;
+        tokio::spawn(async {
+            let _error = resource.await;
+            warn!("Lost connection to D-Bus: {_error}");
+        });
+
+        let objects = Proxy::new(SERVICE_NAME, APP_PATH, TIMEOUT, conn.clone())
+            .get_managed_objects()
+            .await
+            .map_err(dbus_err)?;
+        let mut found: Vec<_> = objects
+            .into_iter()
+            .filter_map(|(path, ifaces)| {
+                let uuid = ifaces
+                    .get(GATT_CHARACTERISTIC_INTERFACE)?
+                    .get("UUID")?
+                    .0
+                    .as_str()?
+                    .parse()
+                    .ok()?;
+                Some((path, uuid))
+            })
+            .collect();
+        // first instance has lowest index
+        found.sort_by(|(a, _), (b, _)| a.len().cmp(&b.len()).then_with(|| a.cmp(b)));
+
+        let mut chrs = HashMap::new();
+        for (path, uuid) in found {
+            chrs.entry(uuid).or_insert(path);
+        }
+        debug!("Characteristics: {chrs:#?}");
+
+        Ok(Self {
+            conn,
+            chrs,
+            socket: socket.into(),
+            matches: Default::default(),
+        })
+    }
+
+    fn proxy(&self, uuid: &Uuid) -> OtsResult<Proxy<'_, &SyncConnection>> {
+        let path = self.chrs.get(uuid).ok_or(OtsError::NotSupported)?;
+        Ok(Proxy::new(SERVICE_NAME, path.clone(), TIMEOUT, &*self.conn))
+    }
+}
+
+impl Gatt for MockGatt {
+    fn has_characteristic(&self, uuid: &Uuid) -> bool {
+        self.chrs.contains_key(uuid)
+    }
+
+    async fn read_characteristic(&self, uuid: &Uuid) -> OtsResult<Vec<u8>> {
+        let (value,): (Vec<u8>,) = self
+            .proxy(uuid)?
+            .method_call(
+                GATT_CHARACTERISTIC_INTERFACE,
+                "ReadValue",
+                (PropMap::new(),),
+            )
+            .await
+            .map_err(dbus_err)?;
+        Ok(value)
+    }
+
+    async fn write_characteristic(&self, uuid: &Uuid, value: Vec<u8>) -> OtsResult<()> {
+        self.proxy(uuid)?
+            .method_call(
+                GATT_CHARACTERISTIC_INTERFACE,
+                "WriteValue",
+                (value, PropMap::new()),
+            )
+            .await
+            .map_err(dbus_err)
+    }
+
+    async fn subscribe(&self, uuid: &Uuid) -> OtsResult<BoxStream<'static, Vec<u8>>> {
+        let proxy = self.proxy(uuid)?;
+        // signals come from unique name of server which is not matched
+        // against well-known name locally
+        let rule = PropertiesPropertiesChanged::match_rule(None, Some(&proxy.path)).static_clone();
+        let (msg_match, values) = self.conn.add_match(rule).await.map_err(dbus_err)?.stream();
+        let previous = self.matches.lock().unwrap().insert(*uuid, msg_match);
+        if let Some(msg_match) = previous {
+            self.conn
+                .remove_match(msg_match.token())
+                .await
+                .map_err(dbus_err)?;
+        }
+
+        proxy
+            .method_call::<(), _, _, _>(GATT_CHARACTERISTIC_INTERFACE, "StartNotify", ())
+            .await
+            .map_err(dbus_err)?;
+
+        Ok(values
+            .filter_map(|(_, changed): (_, PropertiesPropertiesChanged)| {
+                core::future::ready(
+                    changed
+                        .changed_properties
+                        .get("Value")
+                        .and_then(|value| dbus::arg::cast::<Vec<u8>>(&value.0).cloned()),
+                )
+            })
+            .boxed())
+    }
+
+    async fn unsubscribe(&self, uuid: &Uuid) -> OtsResult<()> {
+        self.proxy(uuid)?
+            .method_call::<(), _, _, _>(GATT_CHARACTERISTIC_INTERFACE, "StopNotify", ())
+            .await
+            .map_err(dbus_err)?;
+        let msg_match = self.matches.lock().unwrap().remove(uuid);
+        if let Some(msg_match) = msg_match {
+            self.conn
+                .remove_match(msg_match.token())
+                .await
+                .map_err(dbus_err)?;
+        }
+        Ok(())
+    }
+
+    async fn open_channel(&self) -> OtsResult<L2capStream> {
+        debug!("Connect to {}", self.socket.display());
+        let socket = Socket::new(Domain::UNIX, Type::SEQPACKET, None)?;
+        socket.connect(&SockAddr::unix(&self.socket)?)?;
+        let socket = ots_core::l2cap::L2capSocket::from(socket);
+        Ok(L2capStream::with_mtu(socket.into(), MTU, MTU)?)
+    }
+}
+
+fn dbus_err(error: dbus::Error) -> OtsError {
+    BluetoothError::from(error).into()
+}
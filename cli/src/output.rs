@@ -0,0 +1,215 @@
+//! Output formatting
+//!
+//! Table format is meant for humans and may change, while JSON and CSV
+//! formats are stable so scripts may parse them.
+
+use crate::{cli::Format, Result};
+use serde::Serialize;
+use std::io::Write;
+
+/// Print value as JSON document
+pub fn print_json<T: Serialize + ?Sized>(value: &T) -> Result<()> {
+    let mut out = std::io::stdout().lock();
+    serde_json::to_writer_pretty(&mut out, value)?;
+    writeln!(out)?;
+    Ok(())
+}
+
+/// Print single record in selected format
+///
+/// Fields of serialized value are used as columns in table and CSV formats.
+pub fn print_record<T: Serialize>(format: Format, value: &T) -> Result<()> {
+    if format == Format::Json {
+        return print_json(value);
+    }
+    let (header, row) = fields(value)?;
+    let mut records = Records::new(header);
+    records.push(row);
+    records.print(format)
+}
+
+/// Get names and values of fields of serialized value
+fn fields<T: Serialize>(value: &T) -> Result<(Vec<String>, Vec<String>)> {
+    let mut header = Vec::new();
+    let mut row = Vec::new();
+    if let serde_json::Value::Object(fields) = serde_json::to_value(value)? {
+        for (name, value) in fields {
+            header.push(name);
+            row.push(match value {
+                serde_json::Value::Null => String::new(),
+                serde_json::Value::String(value) => value,
+                value => value.to_string(),
+            });
+        }
+    }
+    Ok((header, row))
+}
+
+/// Records printed as they come
+///
+/// Table and CSV formats print header before first record, while JSON
+/// format prints each record as single line document.
+pub struct RecordStream<W: Write = std::io::Stdout> {
+    format: Format,
+    out: Output<W>,
+    started: bool,
+}
+
+/// Writer of records
+enum Output<W: Write> {
+    Text(W),
+    Csv(Box<csv::Writer<W>>),
+}
+
+impl RecordStream {
+    /// Create stream of records in selected format
+    pub fn new(format: Format) -> Self {
+        Self::with_writer(format, std::io::stdout())
+    }
+}
+
+impl<W: Write> RecordStream<W> {
+    /// Create stream of records printed to writer
+    pub fn with_writer(format: Format, out: W) -> Self {
+        let out = match format {
+            Format::Csv => Output::Csv(csv::Writer::from_writer(out).into()),
+            _ => Output::Text(out),
+        };
+        Self {
+            format,
+            out,
+            started: false,
+        }
+    }
+
+    /// Print record
+    ///
+    /// Fields of serialized value are used as columns in table and CSV formats.
+    pub fn push<T: Serialize>(&mut self, value: &T) -> Result<()> {
+        match &mut self.out {
+            Output::Text(out) if self.format == Format::Json => {
+                serde_json::to_writer(&mut *out, value)?;
+                writeln!(out)?;
+                out.flush()?;
+            }
+            Output::Text(out) => {
+                let (header, row) = fields(value)?;
+                if !core::mem::replace(&mut self.started, true) {
+                    writeln!(out, "{}", header.join("\t"))?;
+                }
+                writeln!(out, "{}", row.join("\t"))?;
+                out.flush()?;
+            }
+            Output::Csv(writer) => {
+                let (header, row) = fields(value)?;
+                if !core::mem::replace(&mut self.started, true) {
+                    writer.write_record(&header)?;
+                }
+                writer.write_record(&row)?;
+                writer.flush()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Records with named columns
+///
+/// Printed as tab-separated text or CSV.
+pub struct Records {
+    header: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Records {
+    /// Create records with columns
+    pub fn new(header: Vec<String>) -> Self {
+        Self {
+            header,
+            rows: Vec::new(),
+        }
+    }
+
+    /// Add record
+    pub fn push(&mut self, row: Vec<String>) {
+        self.rows.push(row);
+    }
+
+    /// Print records in table or CSV format
+    pub fn print(&self, format: Format) -> Result<()> {
+        let mut out = std::io::stdout().lock();
+        if format == Format::Csv {
+            let mut writer = csv::Writer::from_writer(out);
+            writer.write_record(&self.header)?;
+            for row in &self.rows {
+                writer.write_record(row)?;
+            }
+            writer.flush()?;
+        } else {
+            writeln!(out, "{}", self.header.join("\t"))?;
+            for row in &self.rows {
+                writeln!(out, "{}", row.join("\t"))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn stream(format: Format, values: &[serde_json::Value]) -> String {
+        let mut out = Vec::new();
+        let mut stream = RecordStream::with_writer(format, &mut out);
+        for value in values {
+            stream.push(value).unwrap();
+        }
+        drop(stream);
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn record_fields() {
+        let (header, row) = fields(&json!({
+            "name": "log, \"old\"",
+            "id": 256,
+            "size": null,
+            "flags": ["read", "write"],
+            "ok": true,
+        }))
+        .unwrap();
+        assert_eq!(header, ["name", "id", "size", "flags", "ok"]);
+        assert_eq!(
+            row,
+            ["log, \"old\"", "256", "", "[\"read\",\"write\"]", "true"]
+        );
+
+        // not a structure
+        let (header, row) = fields(&42).unwrap();
+        assert!(header.is_empty() && row.is_empty());
+    }
+
+    #[test]
+    fn record_stream() {
+        let values = [
+            json!({ "name": "a, b", "id": 256, "size": null }),
+            json!({ "name": "c\"d", "id": null, "size": 10 }),
+        ];
+        assert_eq!(
+            stream(Format::Csv, &values),
+            "name,id,size\n\"a, b\",256,\n\"c\"\"d\",,10\n"
+        );
+        assert_eq!(
+            stream(Format::Json, &values),
+            "{\"name\":\"a, b\",\"id\":256,\"size\":null}\n\
+             {\"name\":\"c\\\"d\",\"id\":null,\"size\":10}\n"
+        );
+        assert_eq!(
+            stream(Format::Table, &values),
+            "name\tid\tsize\na, b\t256\t\nc\"d\t\t10\n"
+        );
+        assert_eq!(stream(Format::Csv, &[]), "");
+    }
+}
@@ -0,0 +1,139 @@
+//! Device discovery
+//!
+//! Devices which advertise Object Transfer service are marked, so the
+//! only such device in range can be used without giving its address.
+
+use crate::{cli::ScanArgs, output::RecordStream, start_discovery, stop_discovery, Result};
+use bluez_async::{AdapterId, BluetoothEvent, BluetoothSession, DeviceEvent, DeviceInfo};
+use core::time::Duration;
+use futures_util::{future, StreamExt};
+use ots_core::ids;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Discovered device
+#[derive(Serialize)]
+struct Found {
+    ots: bool,
+    address: String,
+    address_type: String,
+    rssi: Option<i16>,
+    name: Option<String>,
+}
+
+impl ScanArgs {
+    pub async fn run(
+        &self,
+        bs: &BluetoothSession,
+        adapter_id: Option<&AdapterId>,
+        format: crate::cli::Format,
+    ) -> Result<()> {
+        let mut events = bs.event_stream().await?;
+        start_discovery(bs, adapter_id).await?;
+
+        let mut out = RecordStream::new(format);
+        // last reported state of devices
+        let mut seen = HashMap::new();
+
+        for dev in bs.get_devices().await? {
+            if in_range(&dev) {
+                self.report(&mut out, &mut seen, adapter_id, &dev)?;
+            }
+        }
+
+        let timeout = async {
+            match self.timeout {
+                Some(secs) => tokio::time::sleep(Duration::from_secs(secs as _)).await,
+                None => future::pending().await,
+            }
+        };
+        tokio::pin!(timeout);
+        let interrupt = tokio::signal::ctrl_c();
+        tokio::pin!(interrupt);
+
+        let result = loop {
+            tokio::select! {
+                event = events.next() => {
+                    let Some(event) = event else {
+                        break Ok(());
+                    };
+                    let BluetoothEvent::Device {
+                        id,
+                        event:
+                            DeviceEvent::Discovered
+                            | DeviceEvent::Rssi { .. }
+                            | DeviceEvent::Services { .. },
+                    } = event
+                    else {
+                        continue;
+                    };
+                    // device may be removed meanwhile
+                    if let Ok(dev) = bs.get_device_info(&id).await {
+                        if let Err(error) = self.report(&mut out, &mut seen, adapter_id, &dev) {
+                            break Err(error);
+                        }
+                    }
+                }
+                _ = &mut timeout => break Ok(()),
+                _ = &mut interrupt => break Ok(()),
+            }
+        };
+
+        stop_discovery(bs, adapter_id).await?;
+        result
+    }
+
+    /// Print device when it is new or its name or services changed
+    fn report(
+        &self,
+        out: &mut RecordStream,
+        seen: &mut HashMap<String, (bool, Option<String>)>,
+        adapter_id: Option<&AdapterId>,
+        dev: &DeviceInfo,
+    ) -> Result<()> {
+        if adapter_id.is_some_and(|id| *id != dev.id.adapter()) {
+            return Ok(());
+        }
+        let ots = is_ots(dev);
+        if self.ots && !ots {
+            return Ok(());
+        }
+        let address = dev.mac_address.to_string();
+        let state = (ots, dev.name.clone());
+        if seen.get(&address) == Some(&state) {
+            return Ok(());
+        }
+        seen.insert(address.clone(), state);
+
+        out.push(&Found {
+            ots,
+            address,
+            address_type: dev.address_type.to_string(),
+            rssi: dev.rssi,
+            name: dev.name.clone(),
+        })
+    }
+}
+
+/// Get devices in range which advertise Object Transfer service
+pub async fn ots_devices(
+    bs: &BluetoothSession,
+    adapter_id: Option<&AdapterId>,
+) -> Result<Vec<DeviceInfo>> {
+    Ok(bs
+        .get_devices()
+        .await?
+        .into_iter()
+        .filter(|dev| adapter_id.is_none_or(|id| *id == dev.id.adapter()))
+        .filter(|dev| is_ots(dev) && in_range(dev))
+        .collect())
+}
+
+fn is_ots(dev: &DeviceInfo) -> bool {
+    dev.services.contains(&ids::service::object_transfer)
+}
+
+/// Check that device was seen during discovery or is connected
+fn in_range(dev: &DeviceInfo) -> bool {
+    dev.rssi.is_some() || dev.connected
+}
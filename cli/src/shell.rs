@@ -0,0 +1,372 @@
+//! Interactive shell
+//!
+//! Keeps client connected and runs ftp-like commands on objects.
+//! Commands act on current object unless other object is given either
+//! by name or by identifier prefixed with `#`.
+
+use crate::{
+    cli::ShellArgs, hex, parse_hex, read_file, transport::Client, valid_name, Error, HexDump,
+    Result,
+};
+use bluez_async_ots::{Metadata, WriteMode};
+use rustyline::{
+    completion::{Completer, Pair},
+    error::ReadlineError,
+    highlight::Highlighter,
+    hint::Hinter,
+    history::DefaultHistory,
+    validate::Validator,
+    Context, Editor, Helper,
+};
+use std::{
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+/// Shell commands with arguments and description
+const COMMANDS: &[(&str, &str, &str)] = &[
+    ("ls", "[-l]", "list objects"),
+    ("cd", "<object>", "select object"),
+    ("stat", "[object]", "show object metadata"),
+    ("get", "[object] [file]", "download object data to file"),
+    ("put", "<file> [object]", "upload file data to object"),
+    ("cat", "[object]", "dump object data"),
+    ("rm", "[object]", "delete object"),
+    ("mv", "[object] <name>", "rename object"),
+    ("sum", "[object]", "calculate checksum of object data"),
+    ("exec", "[hex]", "execute current object"),
+    ("help", "", "show commands"),
+    ("exit", "", "leave shell"),
+];
+
+/// Object names known from last listing
+#[derive(Default, Clone)]
+struct Names(Arc<Mutex<Vec<String>>>);
+
+impl Names {
+    fn set(&self, names: Vec<String>) {
+        *self.0.lock().unwrap_or_else(|error| error.into_inner()) = names;
+    }
+
+    fn matching(&self, prefix: &str) -> Vec<String> {
+        self.0
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+            .iter()
+            .filter(|name| name.starts_with(prefix))
+            .cloned()
+            .collect()
+    }
+}
+
+/// Completion of commands and object names
+struct ShellHelper {
+    names: Names,
+}
+
+impl Completer for ShellHelper {
+    type Candidate = Pair;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let line = &line[..pos];
+        let start = line.rfind(' ').map(|index| index + 1).unwrap_or(0);
+        let word = &line[start..];
+        let candidates = if start == 0 {
+            COMMANDS
+                .iter()
+                .filter(|(name, _, _)| name.starts_with(word))
+                .map(|(name, _, _)| Pair {
+                    display: name.to_string(),
+                    replacement: format!("{name} "),
+                })
+                .collect()
+        } else {
+            self.names
+                .matching(word)
+                .into_iter()
+                .map(|name| Pair {
+                    replacement: shell_words::quote(&name).into(),
+                    display: name,
+                })
+                .collect()
+        };
+        Ok((start, candidates))
+    }
+}
+
+impl Hinter for ShellHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ShellHelper {}
+
+impl Validator for ShellHelper {}
+
+impl Helper for ShellHelper {}
+
+/// Shell session
+struct Shell<'a> {
+    ots: &'a Client,
+    names: Names,
+}
+
+impl ShellArgs {
+    pub async fn run(&self, ots: &Client) -> Result<()> {
+        let names = Names::default();
+        let mut editor = Editor::<ShellHelper, DefaultHistory>::new().map_err(readline_error)?;
+        editor.set_helper(Some(ShellHelper {
+            names: names.clone(),
+        }));
+
+        let history = self.history.clone().or_else(|| {
+            std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".ots_history"))
+        });
+        if let Some(history) = &history {
+            let _ = editor.load_history(history);
+        }
+
+        let shell = Shell { ots, names };
+        // fill names for completion
+        if let Err(error) = shell.list(false, false).await {
+            eprintln!("Error: {error}");
+        }
+
+        loop {
+            let prompt = match shell.current().await {
+                Some(name) => format!("ots:{name}> "),
+                None => "ots> ".into(),
+            };
+            let line = match tokio::task::block_in_place(|| editor.readline(&prompt)) {
+                Ok(line) => line,
+                Err(ReadlineError::Interrupted) => continue,
+                Err(ReadlineError::Eof) => break,
+                Err(error) => return Err(readline_error(error)),
+            };
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let _ = editor.add_history_entry(line);
+
+            let words = match shell_words::split(line) {
+                Ok(words) => words,
+                Err(error) => {
+                    eprintln!("Error: {error}");
+                    continue;
+                }
+            };
+            let words = words.iter().map(String::as_str).collect::<Vec<_>>();
+            match shell.command(&words).await {
+                Ok(true) => {}
+                Ok(false) => break,
+                Err(error) => eprintln!("Error: {error}"),
+            }
+        }
+
+        if let Some(history) = &history {
+            let _ = editor.save_history(history);
+        }
+        Ok(())
+    }
+}
+
+impl Shell<'_> {
+    /// Run command
+    ///
+    /// Returns `false` when shell should exit.
+    async fn command(&self, words: &[&str]) -> Result<bool> {
+        match words {
+            ["exit" | "quit"] => return Ok(false),
+            ["help"] => {
+                for (name, args, about) in COMMANDS {
+                    println!("{:24}{about}", format!("{name} {args}"));
+                }
+                println!("Object is given by name or by identifier prefixed with '#'");
+            }
+            ["ls"] => self.list(true, false).await?,
+            ["ls", "-l"] => self.list(true, true).await?,
+            ["cd", object] => self.select(object).await?,
+            ["stat", object @ ..] => {
+                self.select_opt(object).await?;
+                let meta = self.ots.metadata().await?;
+                print_metadata(&meta);
+            }
+            ["get", object @ ..] => {
+                let (object, file) = match object {
+                    [] => (None, None),
+                    [object] => (Some(*object), None),
+                    [object, file] => (Some(*object), Some(*file)),
+                    _ => return Err(Error::UsageError("get [object] [file]")),
+                };
+                if let Some(object) = object {
+                    self.select(object).await?;
+                }
+                let file = match file {
+                    Some(file) => file.into(),
+                    None => {
+                        // name comes from device, so it must not point outside
+                        let name = self.ots.name().await?;
+                        if !valid_name(&name) {
+                            return Err(Error::BadFileName(name));
+                        }
+                        name
+                    }
+                };
+                let data = self.ots.read(0, None).await?;
+                tokio::fs::write(&file, &data).await?;
+                println!("{} bytes written to {file}", data.len());
+            }
+            ["put", file, object @ ..] => {
+                self.select_opt(object).await?;
+                let data = read_file(file.as_ref()).await?;
+                let written = self.ots.write(0, &data, WriteMode::Truncate).await?;
+                println!("{written} bytes of {file} written");
+                if written < data.len() {
+                    return Err(Error::ShortWrite {
+                        written,
+                        length: data.len(),
+                    });
+                }
+            }
+            ["cat", object @ ..] => {
+                self.select_opt(object).await?;
+                print!("{}", HexDump(self.ots.read(0, None).await?));
+            }
+            ["rm", object @ ..] => {
+                self.select_opt(object).await?;
+                self.ots.delete().await?;
+                self.list(false, false).await?;
+            }
+            ["mv", object @ .., name] => {
+                self.select_opt(object).await?;
+                self.ots.set_name(name).await?;
+                self.list(false, false).await?;
+            }
+            ["sum", object @ ..] => {
+                self.select_opt(object).await?;
+                let size = self.ots.size().await?;
+                let value = self.ots.check_sum(0, size.current).await?;
+                println!("{value:08x}");
+            }
+            ["exec", param @ ..] => {
+                let param = parse_hex(&param.concat())?;
+                println!("{}", hex(&self.ots.execute(param).await?));
+            }
+            [command, ..] => {
+                if let Some((name, args, _)) = COMMANDS.iter().find(|(name, _, _)| name == command)
+                {
+                    println!("Usage: {name} {args}");
+                } else {
+                    println!("Unknown command: {command}, type 'help' to show commands");
+                }
+            }
+            [] => {}
+        }
+        Ok(true)
+    }
+
+    /// Get name of current object
+    async fn current(&self) -> Option<String> {
+        self.ots.id().await.ok()?;
+        self.ots.name().await.ok()
+    }
+
+    /// List objects keeping current object selected
+    async fn list(&self, print: bool, long: bool) -> Result<()> {
+        let current = self.ots.id().await.ok().flatten();
+
+        let mut names = Vec::new();
+        self.ots.first().await?;
+        loop {
+            let name = if long {
+                let meta = self.ots.metadata().await?;
+                println!(
+                    "{:>14}  {:>10}  {:19}  {:24}  {}",
+                    meta.id.map(|id| format!("#{id:#x}")).unwrap_or_default(),
+                    opt(&meta.current_size),
+                    opt(&meta.last_modified),
+                    meta.properties.to_string(),
+                    meta.name,
+                );
+                meta.name
+            } else {
+                let name = self.ots.name().await?;
+                if print {
+                    println!("{name}");
+                }
+                name
+            };
+            names.push(name);
+            if !self.ots.next().await? {
+                break;
+            }
+        }
+        self.names.set(names);
+
+        if let Some(id) = current {
+            self.ots.go_to(id).await?;
+        }
+        Ok(())
+    }
+
+    /// Select object by name or `#` prefixed identifier
+    async fn select(&self, object: &str) -> Result<()> {
+        if let Some(id) = object.strip_prefix('#') {
+            let id = if let Some(hex) = id.strip_prefix("0x") {
+                u64::from_str_radix(hex, 16)
+            } else {
+                id.parse()
+            }
+            .map_err(|_| Error::ObjIdError)?;
+            if !self.ots.go_to(id).await? {
+                return Err(Error::NoObject);
+            }
+            return Ok(());
+        }
+        self.ots.first().await?;
+        loop {
+            if self.ots.name().await? == object {
+                return Ok(());
+            }
+            if !self.ots.next().await? {
+                return Err(Error::NoObject);
+            }
+        }
+    }
+
+    /// Select object when given
+    async fn select_opt(&self, object: &[&str]) -> Result<()> {
+        match object {
+            [] => Ok(()),
+            [object] => self.select(object).await,
+            _ => Err(Error::UsageError("single object expected")),
+        }
+    }
+}
+
+fn opt<T: ToString>(value: &Option<T>) -> String {
+    value.as_ref().map(T::to_string).unwrap_or_default()
+}
+
+fn print_metadata(meta: &Metadata) {
+    println!("id:             {}", opt(&meta.id));
+    println!("name:           {}", meta.name);
+    println!("type:           {}", meta.type_);
+    println!("current size:   {}", opt(&meta.current_size));
+    println!("allocated size: {}", opt(&meta.allocated_size));
+    println!("first created:  {}", opt(&meta.first_created));
+    println!("last modified:  {}", opt(&meta.last_modified));
+    println!("properties:     {}", meta.properties);
+}
+
+fn readline_error(error: ReadlineError) -> Error {
+    match error {
+        ReadlineError::Io(error) => error.into(),
+        error => std::io::Error::other(error).into(),
+    }
+}
@@ -0,0 +1,527 @@
+//! Directory synchronization
+//!
+//! Files of local directory are matched to objects by name. Sizes and
+//! modification times of both sides are recorded in state file after
+//! each synchronization, so next run knows which side has changed and
+//! which objects the files belong to.
+
+use crate::{
+    cli::{Format, SyncArgs, SyncMode},
+    output::RecordStream,
+    read_objects, system_time,
+    transport::Client,
+    valid_name, Error, Result,
+};
+use bluez_async_ots::{DateTime, Metadata, WriteMode};
+use ots_core::crc::crc32;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, path::Path};
+use time::{OffsetDateTime, PrimitiveDateTime};
+
+/// Default state file name in local directory
+const STATE_FILE: &str = ".ots-sync.json";
+
+/// Synchronization state
+#[derive(Serialize, Deserialize, Default)]
+struct State {
+    objects: BTreeMap<String, Entry>,
+}
+
+/// File and object state after last synchronization
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+struct Entry {
+    id: Option<u64>,
+    local: Stamp,
+    remote: Stamp,
+}
+
+/// Size and last modification time
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+struct Stamp {
+    size: usize,
+    modified: Option<DateTime>,
+}
+
+impl From<&Metadata> for Stamp {
+    fn from(meta: &Metadata) -> Self {
+        Self {
+            size: meta.current_size.unwrap_or_default(),
+            modified: meta.last_modified.clone(),
+        }
+    }
+}
+
+/// File and object with same name
+struct Item {
+    name: String,
+    local: Option<Stamp>,
+    remote: Option<Metadata>,
+    state: Option<Entry>,
+}
+
+impl Item {
+    /// Whether file and object changed since last synchronization
+    fn dirty(&self) -> (bool, bool) {
+        let state = self.state.as_ref();
+        (
+            self.local.as_ref() != state.map(|entry| &entry.local),
+            self.remote.as_ref().map(Stamp::from).as_ref() != state.map(|entry| &entry.remote),
+        )
+    }
+}
+
+/// Synchronization step
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    /// Write object data to file
+    Download,
+    /// Write file data to object creating it when missing
+    Upload,
+    /// Delete file
+    DeleteLocal,
+    /// Delete object
+    DeleteRemote,
+    /// Record state of equal file and object
+    Record,
+    /// Forget state of removed file and object
+    Forget,
+}
+
+impl Step {
+    fn name(&self) -> &'static str {
+        match self {
+            Step::Download => "download",
+            Step::Upload => "upload",
+            Step::DeleteLocal => "delete-local",
+            Step::DeleteRemote => "delete-remote",
+            Step::Record => "record",
+            Step::Forget => "forget",
+        }
+    }
+}
+
+/// Printed step
+#[derive(Serialize)]
+struct Planned<'a> {
+    step: &'static str,
+    name: &'a str,
+    id: Option<u64>,
+    size: Option<usize>,
+}
+
+impl SyncArgs {
+    pub async fn run(&self, ots: &Client, format: Format) -> Result<()> {
+        let state_path = self
+            .state
+            .clone()
+            .unwrap_or_else(|| self.dir.join(STATE_FILE));
+        let mut state = load_state(&state_path).await?;
+
+        let items = self.items(ots, &state_path, &state).await?;
+        let mut out = RecordStream::new(format);
+
+        for item in items {
+            let Some(step) = self.step(ots, &item).await? else {
+                continue;
+            };
+            if !matches!(step, Step::Record | Step::Forget) {
+                let remote = item.remote.as_ref();
+                out.push(&Planned {
+                    step: step.name(),
+                    name: &item.name,
+                    id: remote.and_then(|meta| meta.id),
+                    size: match step {
+                        Step::Upload | Step::DeleteLocal => item.local.as_ref().map(|l| l.size),
+                        _ => remote.and_then(|meta| meta.current_size),
+                    },
+                })?;
+            }
+            if self.dry_run {
+                continue;
+            }
+            match self.apply(ots, &item, step).await? {
+                Some(entry) => state.objects.insert(item.name, entry),
+                None => state.objects.remove(&item.name),
+            };
+            // keep state consistent when interrupted
+            save_state(&state_path, &state).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Collect files, objects and recorded state by name
+    async fn items(&self, ots: &Client, state_path: &Path, state: &State) -> Result<Vec<Item>> {
+        let mut items = BTreeMap::new();
+        let item = |name: &str| Item {
+            name: name.into(),
+            local: None,
+            remote: None,
+            state: state.objects.get(name).cloned(),
+        };
+
+        let mut entries = tokio::fs::read_dir(&self.dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            if entry.path() == state_path || !entry.file_type().await?.is_file() {
+                continue;
+            }
+            let Some(name) = entry.file_name().to_str().map(String::from) else {
+                continue;
+            };
+            let stamp = local_stamp(&entry.path()).await?;
+            items
+                .entry(name.clone())
+                .or_insert_with(|| item(&name))
+                .local = Some(stamp);
+        }
+
+        for meta in read_objects(ots).await? {
+            // skip directory listing object and names unsafe as file names
+            if meta.id == Some(0) || !valid_name(&meta.name) {
+                continue;
+            }
+            let name = meta.name.clone();
+            let item = items.entry(name.clone()).or_insert_with(|| item(&name));
+            // first object wins when names duplicated
+            if item.remote.is_none() {
+                item.remote = Some(meta);
+            }
+        }
+
+        for name in state.objects.keys() {
+            items.entry(name.clone()).or_insert_with(|| item(name));
+        }
+
+        Ok(items.into_values().collect())
+    }
+
+    /// Compare file and object and choose step for them
+    async fn step(&self, ots: &Client, item: &Item) -> Result<Option<Step>> {
+        let same = match (&item.local, &item.remote) {
+            (Some(local), Some(remote)) if item.dirty() != (false, false) => {
+                self.same(ots, item, local, &remote.into()).await?
+            }
+            _ => false,
+        };
+        Ok(self.plan(item, same))
+    }
+
+    /// Choose step for file and object
+    ///
+    /// Whether file and object have same data is only used when both exist.
+    fn plan(&self, item: &Item, same: bool) -> Option<Step> {
+        let (local_dirty, remote_dirty) = item.dirty();
+        if !local_dirty && !remote_dirty {
+            return None;
+        }
+
+        let step = match (&item.local, &item.remote) {
+            (Some(local), Some(remote)) => {
+                if same {
+                    Step::Record
+                } else {
+                    match (self.mode, local_dirty, remote_dirty) {
+                        (SyncMode::Pull, ..) => Step::Download,
+                        (SyncMode::Push, ..) => Step::Upload,
+                        (SyncMode::Mirror, true, false) => Step::Upload,
+                        (SyncMode::Mirror, false, true) => Step::Download,
+                        (SyncMode::Mirror, ..) if local.modified > remote.last_modified => {
+                            Step::Upload
+                        }
+                        (SyncMode::Mirror, ..) => Step::Download,
+                    }
+                }
+            }
+            (Some(_), None) => match self.mode {
+                SyncMode::Pull if self.delete => Step::DeleteLocal,
+                SyncMode::Pull => return None,
+                // object deleted after last synchronization
+                SyncMode::Mirror if self.delete && item.state.is_some() && !local_dirty => {
+                    Step::DeleteLocal
+                }
+                _ => Step::Upload,
+            },
+            (None, Some(_)) => match self.mode {
+                SyncMode::Push if self.delete => Step::DeleteRemote,
+                SyncMode::Push => return None,
+                // file deleted after last synchronization
+                SyncMode::Mirror if self.delete && item.state.is_some() && !remote_dirty => {
+                    Step::DeleteRemote
+                }
+                _ => Step::Download,
+            },
+            (None, None) => Step::Forget,
+        };
+        Some(step)
+    }
+
+    /// Check that file and object have same data
+    async fn same(&self, ots: &Client, item: &Item, local: &Stamp, remote: &Stamp) -> Result<bool> {
+        if local.size != remote.size {
+            return Ok(false);
+        }
+        if self.checksum {
+            select(ots, item).await?;
+            let remote = ots.check_sum(0, remote.size).await?;
+            let local = crc32(&tokio::fs::read(self.dir.join(&item.name)).await?);
+            return Ok(remote == local);
+        }
+        Ok(local.modified.is_some() && local.modified == remote.modified)
+    }
+
+    /// Do step and get new state
+    async fn apply(&self, ots: &Client, item: &Item, step: Step) -> Result<Option<Entry>> {
+        let path = self.dir.join(&item.name);
+        let meta = match step {
+            Step::Download => {
+                select(ots, item).await?;
+                let data = ots.read(0, None).await?;
+                tokio::fs::write(&path, &data).await?;
+                let meta = ots.metadata().await?;
+                if let Some(modified) = meta.last_modified.clone().and_then(system_time) {
+                    let file = std::fs::File::options().write(true).open(&path)?;
+                    file.set_modified(modified)?;
+                }
+                meta
+            }
+            Step::Upload => {
+                let data = tokio::fs::read(&path).await?;
+                let remote = item.remote.as_ref();
+                if remote.is_some() {
+                    select(ots, item).await?;
+                }
+                // data is not written beyond allocated size, so too small
+                // object is replaced
+                let replace = remote.is_some() && ots.size().await?.allocated < data.len();
+                if replace {
+                    ots.delete().await?;
+                }
+                if remote.is_none() || replace {
+                    let type_ = remote.map(|meta| meta.type_).unwrap_or(self.type_);
+                    ots.create(data.len(), type_).await?;
+                    ots.set_name(&item.name).await?;
+                }
+                let written = ots.write(0, &data, WriteMode::Truncate).await?;
+                if written < data.len() {
+                    return Err(Error::ShortWrite {
+                        written,
+                        length: data.len(),
+                    });
+                }
+                ots.metadata().await?
+            }
+            Step::DeleteLocal => {
+                tokio::fs::remove_file(&path).await?;
+                return Ok(None);
+            }
+            Step::DeleteRemote => {
+                select(ots, item).await?;
+                ots.delete().await?;
+                return Ok(None);
+            }
+            Step::Record => item.remote.clone().ok_or(Error::NoObject)?,
+            Step::Forget => return Ok(None),
+        };
+        Ok(Some(Entry {
+            id: meta.id,
+            local: local_stamp(&path).await?,
+            remote: Stamp::from(&meta),
+        }))
+    }
+}
+
+/// Select object of item
+async fn select(ots: &Client, item: &Item) -> Result<()> {
+    let meta = item.remote.as_ref().ok_or(Error::NoObject)?;
+    if let Some(id) = meta.id {
+        if !ots.go_to(id).await? {
+            return Err(Error::NoObject);
+        }
+        return Ok(());
+    }
+    ots.first().await?;
+    while ots.name().await? != item.name {
+        if !ots.next().await? {
+            return Err(Error::NoObject);
+        }
+    }
+    Ok(())
+}
+
+async fn local_stamp(path: &Path) -> Result<Stamp> {
+    let meta = tokio::fs::metadata(path).await?;
+    let modified = meta.modified().ok().map(|time| {
+        let time = OffsetDateTime::from(time);
+        DateTime::from(PrimitiveDateTime::new(time.date(), time.time()))
+    });
+    Ok(Stamp {
+        size: meta.len() as _,
+        modified,
+    })
+}
+
+async fn load_state(path: &Path) -> Result<State> {
+    match tokio::fs::read(path).await {
+        Ok(data) => Ok(serde_json::from_slice(&data)?),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(State::default()),
+        Err(error) => Err(error.into()),
+    }
+}
+
+async fn save_state(path: &Path, state: &State) -> Result<()> {
+    tokio::fs::write(path, serde_json::to_vec_pretty(state)?).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bluez_async_ots::Property;
+    use clap::Parser;
+    use Step::*;
+
+    fn args(mode: &str, delete: bool) -> SyncArgs {
+        let delete = delete.then_some("--delete");
+        SyncArgs::try_parse_from(["sync", "dir", "--mode", mode].into_iter().chain(delete)).unwrap()
+    }
+
+    fn stamp(size: usize, minute: u8) -> Stamp {
+        Stamp {
+            size,
+            modified: Some(DateTime {
+                year: 2024,
+                month: 1,
+                day: 1,
+                hour: 0,
+                minute,
+                second: 0,
+            }),
+        }
+    }
+
+    fn item(local: Option<Stamp>, remote: Option<Stamp>, state: Option<(Stamp, Stamp)>) -> Item {
+        Item {
+            name: "file".into(),
+            local,
+            remote: remote.map(|stamp| Metadata {
+                id: Some(0x100),
+                name: "file".into(),
+                type_: Default::default(),
+                current_size: Some(stamp.size),
+                allocated_size: Some(stamp.size),
+                first_created: None,
+                last_modified: stamp.modified,
+                properties: Property::default(),
+            }),
+            state: state.map(|(local, remote)| Entry {
+                id: Some(0x100),
+                local,
+                remote,
+            }),
+        }
+    }
+
+    /// Planned steps in pull, push and mirror modes
+    fn plans(item: &Item, delete: bool, same: bool) -> [Option<Step>; 3] {
+        ["pull", "push", "mirror"].map(|mode| args(mode, delete).plan(item, same))
+    }
+
+    #[test]
+    fn plan_both_sides() {
+        let synced = Some((stamp(1, 0), stamp(1, 0)));
+        let clean = item(Some(stamp(1, 0)), Some(stamp(1, 0)), synced.clone());
+        assert_eq!(plans(&clean, true, false), [None; 3]);
+
+        let local_dirty = item(Some(stamp(2, 1)), Some(stamp(1, 0)), synced.clone());
+        assert_eq!(
+            plans(&local_dirty, false, false),
+            [Some(Download), Some(Upload), Some(Upload)]
+        );
+        assert_eq!(plans(&local_dirty, false, true), [Some(Record); 3]);
+
+        let remote_dirty = item(Some(stamp(1, 0)), Some(stamp(2, 1)), synced.clone());
+        assert_eq!(
+            plans(&remote_dirty, false, false),
+            [Some(Download), Some(Upload), Some(Download)]
+        );
+
+        // newer side wins
+        let local_newer = item(Some(stamp(2, 2)), Some(stamp(3, 1)), synced.clone());
+        assert_eq!(
+            plans(&local_newer, false, false),
+            [Some(Download), Some(Upload), Some(Upload)]
+        );
+        let remote_newer = item(Some(stamp(2, 1)), Some(stamp(3, 2)), synced);
+        assert_eq!(
+            plans(&remote_newer, false, false),
+            [Some(Download), Some(Upload), Some(Download)]
+        );
+
+        // not synchronized yet
+        let new = item(Some(stamp(1, 0)), Some(stamp(1, 0)), None);
+        assert_eq!(plans(&new, false, true), [Some(Record); 3]);
+    }
+
+    #[test]
+    fn plan_local_only() {
+        let new = item(Some(stamp(1, 0)), None, None);
+        assert_eq!(
+            plans(&new, false, false),
+            [None, Some(Upload), Some(Upload)]
+        );
+        assert_eq!(
+            plans(&new, true, false),
+            [Some(DeleteLocal), Some(Upload), Some(Upload)]
+        );
+
+        let deleted = item(Some(stamp(1, 0)), None, Some((stamp(1, 0), stamp(1, 0))));
+        assert_eq!(
+            plans(&deleted, false, false),
+            [None, Some(Upload), Some(Upload)]
+        );
+        assert_eq!(
+            plans(&deleted, true, false),
+            [Some(DeleteLocal), Some(Upload), Some(DeleteLocal)]
+        );
+
+        // file changed after object deleted
+        let changed = item(Some(stamp(2, 1)), None, Some((stamp(1, 0), stamp(1, 0))));
+        assert_eq!(
+            plans(&changed, true, false),
+            [Some(DeleteLocal), Some(Upload), Some(Upload)]
+        );
+    }
+
+    #[test]
+    fn plan_remote_only() {
+        let new = item(None, Some(stamp(1, 0)), None);
+        assert_eq!(
+            plans(&new, false, false),
+            [Some(Download), None, Some(Download)]
+        );
+        assert_eq!(
+            plans(&new, true, false),
+            [Some(Download), Some(DeleteRemote), Some(Download)]
+        );
+
+        let deleted = item(None, Some(stamp(1, 0)), Some((stamp(1, 0), stamp(1, 0))));
+        assert_eq!(
+            plans(&deleted, true, false),
+            [Some(Download), Some(DeleteRemote), Some(DeleteRemote)]
+        );
+
+        // object changed after file deleted
+        let changed = item(None, Some(stamp(2, 1)), Some((stamp(1, 0), stamp(1, 0))));
+        assert_eq!(
+            plans(&changed, true, false),
+            [Some(Download), Some(DeleteRemote), Some(Download)]
+        );
+    }
+
+    #[test]
+    fn plan_forget() {
+        let removed = item(None, None, Some((stamp(1, 0), stamp(1, 0))));
+        assert_eq!(plans(&removed, false, false), [Some(Forget); 3]);
+        assert_eq!(plans(&item(None, None, None), false, false), [None; 3]);
+    }
+}
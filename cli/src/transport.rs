@@ -0,0 +1,62 @@
+//! Transports of client
+
+use crate::mock::MockGatt;
+use bluez_async_ots::{BluezGatt, Gatt, L2capStream, OtsClient, Result};
+use futures_util::stream::BoxStream;
+use uuid::Uuid;
+
+/// OTS client used by commands
+pub type Client = OtsClient<Transport>;
+
+/// GATT transport selected by arguments
+#[derive(Debug)]
+pub enum Transport {
+    /// Device connected through BlueZ
+    Bluez(Box<BluezGatt>),
+    /// Local mock server
+    Mock(MockGatt),
+}
+
+impl Gatt for Transport {
+    fn has_characteristic(&self, uuid: &Uuid) -> bool {
+        match self {
+            Self::Bluez(gatt) => gatt.has_characteristic(uuid),
+            Self::Mock(gatt) => gatt.has_characteristic(uuid),
+        }
+    }
+
+    async fn read_characteristic(&self, uuid: &Uuid) -> Result<Vec<u8>> {
+        match self {
+            Self::Bluez(gatt) => gatt.read_characteristic(uuid).await,
+            Self::Mock(gatt) => gatt.read_characteristic(uuid).await,
+        }
+    }
+
+    async fn write_characteristic(&self, uuid: &Uuid, value: Vec<u8>) -> Result<()> {
+        match self {
+            Self::Bluez(gatt) => gatt.write_characteristic(uuid, value).await,
+            Self::Mock(gatt) => gatt.write_characteristic(uuid, value).await,
+        }
+    }
+
+    async fn subscribe(&self, uuid: &Uuid) -> Result<BoxStream<'static, Vec<u8>>> {
+        match self {
+            Self::Bluez(gatt) => gatt.subscribe(uuid).await,
+            Self::Mock(gatt) => gatt.subscribe(uuid).await,
+        }
+    }
+
+    async fn unsubscribe(&self, uuid: &Uuid) -> Result<()> {
+        match self {
+            Self::Bluez(gatt) => gatt.unsubscribe(uuid).await,
+            Self::Mock(gatt) => gatt.unsubscribe(uuid).await,
+        }
+    }
+
+    async fn open_channel(&self) -> Result<L2capStream> {
+        match self {
+            Self::Bluez(gatt) => gatt.open_channel().await,
+            Self::Mock(gatt) => gatt.open_channel().await,
+        }
+    }
+}
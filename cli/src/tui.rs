@@ -0,0 +1,475 @@
+//! Terminal user interface
+//!
+//! Full-screen object browser which shows objects in sortable table
+//! with metadata and hex preview of selected object. Object list is
+//! reloaded when server indicates object changes.
+
+use crate::{
+    cli::{type_name, ObjSel, TuiArgs},
+    read_directory, read_objects,
+    transport::Client,
+    Column, Error, HexDump, Result,
+};
+use bluez_async_ots::{Metadata, ObjectChanged, Property, WriteMode};
+use futures_util::stream::{self, StreamExt};
+use ots_core::crc::crc32;
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind},
+    layout::{Constraint, Layout},
+    style::{Style, Stylize},
+    widgets::{Block, Paragraph, Row, Table, TableState},
+    Frame,
+};
+use tokio::sync::mpsc;
+
+/// Columns of object table
+const COLUMNS: [Column; 8] = [
+    Column::Index,
+    Column::Name,
+    Column::Type,
+    Column::CurSize,
+    Column::AllocSize,
+    Column::CrtTime,
+    Column::ModTime,
+    Column::Props,
+];
+
+/// Number of first bytes of object to preview
+const PREVIEW_SIZE: usize = 128;
+
+/// Key bindings help
+const HELP: &str =
+    "q quit  1-8 sort  r reverse  R reload  d download  u upload  x delete  c checksum";
+
+/// Pending user input
+enum Input {
+    /// Path to download selected object to
+    Download(String),
+    /// Path to upload selected object from
+    Upload(String),
+    /// Confirmation of deletion
+    Delete,
+}
+
+/// Browser state
+#[derive(Default)]
+struct App {
+    /// Objects with indexes in list order
+    objects: Vec<(usize, Metadata)>,
+    table: TableState,
+    /// Index of sort column
+    sort: usize,
+    reverse: bool,
+    /// List index of object which preview belongs to
+    preview_of: Option<usize>,
+    preview: Option<core::result::Result<Vec<u8>, String>>,
+    input: Option<Input>,
+    status: String,
+}
+
+impl TuiArgs {
+    pub async fn run(&self, ots: &Client) -> Result<()> {
+        let mut app = App::default();
+
+        let mut changes = match ots.subscribe_changes().await {
+            Ok(changes) => changes,
+            Err(error) => {
+                app.status = format!("Live refresh unavailable: {error}");
+                stream::pending().boxed()
+            }
+        };
+
+        // terminal events are read by blocking call
+        let (sender, mut events) = mpsc::unbounded_channel();
+        std::thread::spawn(move || {
+            while let Ok(event) = event::read() {
+                if sender.send(event).is_err() {
+                    break;
+                }
+            }
+        });
+
+        if let Err(error) = app.reload(ots, self.dir).await {
+            app.status = format!("Error: {error}");
+        }
+
+        let mut terminal = ratatui::init();
+        let result = loop {
+            app.update_preview(ots).await;
+            if let Err(error) = terminal.draw(|frame| app.draw(frame)) {
+                break Err(error.into());
+            }
+            tokio::select! {
+                event = events.recv() => {
+                    let Some(event) = event else {
+                        break Ok(());
+                    };
+                    let Event::Key(key) = event else {
+                        continue;
+                    };
+                    if key.kind != KeyEventKind::Press {
+                        continue;
+                    }
+                    match app.handle(ots, key, self.dir).await {
+                        Ok(true) => {}
+                        Ok(false) => break Ok(()),
+                        Err(error) => app.status = format!("Error: {error}"),
+                    }
+                }
+                Some(changed) = changes.next() => {
+                    app.changed(&changed);
+                    if let Err(error) = app.reload(ots, self.dir).await {
+                        app.status = format!("Error: {error}");
+                    }
+                }
+            }
+        };
+        ratatui::restore();
+
+        let _ = ots.unsubscribe_changes().await;
+        result
+    }
+}
+
+impl App {
+    /// Get selected object
+    fn selected(&self) -> Option<&(usize, Metadata)> {
+        self.objects.get(self.table.selected()?)
+    }
+
+    /// Select object of table row
+    fn select(&mut self, row: Option<usize>) {
+        self.table.select(if self.objects.is_empty() {
+            None
+        } else {
+            row.map(|row| row.min(self.objects.len() - 1))
+        });
+    }
+
+    fn move_by(&mut self, delta: isize) {
+        let row = self.table.selected().unwrap_or_default();
+        self.select(Some(row.saturating_add_signed(delta)));
+    }
+
+    /// Read metadata of all objects keeping selection
+    async fn reload(&mut self, ots: &Client, dir: bool) -> Result<()> {
+        let selected = self
+            .selected()
+            .map(|(index, meta)| (*index, meta.id))
+            .or(self.table.selected().map(|row| (row, None)));
+
+        let objects = if dir {
+            read_directory(ots).await?
+        } else {
+            None
+        };
+        let objects = match objects {
+            Some(objects) => objects,
+            None => read_objects(ots).await?,
+        };
+
+        self.objects = objects.into_iter().enumerate().collect();
+        self.sort();
+        self.preview_of = None;
+
+        let row = selected.and_then(|(index, id)| {
+            self.objects
+                .iter()
+                .position(|(other_index, meta)| match id {
+                    Some(id) => meta.id == Some(id),
+                    None => *other_index == index,
+                })
+                .or(Some(index))
+        });
+        self.select(row.or(Some(0)));
+        Ok(())
+    }
+
+    /// Sort objects by selected column keeping selection
+    fn sort(&mut self) {
+        let selected = self.selected().map(|(index, _)| *index);
+
+        let column = COLUMNS[self.sort];
+        let reverse = self.reverse;
+        self.objects.sort_by(|(a_index, a), (b_index, b)| {
+            let order = column.compare(a, b).then(a_index.cmp(b_index));
+            if reverse {
+                order.reverse()
+            } else {
+                order
+            }
+        });
+
+        if let Some(index) = selected {
+            let row = self.objects.iter().position(|(other, _)| *other == index);
+            self.select(row);
+        }
+    }
+
+    fn changed(&mut self, changed: &ObjectChanged) {
+        self.status = format!("Object {:#x} changed: {}", changed.id, changed.flags);
+    }
+
+    /// Read first bytes of selected object when selection changed
+    async fn update_preview(&mut self, ots: &Client) {
+        let Some((index, meta)) = self.selected() else {
+            self.preview_of = None;
+            self.preview = None;
+            return;
+        };
+        if self.preview_of == Some(*index) {
+            return;
+        }
+        let index = *index;
+        let readable = meta.properties.contains(Property::Read);
+        let object = obj_sel(index, meta);
+
+        self.preview_of = Some(index);
+        self.preview = if readable {
+            Some(
+                preview(ots, &object)
+                    .await
+                    .map_err(|error| error.to_string()),
+            )
+        } else {
+            None
+        };
+    }
+
+    /// Handle key press
+    ///
+    /// Returns `false` when browser should exit.
+    async fn handle(&mut self, ots: &Client, key: KeyEvent, dir: bool) -> Result<bool> {
+        if let Some(input) = self.input.take() {
+            self.handle_input(ots, input, key, dir).await?;
+            return Ok(true);
+        }
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(false),
+            KeyCode::Down | KeyCode::Char('j') => self.move_by(1),
+            KeyCode::Up | KeyCode::Char('k') => self.move_by(-1),
+            KeyCode::PageDown => self.move_by(10),
+            KeyCode::PageUp => self.move_by(-10),
+            KeyCode::Home | KeyCode::Char('g') => self.select(Some(0)),
+            KeyCode::End | KeyCode::Char('G') => self.select(Some(usize::MAX)),
+            KeyCode::Char(chr @ '1'..='8') => {
+                let sort = chr as usize - '1' as usize;
+                if sort == self.sort {
+                    self.reverse = !self.reverse;
+                } else {
+                    self.sort = sort;
+                    self.reverse = false;
+                }
+                self.sort();
+            }
+            KeyCode::Char('r') => {
+                self.reverse = !self.reverse;
+                self.sort();
+            }
+            KeyCode::Char('R') | KeyCode::F(5) => {
+                self.reload(ots, dir).await?;
+                self.status = "Reloaded".into();
+            }
+            KeyCode::Char('d') => {
+                if let Some((_, meta)) = self.selected() {
+                    self.input = Some(Input::Download(meta.name.clone()));
+                }
+            }
+            KeyCode::Char('u') if self.selected().is_some() => {
+                self.input = Some(Input::Upload(String::new()));
+            }
+            KeyCode::Char('x') | KeyCode::Delete if self.selected().is_some() => {
+                self.input = Some(Input::Delete);
+            }
+            KeyCode::Char('c') => self.check_sum(ots).await?,
+            _ => {}
+        }
+        Ok(true)
+    }
+
+    async fn handle_input(
+        &mut self,
+        ots: &Client,
+        mut input: Input,
+        key: KeyEvent,
+        dir: bool,
+    ) -> Result<()> {
+        let Some((index, meta)) = self.selected() else {
+            return Ok(());
+        };
+        let object = obj_sel(*index, meta);
+
+        match (&mut input, key.code) {
+            (Input::Delete, KeyCode::Char('y')) => {
+                object.select(ots).await?;
+                ots.delete().await?;
+                self.reload(ots, dir).await?;
+                self.status = "Object deleted".into();
+            }
+            (_, KeyCode::Esc) | (Input::Delete, _) => self.status = "Cancelled".into(),
+            (Input::Download(path), KeyCode::Enter) => {
+                object.select(ots).await?;
+                let data = ots.read(0, None).await?;
+                tokio::fs::write(&path, &data).await?;
+                self.status = format!("{} bytes written to {path}", data.len());
+            }
+            (Input::Upload(path), KeyCode::Enter) => {
+                let data = tokio::fs::read(&path).await?;
+                object.select(ots).await?;
+                let written = ots.write(0, &data, WriteMode::Truncate).await?;
+                self.reload(ots, dir).await?;
+                if written < data.len() {
+                    return Err(Error::ShortWrite {
+                        written,
+                        length: data.len(),
+                    });
+                }
+                self.status = format!("{written} bytes of {path} written");
+            }
+            (Input::Download(path) | Input::Upload(path), code) => {
+                match code {
+                    KeyCode::Char(chr) => path.push(chr),
+                    KeyCode::Backspace => {
+                        path.pop();
+                    }
+                    _ => {}
+                }
+                self.input = Some(input);
+            }
+        }
+        Ok(())
+    }
+
+    /// Verify checksum calculated by server against object data
+    async fn check_sum(&mut self, ots: &Client) -> Result<()> {
+        let Some((index, meta)) = self.selected() else {
+            return Ok(());
+        };
+        obj_sel(*index, meta).select(ots).await?;
+
+        let size = ots.size().await?;
+        let remote = ots.check_sum(0, size.current).await?;
+        let local = crc32(&ots.read(0, None).await?);
+
+        self.status = if remote == local {
+            format!("Checksum {remote:08x} verified")
+        } else {
+            format!("Checksum mismatch: server {remote:08x}, data {local:08x}")
+        };
+        Ok(())
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [table_area, detail_area, status_area] = Layout::vertical([
+            Constraint::Min(5),
+            Constraint::Length(12),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        let [meta_area, preview_area] =
+            Layout::horizontal([Constraint::Length(48), Constraint::Min(20)]).areas(detail_area);
+
+        let header = Row::new(COLUMNS.iter().enumerate().map(|(index, column)| {
+            let mark = match (index == self.sort, self.reverse) {
+                (false, _) => "",
+                (true, false) => " ▲",
+                (true, true) => " ▼",
+            };
+            format!("{}{mark}", column.name().replace('_', " "))
+        }))
+        .style(Style::new().bold());
+
+        let rows = self.objects.iter().map(|(index, meta)| {
+            Row::new(COLUMNS.iter().map(|column| {
+                match column {
+                    Column::Type => type_name(&meta.type_)
+                        .map(String::from)
+                        .unwrap_or_else(|| meta.type_.to_string()),
+                    column => column.value(*index, meta),
+                }
+            }))
+        });
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(7),
+                Constraint::Fill(1),
+                Constraint::Length(12),
+                Constraint::Length(14),
+                Constraint::Length(16),
+                Constraint::Length(19),
+                Constraint::Length(19),
+                Constraint::Fill(1),
+            ],
+        )
+        .header(header)
+        .row_highlight_style(Style::new().reversed())
+        .block(Block::bordered().title(format!(" Objects ({}) ", self.objects.len())));
+        frame.render_stateful_widget(table, table_area, &mut self.table);
+
+        let meta = self
+            .selected()
+            .map(|(_, meta)| metadata_text(meta))
+            .unwrap_or_default();
+        frame.render_widget(
+            Paragraph::new(meta).block(Block::bordered().title(" Metadata ")),
+            meta_area,
+        );
+
+        let preview = match &self.preview {
+            Some(Ok(data)) => HexDump(data).to_string(),
+            Some(Err(error)) => format!("Error: {error}"),
+            None => String::new(),
+        };
+        frame.render_widget(
+            Paragraph::new(preview).block(Block::bordered().title(" Preview ")),
+            preview_area,
+        );
+
+        let status = match &self.input {
+            Some(Input::Download(path)) => format!("Download to: {path}_"),
+            Some(Input::Upload(path)) => format!("Upload from: {path}_"),
+            Some(Input::Delete) => "Delete selected object? (y/N)".into(),
+            None if self.status.is_empty() => HELP.into(),
+            None => format!("{}  |  {HELP}", self.status),
+        };
+        frame.render_widget(Paragraph::new(status), status_area);
+    }
+}
+
+/// Get selection of object by identifier or list index
+fn obj_sel(index: usize, meta: &Metadata) -> ObjSel {
+    ObjSel {
+        index: meta.id.is_none().then_some(index),
+        id: meta.id,
+        name: None,
+    }
+}
+
+/// Read first bytes of object
+async fn preview(ots: &Client, object: &ObjSel) -> Result<Vec<u8>> {
+    object.select(ots).await?;
+    let size = ots.size().await?.current;
+    Ok(ots.read(0, Some(size.min(PREVIEW_SIZE))).await?)
+}
+
+fn metadata_text(meta: &Metadata) -> String {
+    fn opt<T: ToString>(value: &Option<T>) -> String {
+        value.as_ref().map(T::to_string).unwrap_or_default()
+    }
+
+    [
+        ("id", opt(&meta.id)),
+        ("name", meta.name.clone()),
+        ("type", meta.type_.to_string()),
+        ("current size", opt(&meta.current_size)),
+        ("allocated size", opt(&meta.allocated_size)),
+        ("first created", opt(&meta.first_created)),
+        ("last modified", opt(&meta.last_modified)),
+        ("properties", meta.properties.to_string()),
+    ]
+    .iter()
+    .map(|(name, value)| format!("{name:15} {value}\n"))
+    .collect()
+}
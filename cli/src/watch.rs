@@ -0,0 +1,112 @@
+//! Object change watching
+//!
+//! Prints Object Changed indications and optionally runs hook command
+//! for each of them with event passed in environment variables:
+//!
+//! - `OTS_OBJECT_ID` identifier of changed object
+//! - `OTS_OBJECT_NAME` name of object (empty when unknown)
+//! - `OTS_CHANGE_FLAGS` space-separated change flags
+//! - `OTS_CHANGE_TIME` time when change received (RFC 3339)
+
+use crate::{
+    cli::{Format, WatchArgs},
+    output::RecordStream,
+    transport::Client,
+    Result,
+};
+use bluez_async_ots::{ChangeFlag, ObjectChanged};
+use futures_util::StreamExt;
+use serde::Serialize;
+use std::collections::HashMap;
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+
+/// Object change event
+#[derive(Serialize)]
+struct Event {
+    time: String,
+    id: u64,
+    name: Option<String>,
+    flags: String,
+}
+
+impl WatchArgs {
+    pub async fn run(&self, ots: &Client, format: Format) -> Result<()> {
+        let mut changes = ots.subscribe_changes().await?;
+        let mut out = RecordStream::new(format);
+        // names of objects seen, so deleted objects can be named
+        let mut names = HashMap::new();
+
+        let interrupt = tokio::signal::ctrl_c();
+        tokio::pin!(interrupt);
+
+        let result = loop {
+            let changed = tokio::select! {
+                changed = changes.next() => changed,
+                _ = &mut interrupt => None,
+            };
+            let Some(changed) = changed else {
+                break Ok(());
+            };
+            if let Err(error) = self.handle(ots, &mut out, &mut names, &changed).await {
+                break Err(error);
+            }
+        };
+
+        ots.unsubscribe_changes().await?;
+        result
+    }
+
+    async fn handle(
+        &self,
+        ots: &Client,
+        out: &mut RecordStream,
+        names: &mut HashMap<u64, String>,
+        changed: &ObjectChanged,
+    ) -> Result<()> {
+        let name = if changed.flags.contains(ChangeFlag::Delete) {
+            names.remove(&changed.id)
+        } else {
+            let name = resolve_name(ots, changed.id).await;
+            if let Some(name) = &name {
+                names.insert(changed.id, name.clone());
+            }
+            name
+        };
+
+        let event = Event {
+            time: OffsetDateTime::now_utc()
+                .format(&Rfc3339)
+                .unwrap_or_default(),
+            id: changed.id,
+            name,
+            flags: changed.flags.to_string(),
+        };
+        out.push(&event)?;
+
+        if let Some(command) = &self.exec {
+            let status = tokio::process::Command::new("sh")
+                .arg("-c")
+                .arg(command)
+                .env("OTS_OBJECT_ID", event.id.to_string())
+                .env("OTS_OBJECT_NAME", event.name.unwrap_or_default())
+                .env("OTS_CHANGE_FLAGS", event.flags)
+                .env("OTS_CHANGE_TIME", event.time)
+                .status()
+                .await?;
+            if !status.success() {
+                eprintln!("Hook failed: {status}");
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Get name of object by selecting it
+///
+/// Returns `None` when object cannot be selected.
+async fn resolve_name(ots: &Client, id: u64) -> Option<String> {
+    if !ots.go_to(id).await.ok()? {
+        return None;
+    }
+    ots.name().await.ok()
+}
@@ -0,0 +1,64 @@
+//! CRC-32 checksum
+//!
+//! The OACP Calculate Checksum procedure uses CRC-32 as defined by IEEE 802.3
+//! (reflected polynomial `0xEDB88320`, initial value and final xor `0xFFFFFFFF`).
+
+const POLY: u32 = 0xedb88320;
+
+const TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut index = 0;
+    while index < 256 {
+        let mut crc = index as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ POLY
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[index] = crc;
+        index += 1;
+    }
+    table
+};
+
+/// Incremental CRC-32 calculator
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Crc32 {
+    state: u32,
+}
+
+impl Default for Crc32 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Crc32 {
+    /// Create new calculator
+    pub const fn new() -> Self {
+        Self { state: !0 }
+    }
+
+    /// Feed data to calculator
+    pub fn update(&mut self, data: &[u8]) {
+        for byte in data {
+            self.state = TABLE[((self.state ^ *byte as u32) & 0xff) as usize] ^ (self.state >> 8);
+        }
+    }
+
+    /// Get checksum of fed data
+    pub fn finish(&self) -> u32 {
+        !self.state
+    }
+}
+
+/// Calculate CRC-32 checksum of data
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = Crc32::new();
+    crc.update(data);
+    crc.finish()
+}
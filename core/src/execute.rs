@@ -0,0 +1,90 @@
+//! Typed Execute commands
+//!
+//! Object Action Control Point Execute request and response carry opaque
+//! parameters which meaning depends on type of executed object. The
+//! [`ExecuteCommand`] trait binds object type with types of parameters,
+//! so clients and servers may share command definitions.
+
+use crate::{Error, Result};
+use uuid::Uuid;
+
+/// Encode Execute parameter
+pub trait ExecuteEncode {
+    /// Encode parameter to raw data
+    fn encode(&self) -> Vec<u8>;
+}
+
+/// Decode Execute parameter
+pub trait ExecuteDecode: Sized {
+    /// Decode parameter from raw data
+    fn decode(raw: &[u8]) -> Result<Self>;
+}
+
+/// Execute command
+pub trait ExecuteCommand {
+    /// Type of objects which can be executed by command
+    const OBJECT_TYPE: Uuid;
+    /// Parameter of request
+    type Param: ExecuteEncode + ExecuteDecode;
+    /// Parameter of response
+    type Response: ExecuteEncode + ExecuteDecode;
+}
+
+impl ExecuteEncode for () {
+    fn encode(&self) -> Vec<u8> {
+        Vec::new()
+    }
+}
+
+impl ExecuteDecode for () {
+    fn decode(raw: &[u8]) -> Result<Self> {
+        Error::check_len_exact(raw.len(), 0)
+    }
+}
+
+impl ExecuteEncode for Vec<u8> {
+    fn encode(&self) -> Vec<u8> {
+        self.clone()
+    }
+}
+
+impl ExecuteDecode for Vec<u8> {
+    fn decode(raw: &[u8]) -> Result<Self> {
+        Ok(raw.into())
+    }
+}
+
+impl ExecuteEncode for String {
+    fn encode(&self) -> Vec<u8> {
+        self.as_bytes().into()
+    }
+}
+
+impl ExecuteDecode for String {
+    fn decode(raw: &[u8]) -> Result<Self> {
+        Ok(core::str::from_utf8(raw)?.into())
+    }
+}
+
+macro_rules! impl_int {
+    ($($type:ty)*) => {
+        $(
+            impl ExecuteEncode for $type {
+                fn encode(&self) -> Vec<u8> {
+                    self.to_le_bytes().into()
+                }
+            }
+
+            impl ExecuteDecode for $type {
+                fn decode(raw: &[u8]) -> Result<Self> {
+                    Error::check_size_exact::<Self>(raw.len())?;
+                    let mut bytes = [0; core::mem::size_of::<Self>()];
+                    bytes.copy_from_slice(raw);
+                    Ok(Self::from_le_bytes(bytes))
+                }
+            }
+        )*
+    };
+}
+
+impl_int!(u8 u16 u32 u64 i8 i16 i32 i64);
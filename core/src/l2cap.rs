@@ -1,7 +1,7 @@
 use core::mem::{size_of, MaybeUninit};
 use std::{
     io::{Error, Result},
-    os::fd::{AsRawFd, RawFd},
+    os::fd::{AsFd, AsRawFd, BorrowedFd, RawFd},
 };
 
 pub use macaddr::MacAddr6 as MacAddress;
@@ -216,6 +216,12 @@ impl core::ops::DerefMut for L2capSocket {
     }
 }
 
+impl From<socket2::Socket> for L2capSocket {
+    fn from(inner: socket2::Socket) -> Self {
+        Self { inner }
+    }
+}
+
 impl L2capSocket {
     pub fn new(type_: SocketType) -> Result<Self> {
         let inner = socket2::Socket::new(
@@ -234,6 +240,19 @@ impl L2capSocket {
         self.inner.connect(&sockaddr.into())
     }
 
+    /// Mark socket as ready to accept incoming connections
+    pub fn listen(&self, backlog: i32) -> Result<()> {
+        self.inner.listen(backlog)
+    }
+
+    /// Accept incoming connection
+    ///
+    /// Returns connected socket and its peer address.
+    pub fn accept(&self) -> Result<(Self, L2capSockAddr)> {
+        let (inner, sockaddr) = self.inner.accept()?;
+        Ok((Self { inner }, sockaddr.try_into()?))
+    }
+
     pub fn set_nonblocking(&self, nonblocking: bool) -> Result<()> {
         self.inner.set_nonblocking(nonblocking)
     }
@@ -291,6 +310,12 @@ impl AsRawFd for L2capSocket {
     }
 }
 
+impl AsFd for L2capSocket {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.inner.as_fd()
+    }
+}
+
 fn getsockopt<T>(socket: &impl AsRawFd, level: libc::c_int, optname: libc::c_int) -> Result<T> {
     let mut optval: MaybeUninit<T> = MaybeUninit::uninit();
     let mut optlen: libc::socklen_t = size_of::<T>() as _;
@@ -2,8 +2,11 @@
 //#![deny(bad_style, missing_docs)]
 #![doc = include_str!("../README.md")]
 
+pub mod crc;
+pub mod execute;
 pub mod ids;
 pub mod l2cap;
+pub mod list;
 pub mod types;
 
 use types::{ActionRc, ListRc, OpType};
@@ -50,9 +53,15 @@ pub enum Error {
     /// Invalid properties received
     #[error("Invalid properties: {0:08x?}")]
     BadProperties(u32),
+    /// Invalid write mode flags received
+    #[error("Invalid write mode: {0:02x?}")]
+    BadWriteMode(u8),
     /// Invalid directory flags received
     #[error("Invalid directory flags: {0:02x?}")]
     BadDirFlags(u8),
+    /// Invalid object changed flags received
+    #[error("Invalid object changed flags: {0:02x?}")]
+    BadChangeFlags(u8),
     /// Not enough data to parse
     #[error("Not enough data ({actual} < {needed})")]
     NotEnoughData {
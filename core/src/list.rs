@@ -0,0 +1,127 @@
+//! Object list evaluation
+//!
+//! [`ObjectList`] is the view of objects which server exposes through
+//! Object List Control Point: objects are filtered by Object List Filters
+//! and sorted by requested order.
+
+use crate::types::{Filter, ListRc, Metadata, SortOrder};
+
+/// Filtered and sorted list of objects
+///
+/// Sorting is stable so objects which are equal by sort key keep the
+/// order in which they were passed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ObjectList {
+    objects: Vec<Metadata>,
+}
+
+impl ObjectList {
+    /// Create view of objects
+    ///
+    /// Object is included when it matches all filters.
+    pub fn new(
+        objects: impl IntoIterator<Item = Metadata>,
+        filters: &[Filter],
+        order: Option<SortOrder>,
+    ) -> Self {
+        let mut objects: Vec<_> = objects
+            .into_iter()
+            .filter(|object| filters.iter().all(|filter| filter.matches(object)))
+            .collect();
+        if let Some(order) = order {
+            sort(&mut objects, order);
+        }
+        Self { objects }
+    }
+
+    /// Number of objects in view
+    pub fn len(&self) -> usize {
+        self.objects.len()
+    }
+
+    /// Check that view is empty
+    pub fn is_empty(&self) -> bool {
+        self.objects.is_empty()
+    }
+
+    /// Objects of view
+    pub fn objects(&self) -> &[Metadata] {
+        &self.objects
+    }
+
+    /// Find position of object with identifier
+    pub fn position(&self, id: u64) -> Option<usize> {
+        self.objects.iter().position(|object| object.id == Some(id))
+    }
+
+    /// Get first object
+    pub fn first(&self) -> Result<&Metadata, ListRc> {
+        self.objects.first().ok_or(ListRc::NoObject)
+    }
+
+    /// Get last object
+    pub fn last(&self) -> Result<&Metadata, ListRc> {
+        self.objects.last().ok_or(ListRc::NoObject)
+    }
+
+    /// Get object before current one
+    pub fn previous(&self, current: Option<u64>) -> Result<&Metadata, ListRc> {
+        match self.current(current)? {
+            0 => Err(ListRc::OutOfBounds),
+            index => Ok(&self.objects[index - 1]),
+        }
+    }
+
+    /// Get object after current one
+    pub fn next(&self, current: Option<u64>) -> Result<&Metadata, ListRc> {
+        self.objects
+            .get(self.current(current)? + 1)
+            .ok_or(ListRc::OutOfBounds)
+    }
+
+    /// Get object with identifier
+    pub fn go_to(&self, id: u64) -> Result<&Metadata, ListRc> {
+        self.position(id)
+            .map(|index| &self.objects[index])
+            .ok_or(ListRc::ObjectIdNotFound)
+    }
+
+    /// Get number of objects
+    pub fn number_of(&self) -> Result<u32, ListRc> {
+        self.len().try_into().map_err(|_| ListRc::TooManyObjects)
+    }
+
+    fn current(&self, current: Option<u64>) -> Result<usize, ListRc> {
+        if self.is_empty() {
+            return Err(ListRc::NoObject);
+        }
+        // current object is missing or does not match filters
+        current
+            .and_then(|id| self.position(id))
+            .ok_or(ListRc::OperationFailed)
+    }
+}
+
+impl From<ObjectList> for Vec<Metadata> {
+    fn from(list: ObjectList) -> Self {
+        list.objects
+    }
+}
+
+fn sort(objects: &mut [Metadata], order: SortOrder) {
+    use core::cmp::Reverse;
+    use SortOrder::*;
+
+    match order {
+        NameAsc => objects.sort_by(|a, b| a.name.cmp(&b.name)),
+        TypeAsc => objects.sort_by_key(|object| object.type_),
+        CurSizeAsc => objects.sort_by_key(|object| object.current_size),
+        CrtTimeAsc => objects.sort_by(|a, b| a.first_created.cmp(&b.first_created)),
+        ModTimeAsc => objects.sort_by(|a, b| a.last_modified.cmp(&b.last_modified)),
+        NameDesc => objects.sort_by(|a, b| b.name.cmp(&a.name)),
+        TypeDesc => objects.sort_by_key(|object| Reverse(object.type_)),
+        CurSizeDesc => objects.sort_by_key(|object| Reverse(object.current_size)),
+        CrtTimeDesc => objects.sort_by(|a, b| b.first_created.cmp(&a.first_created)),
+        ModTimeDesc => objects.sort_by(|a, b| b.last_modified.cmp(&a.last_modified)),
+    }
+}
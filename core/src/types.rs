@@ -13,7 +13,7 @@ macro_rules! bitflag_types {
             $(
                 $(#[$($meta)*])*
                 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
-                #[cfg_attr(feature = "serde", derive(serde::Serialize))]
+                #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
                 #[repr(C)]
                 pub struct $type: $repr {
                     $(
@@ -49,6 +49,12 @@ macro_rules! bitflag_types {
                 }
             }
 
+            impl From<$type> for [u8; core::mem::size_of::<$repr>()] {
+                fn from(val: $type) -> Self {
+                    val.bits().to_le_bytes()
+                }
+            }
+
             impl core::fmt::Display for $type {
                 fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
                     let mut first = true;
@@ -129,6 +135,45 @@ bitflags! {
         const HasProperties = 1 << 5;
         const HasExtendedFlags = 1 << 7;
     }
+
+    /// Object changed flags
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+    #[repr(C)]
+    pub struct ChangeFlag: u8 {
+        /// Change is made by client (otherwise by server)
+        const Client = 1 << 0;
+        /// Object contents changed
+        const Contents = 1 << 1;
+        /// Object metadata changed
+        const Metadata = 1 << 2;
+        /// Object created
+        const Create = 1 << 3;
+        /// Object deleted
+        const Delete = 1 << 4;
+    }
+}
+
+impl core::fmt::Display for ChangeFlag {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        let names = [
+            (ChangeFlag::Client, "client"),
+            (ChangeFlag::Contents, "contents"),
+            (ChangeFlag::Metadata, "metadata"),
+            (ChangeFlag::Create, "create"),
+            (ChangeFlag::Delete, "delete"),
+        ];
+        let mut first = true;
+        for (flag, name) in names {
+            if self.contains(flag) {
+                if !first {
+                    ' '.fmt(f)?;
+                }
+                first = false;
+                name.fmt(f)?;
+            }
+        }
+        Ok(())
+    }
 }
 
 const UUID_BASE: u128 = 0x00000000_0000_1000_8000_00805f9b34fb;
@@ -146,6 +191,18 @@ pub fn uuid_from_raw(raw: &[u8]) -> Result<Uuid> {
     })
 }
 
+/// Encode UUID to raw data
+///
+/// UUIDs which derived from Bluetooth base UUID will be encoded to 16-bit form.
+pub fn uuid_to_raw(uuid: &Uuid) -> Vec<u8> {
+    let val = uuid.as_u128();
+    if val & !(0xffff << 96) == UUID_BASE {
+        ((val >> 96) as u16).to_le_bytes().into()
+    } else {
+        uuid.as_bytes().as_ref().into()
+    }
+}
+
 /// 48-bit unsigned int type
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(transparent)]
@@ -213,6 +270,15 @@ impl From<[u8; 8]> for Sizes {
     }
 }
 
+impl From<&Sizes> for [u8; 8] {
+    fn from(sizes: &Sizes) -> Self {
+        let mut raw = [0; 8];
+        raw[..4].copy_from_slice(&sizes.current.to_le_bytes());
+        raw[4..].copy_from_slice(&sizes.allocated.to_le_bytes());
+        raw
+    }
+}
+
 impl TryFrom<&[u8]> for Sizes {
     type Error = Error;
     fn try_from(raw: &[u8]) -> Result<Self> {
@@ -325,6 +391,21 @@ impl_bc! {
         ObjectIdNotFound = 0x08,
     }
 
+    /// Object list filter type
+    FilterType (|raw| raw <= Marked as _) {
+        None = 0x00,
+        NameStartsWith = 0x01,
+        NameEndsWith = 0x02,
+        NameContains = 0x03,
+        NameIs = 0x04,
+        Type = 0x05,
+        Created = 0x06,
+        Modified = 0x07,
+        CurrentSize = 0x08,
+        AllocatedSize = 0x09,
+        Marked = 0x0a,
+    }
+
     /// Object action operation code
     ActionOp (|raw| raw >= Create as _ && raw <= Abort as _ || raw == Response as _) {
         Create = 0x01,
@@ -392,6 +473,64 @@ impl From<&ListReq> for Vec<u8> {
     }
 }
 
+impl ListReq {
+    /// Get operation code of request
+    pub fn op(&self) -> ListOp {
+        use ListReq::*;
+
+        match self {
+            First => ListOp::First,
+            Last => ListOp::Last,
+            Previous => ListOp::Previous,
+            Next => ListOp::Next,
+            GoTo { .. } => ListOp::GoTo,
+            Order { .. } => ListOp::Order,
+            NumberOf => ListOp::NumberOf,
+            ClearMark => ListOp::ClearMark,
+        }
+    }
+}
+
+impl TryFrom<&[u8]> for ListReq {
+    type Error = Error;
+
+    fn try_from(raw: &[u8]) -> Result<Self> {
+        use ListReq::*;
+
+        Error::check_len(raw.len(), 1)?;
+        let (code, raw) = (raw[0], &raw[1..]);
+
+        Ok(match code.try_into()? {
+            ListOp::GoTo => GoTo {
+                id: Ule48::try_from(raw)?.into(),
+            },
+            ListOp::Order => {
+                Error::check_len_exact(raw.len(), 1)?;
+                Order {
+                    order: raw[0].try_into()?,
+                }
+            }
+            ListOp::Response => {
+                return Err(Error::BadOpCode {
+                    type_: OpType::ListOp,
+                    code,
+                })
+            }
+            op => {
+                Error::check_len_exact(raw.len(), 0)?;
+                match op {
+                    ListOp::First => First,
+                    ListOp::Last => Last,
+                    ListOp::Previous => Previous,
+                    ListOp::Next => Next,
+                    ListOp::NumberOf => NumberOf,
+                    _ => ClearMark,
+                }
+            }
+        })
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum ListRes {
     None,
@@ -424,6 +563,27 @@ impl TryFrom<&[u8]> for ListRes {
     }
 }
 
+impl ListRes {
+    /// Encode response to request
+    ///
+    /// Resulting data is suitable to be indicated through OLCP.
+    pub fn encode(op: ListOp, result: core::result::Result<&ListRes, ListRc>) -> Vec<u8> {
+        let mut out = Vec::with_capacity(7);
+        out.push(ListOp::Response as _);
+        out.push(op as _);
+        match result {
+            Ok(res) => {
+                out.push(ListRc::Success as _);
+                if let ListRes::NumberOf { count } = res {
+                    out.extend_from_slice(&count.to_le_bytes());
+                }
+            }
+            Err(rc) => out.push(rc as _),
+        }
+        out
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum ActionReq {
     Create {
@@ -492,6 +652,89 @@ impl From<&ActionReq> for Vec<u8> {
     }
 }
 
+impl ActionReq {
+    /// Get operation code of request
+    pub fn op(&self) -> ActionOp {
+        use ActionReq::*;
+
+        match self {
+            Create { .. } => ActionOp::Create,
+            Delete => ActionOp::Delete,
+            CheckSum { .. } => ActionOp::CheckSum,
+            Execute { .. } => ActionOp::Execute,
+            Read { .. } => ActionOp::Read,
+            Write { .. } => ActionOp::Write,
+            Abort => ActionOp::Abort,
+        }
+    }
+}
+
+impl TryFrom<&[u8]> for ActionReq {
+    type Error = Error;
+
+    fn try_from(raw: &[u8]) -> Result<Self> {
+        use ActionReq::*;
+
+        fn offset_length(raw: &[u8]) -> (usize, usize) {
+            let (offset, raw) = raw.split_array_ref_();
+            let (length, _) = raw.split_array_ref_();
+            (
+                u32::from_le_bytes(*offset) as _,
+                u32::from_le_bytes(*length) as _,
+            )
+        }
+
+        Error::check_len(raw.len(), 1)?;
+        let (code, raw) = (raw[0], &raw[1..]);
+
+        Ok(match code.try_into()? {
+            ActionOp::Create => {
+                Error::check_len(raw.len(), 4)?;
+                let (size, raw) = raw.split_array_ref_();
+                Create {
+                    size: u32::from_le_bytes(*size) as _,
+                    type_: uuid_from_raw(raw)?,
+                }
+            }
+            ActionOp::Delete => {
+                Error::check_len_exact(raw.len(), 0)?;
+                Delete
+            }
+            ActionOp::CheckSum => {
+                Error::check_len_exact(raw.len(), 8)?;
+                let (offset, length) = offset_length(raw);
+                CheckSum { offset, length }
+            }
+            ActionOp::Execute => Execute { param: raw.into() },
+            ActionOp::Read => {
+                Error::check_len_exact(raw.len(), 8)?;
+                let (offset, length) = offset_length(raw);
+                Read { offset, length }
+            }
+            ActionOp::Write => {
+                Error::check_len_exact(raw.len(), 9)?;
+                let (offset, length) = offset_length(raw);
+                let mode = WriteMode::from_bits(raw[8]).ok_or(Error::BadWriteMode(raw[8]))?;
+                Write {
+                    offset,
+                    length,
+                    mode,
+                }
+            }
+            ActionOp::Abort => {
+                Error::check_len_exact(raw.len(), 0)?;
+                Abort
+            }
+            ActionOp::Response => {
+                return Err(Error::BadOpCode {
+                    type_: OpType::ActionOp,
+                    code,
+                })
+            }
+        })
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum ActionRes {
     None,
@@ -529,6 +772,59 @@ impl TryFrom<&[u8]> for ActionRes {
     }
 }
 
+impl ActionRes {
+    /// Encode response to request
+    ///
+    /// Resulting data is suitable to be indicated through OACP.
+    pub fn encode(op: ActionOp, result: core::result::Result<&ActionRes, ActionRc>) -> Vec<u8> {
+        let mut out = Vec::with_capacity(7);
+        out.push(ActionOp::Response as _);
+        out.push(op as _);
+        match result {
+            Ok(res) => {
+                out.push(ActionRc::Success as _);
+                match res {
+                    ActionRes::CheckSum { value } => out.extend_from_slice(&value.to_le_bytes()),
+                    ActionRes::Execute { param } => out.extend_from_slice(param),
+                    ActionRes::None => {}
+                }
+            }
+            Err(rc) => out.push(rc as _),
+        }
+        out
+    }
+}
+
+/// Object changed indication
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ObjectChanged {
+    /// Source and kind of change
+    pub flags: ChangeFlag,
+    /// Identifier of changed object
+    pub id: u64,
+}
+
+impl TryFrom<&[u8]> for ObjectChanged {
+    type Error = Error;
+    fn try_from(raw: &[u8]) -> Result<Self> {
+        Error::check_len_exact(raw.len(), 7)?;
+        let (flags, raw) = raw.split_array_ref_();
+        let flags = u8::from_le_bytes(*flags);
+        let flags = ChangeFlag::from_bits(flags).ok_or(Error::BadChangeFlags(flags))?;
+        let id = Ule48::try_from(raw)?.into();
+        Ok(Self { flags, id })
+    }
+}
+
+impl From<&ObjectChanged> for [u8; 7] {
+    fn from(changed: &ObjectChanged) -> Self {
+        let mut raw = [0; 7];
+        raw[0] = changed.flags.bits();
+        raw[1..].copy_from_slice(&<[u8; 6]>::from(Ule48::from(changed.id)));
+        raw
+    }
+}
+
 /// Object date and time
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -571,6 +867,15 @@ impl From<&[u8; 7]> for DateTime {
     }
 }
 
+impl From<&DateTime> for [u8; 7] {
+    fn from(dt: &DateTime) -> Self {
+        let year = dt.year.to_le_bytes();
+        [
+            year[0], year[1], dt.month, dt.day, dt.hour, dt.minute, dt.second,
+        ]
+    }
+}
+
 impl TryFrom<&[u8]> for DateTime {
     type Error = Error;
     fn try_from(raw: &[u8]) -> Result<Self> {
@@ -580,6 +885,60 @@ impl TryFrom<&[u8]> for DateTime {
     }
 }
 
+impl From<std::time::SystemTime> for DateTime {
+    /// Convert system time to UTC date and time
+    ///
+    /// Times before Unix epoch are clamped to epoch.
+    fn from(time: std::time::SystemTime) -> Self {
+        let secs = time
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default();
+        let (days, secs) = (secs / 86400, secs % 86400);
+
+        // civil from days algorithm by Howard Hinnant
+        let z = days + 719468;
+        let era = z / 146097;
+        let doe = z - era * 146097;
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = doy - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = yoe + era * 400 + (month <= 2) as u64;
+
+        Self {
+            year: year as _,
+            month: month as _,
+            day: day as _,
+            hour: (secs / 3600) as _,
+            minute: (secs / 60 % 60) as _,
+            second: (secs % 60) as _,
+        }
+    }
+}
+
+impl From<&DateTime> for std::time::SystemTime {
+    /// Convert UTC date and time to system time
+    ///
+    /// Times before Unix epoch are clamped to epoch.
+    fn from(time: &DateTime) -> Self {
+        // days from civil algorithm by Howard Hinnant
+        let month = time.month as i64;
+        let year = time.year as i64 - (month <= 2) as i64;
+        let era = year.div_euclid(400);
+        let yoe = year - era * 400;
+        let mp = if month > 2 { month - 3 } else { month + 9 };
+        let doy = (153 * mp + 2) / 5 + time.day as i64 - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        let days = era * 146097 + doe - 719468;
+
+        let secs =
+            days * 86400 + time.hour as i64 * 3600 + time.minute as i64 * 60 + time.second as i64;
+        std::time::UNIX_EPOCH + core::time::Duration::from_secs(secs.max(0) as _)
+    }
+}
+
 #[cfg(feature = "time")]
 impl TryFrom<DateTime> for time::PrimitiveDateTime {
     type Error = time::Error;
@@ -635,7 +994,7 @@ impl From<chrono::naive::NaiveDateTime> for DateTime {
 
 /// Object metadata
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
 pub struct Metadata {
     /// Identifier of object (48-bit)
@@ -718,6 +1077,62 @@ impl<'i> Iterator for DirEntries<'i> {
 
 impl<'i> core::iter::FusedIterator for DirEntries<'i> {}
 
+impl DirEntries<'_> {
+    /// Encode directory listing object contents
+    pub fn encode<'a>(objects: impl IntoIterator<Item = &'a Metadata>) -> Vec<u8> {
+        let mut out = Vec::new();
+        for object in objects {
+            object.encode_dir_entry(&mut out);
+        }
+        out
+    }
+}
+
+impl Metadata {
+    /// Encode directory listing record of object
+    ///
+    /// Record is appended to output. Names longer than 255 bytes are truncated.
+    pub fn encode_dir_entry(&self, out: &mut Vec<u8>) {
+        let start = out.len();
+        out.extend_from_slice(&[0; 2]);
+        out.extend_from_slice(&<[u8; 6]>::from(Ule48::from(self.id.unwrap_or_default())));
+
+        let mut name_len = self.name.len().min(u8::MAX as _);
+        while !self.name.is_char_boundary(name_len) {
+            name_len -= 1;
+        }
+        out.push(name_len as _);
+        out.extend_from_slice(&self.name.as_bytes()[..name_len]);
+
+        let type_ = uuid_to_raw(&self.type_);
+        let mut flags = DirFlag::HasProperties;
+        flags.set(DirFlag::TypeUuid128, type_.len() == 16);
+        flags.set(DirFlag::HasCurrentSize, self.current_size.is_some());
+        flags.set(DirFlag::HasAllocatedSize, self.allocated_size.is_some());
+        flags.set(DirFlag::HasFirstCreated, self.first_created.is_some());
+        flags.set(DirFlag::HasLastModified, self.last_modified.is_some());
+        out.push(flags.bits());
+        out.extend_from_slice(&type_);
+
+        for size in [self.current_size, self.allocated_size]
+            .into_iter()
+            .flatten()
+        {
+            out.extend_from_slice(&(size as u32).to_le_bytes());
+        }
+        for time in [&self.first_created, &self.last_modified]
+            .into_iter()
+            .flatten()
+        {
+            out.extend_from_slice(&<[u8; 7]>::from(time));
+        }
+        out.extend_from_slice(&<[u8; 4]>::from(self.properties));
+
+        let len = (out.len() - start) as u16;
+        out[start..start + 2].copy_from_slice(&len.to_le_bytes());
+    }
+}
+
 impl TryFrom<&[u8]> for Metadata {
     type Error = Error;
     fn try_from(raw: &[u8]) -> Result<Self> {
@@ -767,7 +1182,7 @@ impl TryFrom<&[u8]> for Metadata {
         } else {
             (None, raw)
         };
-        let (last_modified, raw) = if flags.contains(DirFlag::HasFirstCreated) {
+        let (last_modified, raw) = if flags.contains(DirFlag::HasLastModified) {
             Error::check_len(raw.len(), 7)?;
             let (time, raw) = raw.split_array_ref_();
             let time = DateTime::from(time);
@@ -796,6 +1211,165 @@ impl TryFrom<&[u8]> for Metadata {
     }
 }
 
+/// Object list filter
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub enum Filter {
+    /// No filtering
+    #[default]
+    None,
+    /// Name starts with string
+    NameStartsWith(String),
+    /// Name ends with string
+    NameEndsWith(String),
+    /// Name contains string
+    NameContains(String),
+    /// Name is exactly string
+    NameIs(String),
+    /// Object type
+    Type(Uuid),
+    /// First created time in range (inclusive)
+    Created { from: DateTime, to: DateTime },
+    /// Last modified time in range (inclusive)
+    Modified { from: DateTime, to: DateTime },
+    /// Current size in range (inclusive)
+    CurrentSize { min: u32, max: u32 },
+    /// Allocated size in range (inclusive)
+    AllocatedSize { min: u32, max: u32 },
+    /// Objects marked by server
+    Marked,
+}
+
+impl Filter {
+    /// Get type of filter
+    pub fn type_(&self) -> FilterType {
+        use Filter::*;
+
+        match self {
+            None => FilterType::None,
+            NameStartsWith(_) => FilterType::NameStartsWith,
+            NameEndsWith(_) => FilterType::NameEndsWith,
+            NameContains(_) => FilterType::NameContains,
+            NameIs(_) => FilterType::NameIs,
+            Type(_) => FilterType::Type,
+            Created { .. } => FilterType::Created,
+            Modified { .. } => FilterType::Modified,
+            CurrentSize { .. } => FilterType::CurrentSize,
+            AllocatedSize { .. } => FilterType::AllocatedSize,
+            Marked => FilterType::Marked,
+        }
+    }
+
+    /// Check that object matches filter
+    ///
+    /// Objects with unknown time or size never match corresponding range.
+    pub fn matches(&self, object: &Metadata) -> bool {
+        use Filter::*;
+
+        fn in_range<T: PartialOrd>(value: Option<T>, from: T, to: T) -> bool {
+            value
+                .map(|value| from <= value && value <= to)
+                .unwrap_or_default()
+        }
+
+        match self {
+            None => true,
+            NameStartsWith(name) => object.name.starts_with(name.as_str()),
+            NameEndsWith(name) => object.name.ends_with(name.as_str()),
+            NameContains(name) => object.name.contains(name.as_str()),
+            NameIs(name) => object.name == *name,
+            Type(type_) => object.type_ == *type_,
+            Created { from, to } => in_range(object.first_created.as_ref(), from, to),
+            Modified { from, to } => in_range(object.last_modified.as_ref(), from, to),
+            CurrentSize { min, max } => in_range(object.current_size, *min as _, *max as _),
+            AllocatedSize { min, max } => in_range(object.allocated_size, *min as _, *max as _),
+            Marked => object.properties.contains(Property::Mark),
+        }
+    }
+}
+
+impl TryFrom<&[u8]> for Filter {
+    type Error = Error;
+    fn try_from(raw: &[u8]) -> Result<Self> {
+        use Filter::*;
+
+        Error::check_len(raw.len(), 1)?;
+        let (type_, raw) = raw.split_at(1);
+
+        fn times(raw: &[u8]) -> Result<(DateTime, DateTime)> {
+            Error::check_len_exact(raw.len(), 14)?;
+            let (from, raw) = raw.split_array_ref_();
+            let (to, _) = raw.split_array_ref_();
+            Ok((DateTime::from(from), DateTime::from(to)))
+        }
+
+        fn sizes(raw: &[u8]) -> Result<(u32, u32)> {
+            let sizes = Sizes::try_from(raw)?;
+            Ok((sizes.current, sizes.allocated))
+        }
+
+        Ok(match FilterType::try_from(type_[0])? {
+            FilterType::None | FilterType::Marked if !raw.is_empty() => {
+                return Err(Error::TooManyData {
+                    actual: raw.len() + 1,
+                    needed: 1,
+                })
+            }
+            FilterType::None => None,
+            FilterType::NameStartsWith => NameStartsWith(core::str::from_utf8(raw)?.into()),
+            FilterType::NameEndsWith => NameEndsWith(core::str::from_utf8(raw)?.into()),
+            FilterType::NameContains => NameContains(core::str::from_utf8(raw)?.into()),
+            FilterType::NameIs => NameIs(core::str::from_utf8(raw)?.into()),
+            FilterType::Type => match raw.len() {
+                2 | 16 => Type(uuid_from_raw(raw)?),
+                len => return Err(Error::BadUuidSize(len)),
+            },
+            FilterType::Created => {
+                let (from, to) = times(raw)?;
+                Created { from, to }
+            }
+            FilterType::Modified => {
+                let (from, to) = times(raw)?;
+                Modified { from, to }
+            }
+            FilterType::CurrentSize => {
+                let (min, max) = sizes(raw)?;
+                CurrentSize { min, max }
+            }
+            FilterType::AllocatedSize => {
+                let (min, max) = sizes(raw)?;
+                AllocatedSize { min, max }
+            }
+            FilterType::Marked => Marked,
+        })
+    }
+}
+
+impl From<&Filter> for Vec<u8> {
+    fn from(filter: &Filter) -> Self {
+        use Filter::*;
+
+        let mut out = vec![filter.type_() as u8];
+        match filter {
+            None | Marked => {}
+            NameStartsWith(name) | NameEndsWith(name) | NameContains(name) | NameIs(name) => {
+                out.extend_from_slice(name.as_bytes())
+            }
+            Type(type_) => out.extend(uuid_to_raw(type_)),
+            Created { from, to } | Modified { from, to } => {
+                out.extend_from_slice(&<[u8; 7]>::from(from));
+                out.extend_from_slice(&<[u8; 7]>::from(to));
+            }
+            CurrentSize { min, max } | AllocatedSize { min, max } => {
+                out.extend_from_slice(&<[u8; 8]>::from(&Sizes {
+                    current: *min,
+                    allocated: *max,
+                }))
+            }
+        }
+        out
+    }
+}
+
 trait SliceExt {
     type V;
     fn split_array_ref_<const N: usize>(&self) -> (&[Self::V; N], &[Self::V]);
@@ -0,0 +1,22 @@
+use ots_core::crc::{crc32, Crc32};
+
+#[test]
+fn check_value() {
+    assert_eq!(crc32(b"123456789"), 0xCBF43926);
+    assert_eq!(crc32(b""), 0);
+}
+
+#[test]
+fn incremental() {
+    let data: Vec<u8> = (0..=255).cycle().take(1000).collect();
+    let mut crc = Crc32::new();
+    for chunk in data.chunks(7) {
+        crc.update(chunk);
+    }
+    assert_eq!(crc.finish(), crc32(&data));
+
+    let mut crc = Crc32::default();
+    crc.update(b"12345");
+    crc.update(b"6789");
+    assert_eq!(crc.finish(), 0xCBF43926);
+}
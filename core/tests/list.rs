@@ -0,0 +1,137 @@
+use ots_core::{
+    list::ObjectList,
+    types::{DateTime, Filter, ListRc, Metadata, Property, SortOrder},
+    Error,
+};
+use uuid::Uuid;
+
+fn object(id: u64, name: &str, size: usize, properties: Property) -> Metadata {
+    Metadata {
+        id: Some(id),
+        name: name.into(),
+        type_: Uuid::from_u128(0x0000_2acb_0000_1000_8000_0080_5f9b_34fb),
+        current_size: Some(size),
+        allocated_size: Some(size),
+        first_created: None,
+        last_modified: Some(DateTime {
+            year: 2024,
+            month: 1,
+            day: id as _,
+            hour: 0,
+            minute: 0,
+            second: 0,
+        }),
+        properties,
+    }
+}
+
+fn objects() -> Vec<Metadata> {
+    vec![
+        object(1, "notes.txt", 10, Property::Read),
+        object(2, "image.png", 300, Property::Read | Property::Mark),
+        object(3, "log.txt", 10, Property::Read),
+        object(4, "data.bin", 0, Property::Mark),
+    ]
+}
+
+fn ids(list: &ObjectList) -> Vec<u64> {
+    list.objects()
+        .iter()
+        .map(|object| object.id.unwrap())
+        .collect()
+}
+
+#[test]
+fn filter_codec() {
+    let filters = [
+        Filter::None,
+        Filter::NameEndsWith(".txt".into()),
+        Filter::Type(Uuid::from_u128(0x0000_2acb_0000_1000_8000_0080_5f9b_34fb)),
+        Filter::Type(Uuid::from_u128(0x0123_4567_89ab_cdef_0123_4567_89ab_cdef)),
+        Filter::Modified {
+            from: DateTime::from(std::time::UNIX_EPOCH),
+            to: DateTime::from(std::time::SystemTime::now()),
+        },
+        Filter::CurrentSize { min: 1, max: 100 },
+        Filter::Marked,
+    ];
+    for filter in filters {
+        let raw = Vec::from(&filter);
+        assert_eq!(Filter::try_from(raw.as_slice()).unwrap(), filter);
+    }
+
+    assert_eq!(Vec::from(&Filter::NameIs("a".into())), [0x04, b'a']);
+    assert_eq!(
+        Vec::from(&Filter::AllocatedSize { min: 1, max: 2 }),
+        [0x09, 1, 0, 0, 0, 2, 0, 0, 0]
+    );
+    assert!(matches!(
+        Filter::try_from([0x0b].as_slice()),
+        Err(Error::BadOpCode { code: 0x0b, .. })
+    ));
+    assert!(Filter::try_from([0x06, 0].as_slice()).is_err());
+    assert!(Filter::try_from([0x0a, 0].as_slice()).is_err());
+    assert!(Filter::try_from([0u8; 0].as_slice()).is_err());
+}
+
+#[test]
+fn filter_and_order() {
+    let list = ObjectList::new(objects(), &[], None);
+    assert_eq!(ids(&list), [1, 2, 3, 4]);
+
+    let list = ObjectList::new(objects(), &[], Some(SortOrder::NameAsc));
+    assert_eq!(ids(&list), [4, 2, 3, 1]);
+
+    // equal sizes keep original order
+    let list = ObjectList::new(objects(), &[], Some(SortOrder::CurSizeDesc));
+    assert_eq!(ids(&list), [2, 1, 3, 4]);
+
+    let list = ObjectList::new(
+        objects(),
+        &[
+            Filter::NameEndsWith(".txt".into()),
+            Filter::None,
+            Filter::CurrentSize { min: 5, max: 10 },
+        ],
+        Some(SortOrder::ModTimeDesc),
+    );
+    assert_eq!(ids(&list), [3, 1]);
+
+    let list = ObjectList::new(objects(), &[Filter::Marked], None);
+    assert_eq!(ids(&list), [2, 4]);
+
+    // unknown creation time never matches
+    let list = ObjectList::new(
+        objects(),
+        &[Filter::Created {
+            from: DateTime::from(std::time::UNIX_EPOCH),
+            to: DateTime::from(std::time::SystemTime::now()),
+        }],
+        None,
+    );
+    assert!(list.is_empty());
+}
+
+#[test]
+fn navigation() {
+    let list = ObjectList::new(objects(), &[Filter::NameContains("o".into())], None);
+    assert_eq!(list.number_of(), Ok(2));
+    assert_eq!(list.first().unwrap().id, Some(1));
+    assert_eq!(list.last().unwrap().id, Some(3));
+    assert_eq!(list.next(Some(1)).unwrap().id, Some(3));
+    assert_eq!(list.previous(Some(3)).unwrap().id, Some(1));
+    assert_eq!(list.previous(Some(1)), Err(ListRc::OutOfBounds));
+    assert_eq!(list.next(Some(3)), Err(ListRc::OutOfBounds));
+    // filtered out current object
+    assert_eq!(list.next(Some(4)), Err(ListRc::OperationFailed));
+    assert_eq!(list.next(None), Err(ListRc::OperationFailed));
+    assert_eq!(list.go_to(4), Err(ListRc::ObjectIdNotFound));
+    assert_eq!(list.go_to(3).unwrap().name, "log.txt");
+
+    let empty = ObjectList::new(objects(), &[Filter::NameIs("none".into())], None);
+    assert_eq!(empty.number_of(), Ok(0));
+    assert_eq!(empty.first(), Err(ListRc::NoObject));
+    assert_eq!(empty.last(), Err(ListRc::NoObject));
+    assert_eq!(empty.next(Some(1)), Err(ListRc::NoObject));
+    assert_eq!(empty.previous(None), Err(ListRc::NoObject));
+}
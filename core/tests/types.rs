@@ -0,0 +1,70 @@
+use ots_core::types::{DateTime, DirEntries, Metadata, Property};
+use uuid::Uuid;
+
+#[test]
+fn dir_entries_codec() {
+    let objects = [
+        Metadata {
+            id: Some(0x100),
+            name: "notes.txt".into(),
+            type_: Uuid::from_u128(0x0000_2acb_0000_1000_8000_0080_5f9b_34fb),
+            current_size: Some(10),
+            allocated_size: Some(16),
+            first_created: None,
+            last_modified: Some(DateTime {
+                year: 2024,
+                month: 2,
+                day: 29,
+                hour: 12,
+                minute: 30,
+                second: 0,
+            }),
+            properties: Property::Read | Property::Write,
+        },
+        Metadata {
+            id: Some(0x101),
+            name: "x".repeat(300),
+            type_: Uuid::from_u128(0x0123_4567_89ab_cdef_0123_4567_89ab_cdef),
+            current_size: None,
+            allocated_size: None,
+            first_created: None,
+            last_modified: None,
+            properties: Property::empty(),
+        },
+    ];
+
+    let raw = DirEntries::encode(&objects);
+    let decoded = DirEntries::from(raw.as_slice())
+        .collect::<ots_core::Result<Vec<_>>>()
+        .unwrap();
+    assert_eq!(decoded[0], objects[0]);
+    // long names are truncated
+    assert_eq!(decoded[1].name.len(), 255);
+    assert_eq!(decoded[1].type_, objects[1].type_);
+    assert_eq!(decoded.len(), 2);
+}
+
+#[test]
+fn date_time_system_time() {
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    for secs in [0, 951_782_400, 1_709_251_199, 4_102_444_800] {
+        let time = UNIX_EPOCH + Duration::from_secs(secs);
+        assert_eq!(SystemTime::from(&DateTime::from(time)), time);
+    }
+    let time = DateTime {
+        year: 2024,
+        month: 2,
+        day: 29,
+        hour: 23,
+        minute: 59,
+        second: 59,
+    };
+    assert_eq!(
+        SystemTime::from(&time),
+        UNIX_EPOCH + Duration::from_secs(1_709_251_199)
+    );
+    // unknown time is before epoch
+    let unknown = DateTime::from(&[0; 7]);
+    assert_eq!(SystemTime::from(&unknown), UNIX_EPOCH);
+}
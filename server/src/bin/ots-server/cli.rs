@@ -0,0 +1,133 @@
+use clap::{Parser, ValueEnum};
+use ots_server::{ActionFeature, SecurityLevel};
+use std::path::PathBuf;
+
+/// Object Transfer Service server
+///
+/// Exposes files of directory as objects.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct Args {
+    /// Directory to expose
+    ///
+    /// Mock server keeps sample objects in memory when not set.
+    #[arg(required_unless_present = "mock")]
+    pub dir: Option<PathBuf>,
+
+    /// Adapter name to use
+    #[arg(short, long, default_value = "hci0")]
+    pub adapter: String,
+
+    /// Local name to advertise service with
+    #[arg(short, long, default_value = "OTS Server")]
+    pub name: String,
+
+    /// Do not advertise service
+    #[arg(long)]
+    pub no_advertise: bool,
+
+    /// Supported object actions
+    #[arg(
+        short,
+        long,
+        value_delimiter = ',',
+        default_value = "read,write,create,delete,checksum,patch,append,truncate"
+    )]
+    pub features: Vec<Feature>,
+
+    /// Required security level
+    #[arg(short, long, default_value = "low")]
+    pub security: Security,
+
+    /// Reject all modifications of objects
+    #[arg(short, long)]
+    pub read_only: bool,
+
+    /// Indicate changes caused by clients
+    ///
+    /// Client which caused change is indicated too, since BlueZ delivers
+    /// indications to all subscribed devices.
+    #[arg(long)]
+    pub client_changes: bool,
+
+    /// Serve over local test transport instead of BlueZ
+    ///
+    /// Application is registered on session bus and object channels are
+    /// accepted on Unix socket, so clients can be tested without adapter.
+    #[arg(short, long)]
+    pub mock: bool,
+
+    /// Unix socket to accept object channels on in mock mode
+    #[arg(long, default_value = "/tmp/ots-server.sock")]
+    pub socket: PathBuf,
+}
+
+/// Object action feature
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Feature {
+    /// Read object data
+    Read,
+    /// Write object data
+    Write,
+    /// Create objects
+    Create,
+    /// Delete objects
+    Delete,
+    /// Calculate checksum of object data
+    Checksum,
+    /// Patch object data
+    Patch,
+    /// Append object data
+    Append,
+    /// Truncate object data
+    Truncate,
+}
+
+impl From<Feature> for ActionFeature {
+    fn from(feature: Feature) -> Self {
+        match feature {
+            Feature::Read => ActionFeature::Read,
+            Feature::Write => ActionFeature::Write,
+            Feature::Create => ActionFeature::Create,
+            Feature::Delete => ActionFeature::Delete,
+            Feature::Checksum => ActionFeature::CheckSum,
+            Feature::Patch => ActionFeature::Patch,
+            Feature::Append => ActionFeature::Append,
+            Feature::Truncate => ActionFeature::Truncate,
+        }
+    }
+}
+
+/// Security level
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Security {
+    /// No encryption required
+    Low,
+    /// Encryption required
+    Medium,
+    /// Authenticated encryption required
+    High,
+}
+
+impl From<Security> for SecurityLevel {
+    fn from(security: Security) -> Self {
+        match security {
+            Security::Low => SecurityLevel::Low,
+            Security::Medium => SecurityLevel::Medium,
+            Security::High => SecurityLevel::High,
+        }
+    }
+}
+
+impl Args {
+    /// Get supported object actions
+    ///
+    /// Modifying actions are removed by server in read-only mode.
+    pub fn action_features(&self) -> ActionFeature {
+        self.features
+            .iter()
+            .fold(ActionFeature::Abort, |features, feature| {
+                features | (*feature).into()
+            })
+    }
+}
@@ -0,0 +1,78 @@
+use ots_server::{
+    bluez::{Application, BluezConfig},
+    store::UNSPECIFIED_TYPE,
+    Config, FsConfig, FsStore, MemoryStore, ObjectStore, Property, Result, Server,
+};
+use tracing::info;
+
+mod cli;
+mod mock;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::FmtSubscriber::builder()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .init();
+
+    let args = <cli::Args as clap::Parser>::parse();
+
+    let properties = if args.read_only {
+        Property::Read
+    } else {
+        FsConfig::default().properties
+    };
+
+    let store: Box<dyn ObjectStore> = if let Some(dir) = &args.dir {
+        info!("Expose directory {}", dir.display());
+        Box::new(FsStore::open(
+            dir,
+            FsConfig {
+                properties,
+                read_only: args.read_only,
+                ..Default::default()
+            },
+        )?)
+    } else {
+        let mut store = MemoryStore::new();
+        store.insert("hello.txt", UNSPECIFIED_TYPE, properties, "Hello, world!\n")?;
+        store.insert(
+            "counter.bin",
+            UNSPECIFIED_TYPE,
+            properties,
+            (0..=255).collect::<Vec<u8>>(),
+        )?;
+        Box::new(store)
+    };
+
+    let server = Server::new(
+        store,
+        Config {
+            action_features: args.action_features(),
+            read_only: args.read_only,
+            ..Default::default()
+        },
+    );
+    info!("Supported actions: {}", server.config().action_features);
+
+    let config = BluezConfig {
+        adapter: args.adapter.clone(),
+        security: args.security.into(),
+        advertise: (!args.no_advertise).then(|| args.name.clone()),
+        client_changes: args.client_changes,
+        ..Default::default()
+    };
+
+    let (app, listener) = if args.mock {
+        let (app, listener) = mock::register(server, config, &args.socket).await?;
+        (app, Some(listener))
+    } else {
+        (Application::register(server, config).await?, None)
+    };
+    info!("Serving, press Ctrl-C to stop");
+
+    tokio::signal::ctrl_c().await?;
+
+    app.unregister().await?;
+    drop(listener);
+    Ok(())
+}
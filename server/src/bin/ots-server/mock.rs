@@ -0,0 +1,146 @@
+//! Local test transport
+//!
+//! Application is registered on session bus where stand-in BlueZ
+//! managers accept it, so characteristics are accessed by calling methods
+//! of application objects directly. Object channels are accepted on Unix
+//! sequential packet socket and belong to client which passes no device
+//! in request options.
+
+use dbus::{arg::PropMap, channel::MatchingReceiver, message::MatchRule, Path};
+use dbus_crossroads::Crossroads;
+use ots_server::{
+    bluez::{Application, BluezConfig},
+    Channel, ClientId, ObjectStore, Result, Server,
+};
+use socket2::{Domain, SockAddr, Socket, Type};
+use std::{
+    net::Shutdown,
+    path::{Path as FsPath, PathBuf},
+    thread,
+};
+use tracing::{debug, info, warn};
+
+/// MTU of object channels
+const MTU: usize = 512;
+
+/// Register application on session bus
+pub async fn register<S: ObjectStore + 'static>(
+    server: Server<S>,
+    config: BluezConfig,
+    socket: &FsPath,
+) -> Result<(Application, Listener)> {
+    let (resource, conn) = dbus_tokio::connection::new_session_sync()?;
+    tokio::spawn(async {
+        let error = resource.await;
+        warn!("Lost connection to D-Bus: {error}");
+    });
+
+    conn.request_name("org.bluez", false, true, true).await?;
+
+    let mut cr = Crossroads::new();
+    let gatt_iface = cr.register("org.bluez.GattManager1", |b| {
+        b.method(
+            "RegisterApplication",
+            ("application", "options"),
+            (),
+            |_, _, (path, _): (Path<'static>, PropMap)| {
+                debug!("Application {path} registered");
+                Ok(())
+            },
+        );
+        b.method(
+            "UnregisterApplication",
+            ("application",),
+            (),
+            |_, _, (_,): (Path<'static>,)| Ok(()),
+        );
+    });
+    let advertising_iface = cr.register("org.bluez.LEAdvertisingManager1", |b| {
+        b.method(
+            "RegisterAdvertisement",
+            ("advertisement", "options"),
+            (),
+            |_, _, (path, _): (Path<'static>, PropMap)| {
+                debug!("Advertisement {path} registered");
+                Ok(())
+            },
+        );
+        b.method(
+            "UnregisterAdvertisement",
+            ("advertisement",),
+            (),
+            |_, _, (_,): (Path<'static>,)| Ok(()),
+        );
+    });
+    cr.insert(
+        format!("/org/bluez/{}", config.adapter),
+        &[gatt_iface, advertising_iface],
+        (),
+    );
+
+    let mut rule = MatchRule::new_method_call();
+    rule.path = Some("/org/bluez".into());
+    rule.path_is_namespace = true;
+    conn.start_receive(
+        rule,
+        Box::new(move |msg, conn| {
+            let _ = cr.handle_message(msg, conn);
+            true
+        }),
+    );
+
+    let listener = Listener::new(server.clone(), socket)?;
+    let app = Application::register_with(
+        conn,
+        server,
+        BluezConfig {
+            psm: None,
+            ..config
+        },
+    )
+    .await?;
+    info!("Accept object channels on {}", socket.display());
+
+    Ok((app, listener))
+}
+
+/// Unix socket object channel listener
+pub struct Listener {
+    socket: Socket,
+    path: PathBuf,
+}
+
+impl Listener {
+    fn new<S: ObjectStore + 'static>(server: Server<S>, path: &FsPath) -> Result<Self> {
+        // remove socket left by previous run
+        let _ = std::fs::remove_file(path);
+
+        let socket = Socket::new(Domain::UNIX, Type::SEQPACKET, None)?;
+        socket.bind(&SockAddr::unix(path)?)?;
+        socket.listen(1)?;
+
+        let listener = socket.try_clone()?;
+        thread::spawn(move || {
+            while let Ok((socket, _)) = listener.accept() {
+                debug!("Accept object channel");
+                server.attach_channel(
+                    &ClientId::default(),
+                    Channel::with_mtu(socket.into(), MTU, MTU),
+                );
+            }
+            debug!("Stop accepting channels");
+        });
+
+        Ok(Self {
+            socket,
+            path: path.into(),
+        })
+    }
+}
+
+impl Drop for Listener {
+    fn drop(&mut self) {
+        let _ = self.socket.shutdown(Shutdown::Both);
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
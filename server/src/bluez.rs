@@ -0,0 +1,542 @@
+//! BlueZ GATT transport
+//!
+//! OTS service is registered as GATT application through
+//! `org.bluez.GattManager1` D-Bus interface and object channels are
+//! accepted on L2CAP PSM 0x25. Service may be also advertised through
+//! `org.bluez.LEAdvertisingManager1` interface.
+//!
+//! BlueZ D-Bus API does not allow to indicate value to particular device:
+//! indicated value is delivered to each device which enabled indications.
+//! Control point responses carry opcode of request, so clients can skip
+//! responses to requests of others. Object Changed cannot be withheld
+//! from client which caused the change, as OTS specification requires,
+//! so by default only changes caused by local events are indicated.
+//! Changes caused by clients may be indicated too (see
+//! [`BluezConfig::client_changes`]) at cost of client receiving its own
+//! changes. In both cases change is indicated only when it has recipients
+//! besides client which caused it, i.e. some other device is connected
+//! while indications are enabled.
+
+#[cfg(all(feature = "log", not(feature = "tracing")))]
+use log::{debug, warn};
+
+#[cfg(feature = "tracing")]
+use tracing::{debug, warn};
+
+use crate::{AttError, ChangeBus, Channel, ClientId, ObjectStore, Result, Server, SubscriptionId};
+use dbus::{
+    arg::{PropMap, RefArg, Variant},
+    channel::{MatchingReceiver, Sender, Token},
+    message::{MatchRule, SignalArgs},
+    nonblock::{
+        stdintf::org_freedesktop_dbus::PropertiesPropertiesChanged, MsgMatch, Proxy, SyncConnection,
+    },
+    MethodErr, Path,
+};
+use dbus_crossroads::{Crossroads, IfaceBuilder};
+use ots_core::{
+    ids::{characteristic as chr, service},
+    l2cap::{AddressType, L2capSockAddr, L2capSocket, Psm, Security, SecurityLevel, SocketType},
+};
+use std::{net::Shutdown, sync::Arc, thread, time::Duration};
+use uuid::Uuid;
+
+const SERVICE_NAME: &str = "org.bluez";
+const GATT_MANAGER_INTERFACE: &str = "org.bluez.GattManager1";
+const GATT_SERVICE_INTERFACE: &str = "org.bluez.GattService1";
+const GATT_CHARACTERISTIC_INTERFACE: &str = "org.bluez.GattCharacteristic1";
+const ADVERTISING_MANAGER_INTERFACE: &str = "org.bluez.LEAdvertisingManager1";
+const ADVERTISEMENT_INTERFACE: &str = "org.bluez.LEAdvertisement1";
+const DEVICE_INTERFACE: &str = "org.bluez.Device1";
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// BlueZ transport configuration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BluezConfig {
+    /// Adapter name
+    pub adapter: String,
+    /// D-Bus path of GATT application
+    pub path: String,
+    /// PSM to accept object channels on
+    ///
+    /// Object channels are not accepted when not set.
+    pub psm: Option<u16>,
+    /// Required security level
+    ///
+    /// Levels above [`SecurityLevel::Low`] require encryption to access
+    /// characteristics.
+    pub security: SecurityLevel,
+    /// Local name to advertise service with
+    ///
+    /// Service is not advertised when not set.
+    pub advertise: Option<String>,
+    /// Indicate changes caused by clients
+    ///
+    /// Indication reaches each device which enabled it, including client
+    /// which caused change, contrary to OTS specification.
+    pub client_changes: bool,
+}
+
+impl Default for BluezConfig {
+    fn default() -> Self {
+        Self {
+            adapter: "hci0".into(),
+            path: "/io/github/katyo/ots".into(),
+            psm: Some(Psm::L2CapLeCidOts.into()),
+            security: SecurityLevel::Low,
+            advertise: None,
+            client_changes: false,
+        }
+    }
+}
+
+/// Characteristic object data
+struct Chr<S> {
+    server: Server<S>,
+    uuid: Uuid,
+    instance: usize,
+    service: Path<'static>,
+    flags: Vec<String>,
+}
+
+/// Registered GATT application
+///
+/// Application is unregistered by [`Application::unregister`]. When
+/// application is dropped BlueZ removes it as soon as connection closed.
+pub struct Application {
+    conn: Arc<SyncConnection>,
+    adapter: Path<'static>,
+    path: Path<'static>,
+    token: Token,
+    advertisement: Option<Path<'static>>,
+    device_match: Option<MsgMatch>,
+    listener: Option<Listener>,
+    forwarder: Option<Forwarder>,
+}
+
+impl core::fmt::Debug for Application {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_struct("Application")
+            .field("adapter", &self.adapter)
+            .field("path", &self.path)
+            .finish()
+    }
+}
+
+impl Application {
+    /// Register OTS service on system bus
+    ///
+    /// Must be called within Tokio runtime.
+    pub async fn register<S: ObjectStore + 'static>(
+        server: Server<S>,
+        config: BluezConfig,
+    ) -> Result<Self> {
+        let (resource, conn) = dbus_tokio::connection::new_system_sync()?;
+
+        tokio::spawn(async {
+            let _error = resource.await;
+            warn!("Lost connection to D-Bus: {_error}");
+        });
+
+        Self::register_with(conn, server, config).await
+    }
+
+    /// Register OTS service using D-Bus connection
+    pub async fn register_with<S: ObjectStore + 'static>(
+        conn: Arc<SyncConnection>,
+        server: Server<S>,
+        config: BluezConfig,
+    ) -> Result<Self> {
+        let adapter = Path::from(format!("/org/bluez/{}", config.adapter));
+        let path = Path::from(config.path.clone());
+
+        let mut cr = Crossroads::new();
+        let service_iface = cr.register(GATT_SERVICE_INTERFACE, |b: &mut IfaceBuilder<Uuid>| {
+            b.property("UUID").get(|_, uuid| Ok(uuid.to_string()));
+            b.property("Primary").get(|_, _| Ok(true));
+        });
+        let chr_iface = register_characteristic::<S>(&mut cr);
+
+        let service_path = Path::from(format!("{path}/service0"));
+        let characteristics = server.config().characteristics();
+        let changed_path = characteristics
+            .iter()
+            .position(|characteristic| characteristic.uuid == chr::object_changed)
+            .map(|index| Path::from(format!("{service_path}/char{index}")));
+        cr.insert(path.clone(), &[cr.object_manager()], ());
+        cr.insert(
+            service_path.clone(),
+            &[service_iface],
+            service::object_transfer,
+        );
+
+        let advertisement = config.advertise.as_ref().map(|name| {
+            let advertisement_iface =
+                cr.register(ADVERTISEMENT_INTERFACE, |b: &mut IfaceBuilder<String>| {
+                    b.property("Type").get(|_, _| Ok("peripheral".to_string()));
+                    b.property("ServiceUUIDs")
+                        .get(|_, _| Ok(vec![service::object_transfer.to_string()]));
+                    b.property("LocalName").get(|_, name| Ok(name.clone()));
+                    b.method("Release", (), (), |_, _, ()| {
+                        debug!("Advertisement released");
+                        Ok(())
+                    });
+                });
+            let advertisement_path = Path::from(format!("{path}/advertisement"));
+            cr.insert(
+                advertisement_path.clone(),
+                &[advertisement_iface],
+                name.clone(),
+            );
+            advertisement_path
+        });
+
+        for (index, chr) in characteristics.iter().enumerate() {
+            let mut flags = Vec::new();
+            if chr.read {
+                flags.push(access_flag("read", config.security));
+            }
+            if chr.write {
+                flags.push(access_flag("write", config.security));
+            }
+            if chr.indicate {
+                flags.push("indicate".into());
+            }
+            cr.insert(
+                format!("{service_path}/char{index}"),
+                &[chr_iface],
+                Chr {
+                    server: server.clone(),
+                    uuid: chr.uuid,
+                    instance: chr.instance,
+                    service: service_path.clone(),
+                    flags,
+                },
+            );
+        }
+
+        let mut rule = MatchRule::new_method_call();
+        rule.path = Some(path.clone());
+        rule.path_is_namespace = true;
+        let token = conn.start_receive(
+            rule,
+            Box::new(move |msg, conn| {
+                let _ = cr.handle_message(msg, conn);
+                true
+            }),
+        );
+
+        let mut app = Self {
+            conn,
+            adapter,
+            path,
+            token,
+            advertisement: None,
+            device_match: None,
+            listener: None,
+            forwarder: None,
+        };
+
+        if let Some(path) = changed_path {
+            app.forwarder = Some(Forwarder::new(
+                app.conn.clone(),
+                server.clone(),
+                path,
+                config.client_changes,
+            ));
+        }
+
+        app.device_match = Some(app.watch_devices(server.clone()).await?);
+
+        if let Some(psm) = config.psm {
+            app.listener = Some(Listener::new(server, psm, config.security)?);
+        }
+
+        debug!("Register application {} on {}", app.path, app.adapter);
+        app.adapter_proxy()
+            .method_call::<(), _, _, _>(
+                GATT_MANAGER_INTERFACE,
+                "RegisterApplication",
+                (app.path.clone(), PropMap::new()),
+            )
+            .await?;
+
+        if let Some(advertisement) = advertisement {
+            debug!("Register advertisement {advertisement}");
+            app.adapter_proxy()
+                .method_call::<(), _, _, _>(
+                    ADVERTISING_MANAGER_INTERFACE,
+                    "RegisterAdvertisement",
+                    (advertisement.clone(), PropMap::new()),
+                )
+                .await?;
+            app.advertisement = Some(advertisement);
+        }
+
+        Ok(app)
+    }
+
+    /// Unregister OTS service
+    pub async fn unregister(mut self) -> Result<()> {
+        if let Some(advertisement) = self.advertisement.take() {
+            debug!("Unregister advertisement {advertisement}");
+            self.adapter_proxy()
+                .method_call::<(), _, _, _>(
+                    ADVERTISING_MANAGER_INTERFACE,
+                    "UnregisterAdvertisement",
+                    (advertisement,),
+                )
+                .await?;
+        }
+        debug!("Unregister application {}", self.path);
+        self.adapter_proxy()
+            .method_call::<(), _, _, _>(
+                GATT_MANAGER_INTERFACE,
+                "UnregisterApplication",
+                (self.path.clone(),),
+            )
+            .await?;
+        if let Some(device_match) = self.device_match.take() {
+            self.conn.remove_match(device_match.token()).await?;
+        }
+        Ok(())
+    }
+
+    fn adapter_proxy(&self) -> Proxy<'_, &SyncConnection> {
+        Proxy::new(SERVICE_NAME, self.adapter.clone(), TIMEOUT, &*self.conn)
+    }
+
+    /// Forget clients when devices disconnected
+    async fn watch_devices<S: ObjectStore + 'static>(&self, server: Server<S>) -> Result<MsgMatch> {
+        let sender = SERVICE_NAME.into();
+        let mut rule = PropertiesPropertiesChanged::match_rule(Some(&sender), None).static_clone();
+        rule.path = Some(self.adapter.clone());
+        rule.path_is_namespace = true;
+
+        Ok(self
+            .conn
+            .add_match(rule)
+            .await?
+            .cb(move |msg, changed: PropertiesPropertiesChanged| {
+                let connected = changed
+                    .changed_properties
+                    .get("Connected")
+                    .and_then(|value| value.0.as_u64());
+                if changed.interface_name == DEVICE_INTERFACE && connected == Some(0) {
+                    if let Some(client) = msg.path().as_deref().and_then(device_address) {
+                        debug!("Device {client} disconnected");
+                        server.disconnect(&client);
+                    }
+                }
+                true
+            }))
+    }
+}
+
+impl Drop for Application {
+    fn drop(&mut self) {
+        self.conn.stop_receive(self.token);
+    }
+}
+
+fn register_characteristic<S: ObjectStore + 'static>(
+    cr: &mut Crossroads,
+) -> dbus_crossroads::IfaceToken<Chr<S>> {
+    cr.register(
+        GATT_CHARACTERISTIC_INTERFACE,
+        |b: &mut IfaceBuilder<Chr<S>>| {
+            b.property("UUID").get(|_, chr| Ok(chr.uuid.to_string()));
+            b.property("Service").get(|_, chr| Ok(chr.service.clone()));
+            b.property("Flags").get(|_, chr| Ok(chr.flags.clone()));
+            b.method(
+                "ReadValue",
+                ("options",),
+                ("value",),
+                |_, chr, (options,): (PropMap,)| {
+                    let (client, offset) = request_options(&options);
+                    chr.server
+                        .read_instance(&client, &chr.uuid, chr.instance, offset)
+                        .map(|value| (value,))
+                        .map_err(method_err)
+                },
+            );
+            b.method(
+                "WriteValue",
+                ("value", "options"),
+                (),
+                |ctx, chr, (value, options): (Vec<u8>, PropMap)| {
+                    let (client, offset) = request_options(&options);
+                    let indication = chr
+                        .server
+                        .write_instance(&client, &chr.uuid, chr.instance, offset, &value)
+                        .map_err(method_err)?;
+                    if let Some(value) = indication {
+                        // sent after method reply
+                        ctx.push_msg(value_changed(ctx.path(), value));
+                    }
+                    Ok(())
+                },
+            );
+            // BlueZ starts notifying when first device enabled indications
+            // and stops when last one disabled them
+            b.method("StartNotify", (), (), |_, chr, ()| {
+                if chr.uuid == chr::object_changed {
+                    chr.server.enable_all_changes(true);
+                }
+                Ok(())
+            });
+            b.method("StopNotify", (), (), |_, chr, ()| {
+                if chr.uuid == chr::object_changed {
+                    chr.server.enable_all_changes(false);
+                }
+                Ok(())
+            });
+        },
+    )
+}
+
+/// Get client and offset from request options
+fn request_options(options: &PropMap) -> (ClientId, usize) {
+    let client = options
+        .get("device")
+        .and_then(|device| device.0.as_str())
+        .and_then(device_address)
+        .unwrap_or_default();
+    let offset = options
+        .get("offset")
+        .and_then(|offset| offset.0.as_u64())
+        .unwrap_or_default();
+    (client, offset as _)
+}
+
+/// Extract device address from D-Bus path
+///
+/// Device path looks like `/org/bluez/hci0/dev_00_11_22_33_44_55`.
+fn device_address(path: &str) -> Option<ClientId> {
+    path.rsplit('/')
+        .next()?
+        .strip_prefix("dev_")?
+        .replace('_', ":")
+        .parse()
+        .ok()
+}
+
+fn access_flag(access: &str, security: SecurityLevel) -> String {
+    match security {
+        SecurityLevel::Sdp | SecurityLevel::Low => access.into(),
+        SecurityLevel::Medium => format!("encrypt-{access}"),
+        SecurityLevel::High | SecurityLevel::Fips => format!("encrypt-authenticated-{access}"),
+    }
+}
+
+fn value_changed(path: &Path<'static>, value: Vec<u8>) -> dbus::Message {
+    let mut changed_properties = PropMap::new();
+    changed_properties.insert("Value".into(), Variant(Box::new(value)));
+    PropertiesPropertiesChanged {
+        interface_name: GATT_CHARACTERISTIC_INTERFACE.into(),
+        changed_properties,
+        invalidated_properties: Vec::new(),
+    }
+    .to_emit_message(path)
+}
+
+/// Convert ATT error to BlueZ error
+///
+/// OTS application errors are passed as `org.bluez.Error.Failed`
+/// with error code as message.
+fn method_err(error: AttError) -> MethodErr {
+    let name = match error {
+        AttError::RequestNotSupported => "org.bluez.Error.NotSupported",
+        AttError::InvalidOffset => "org.bluez.Error.InvalidOffset",
+        AttError::InvalidValueLength => "org.bluez.Error.InvalidValueLength",
+        _ => "org.bluez.Error.Failed",
+    };
+    (name, format!("{:#04x}", error as u8)).into()
+}
+
+/// Forwarder of object changes to Object Changed indications
+struct Forwarder {
+    bus: ChangeBus,
+    subscription: SubscriptionId,
+}
+
+impl Forwarder {
+    fn new<S: ObjectStore + 'static>(
+        conn: Arc<SyncConnection>,
+        server: Server<S>,
+        path: Path<'static>,
+        client_changes: bool,
+    ) -> Self {
+        let bus = server.changes();
+        let (subscription, changes) = bus.subscribe();
+        thread::spawn(move || {
+            for change in changes {
+                if change.origin.is_some() && !client_changes {
+                    debug!("Skip change of object {:#x} caused by client", change.id);
+                    continue;
+                }
+                if server.recipients(&change).is_empty() {
+                    debug!("No recipients of change of object {:#x}", change.id);
+                    continue;
+                }
+                if conn
+                    .send(value_changed(&path, change.value().into()))
+                    .is_err()
+                {
+                    warn!("Unable to indicate change of object {:#x}", change.id);
+                }
+            }
+            debug!("Stop forwarding changes");
+        });
+        Self { bus, subscription }
+    }
+}
+
+impl Drop for Forwarder {
+    fn drop(&mut self) {
+        self.bus.unsubscribe(self.subscription);
+    }
+}
+
+/// L2CAP object channel listener
+struct Listener {
+    socket: L2capSocket,
+}
+
+impl Listener {
+    fn new<S: ObjectStore + 'static>(
+        server: Server<S>,
+        psm: u16,
+        level: SecurityLevel,
+    ) -> Result<Self> {
+        let socket = L2capSocket::new(SocketType::SEQPACKET)?;
+        socket.bind(&L2capSockAddr::new(
+            Default::default(),
+            AddressType::Public,
+            psm,
+        ))?;
+        socket.set_security(&Security { level, key_size: 0 })?;
+        socket.listen(1)?;
+
+        let listener = L2capSocket::from(socket.try_clone()?);
+        thread::spawn(move || {
+            while let Ok((socket, peer)) = listener.accept() {
+                match Channel::new(socket) {
+                    Ok(channel) => server.attach_channel(&peer.addr, channel),
+                    Err(_error) => {
+                        warn!("Unable to use channel of {}: {_error}", peer.addr);
+                    }
+                }
+            }
+            debug!("Stop accepting channels");
+        });
+
+        Ok(Self { socket })
+    }
+}
+
+impl Drop for Listener {
+    fn drop(&mut self) {
+        let _ = self.socket.shutdown(Shutdown::Both);
+    }
+}
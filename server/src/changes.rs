@@ -0,0 +1,119 @@
+//! Object change notifications
+//!
+//! Server publishes changes made by clients to [`ChangeBus`] and stores
+//! publish changes caused by local events. Transports subscribe to bus
+//! and deliver Object Changed indications to clients.
+
+#[cfg(all(feature = "log", not(feature = "tracing")))]
+use log::debug;
+
+#[cfg(feature = "tracing")]
+use tracing::debug;
+
+use crate::ClientId;
+use ots_core::types::{ChangeFlag, ObjectChanged};
+use std::sync::{
+    mpsc::{channel, Receiver, Sender},
+    Arc, Mutex,
+};
+
+/// Change of object
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Change {
+    /// Identifier of changed object
+    pub id: u64,
+    /// Kind of change
+    pub flags: ChangeFlag,
+    /// Client which caused change
+    ///
+    /// Changes caused by server itself have no origin.
+    pub origin: Option<ClientId>,
+}
+
+impl Change {
+    /// Create change caused by server
+    pub fn local(id: u64, flags: ChangeFlag) -> Self {
+        Self {
+            id,
+            flags: flags - ChangeFlag::Client,
+            origin: None,
+        }
+    }
+
+    /// Create change caused by client
+    pub fn client(client: &ClientId, id: u64, flags: ChangeFlag) -> Self {
+        Self {
+            id,
+            flags: flags | ChangeFlag::Client,
+            origin: Some(*client),
+        }
+    }
+
+    /// Get Object Changed characteristic value
+    pub fn value(&self) -> [u8; 7] {
+        (&ObjectChanged {
+            flags: self.flags,
+            id: self.id,
+        })
+            .into()
+    }
+}
+
+/// Identifier of bus subscription
+pub type SubscriptionId = usize;
+
+#[derive(Default)]
+struct Subscribers {
+    next: SubscriptionId,
+    senders: Vec<(SubscriptionId, Sender<Change>)>,
+}
+
+/// Bus which delivers object changes to subscribers
+///
+/// Clones share the same subscribers.
+#[derive(Clone, Default)]
+pub struct ChangeBus {
+    subscribers: Arc<Mutex<Subscribers>>,
+}
+
+impl core::fmt::Debug for ChangeBus {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_struct("ChangeBus")
+            .field("subscribers", &self.lock().senders.len())
+            .finish()
+    }
+}
+
+impl ChangeBus {
+    fn lock(&self) -> std::sync::MutexGuard<'_, Subscribers> {
+        self.subscribers
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+    }
+
+    /// Publish change to all subscribers
+    pub fn publish(&self, change: Change) {
+        debug!("Publish change: {change:?}");
+        self.lock()
+            .senders
+            .retain(|(_, sender)| sender.send(change).is_ok());
+    }
+
+    /// Subscribe to changes
+    ///
+    /// Changes are received until subscription is cancelled
+    /// by [`ChangeBus::unsubscribe`] or receiver is dropped.
+    pub fn subscribe(&self) -> (SubscriptionId, Receiver<Change>) {
+        let (sender, receiver) = channel();
+        let mut subscribers = self.lock();
+        let id = subscribers.next;
+        subscribers.next += 1;
+        subscribers.senders.push((id, sender));
+        (id, receiver)
+    }
+
+    /// Cancel subscription
+    pub fn unsubscribe(&self, id: SubscriptionId) {
+        self.lock().senders.retain(|(sid, _)| *sid != id);
+    }
+}
@@ -0,0 +1,143 @@
+//! Object data channel
+//!
+//! Object contents is transferred over connection oriented L2CAP channel
+//! which is opened by client before requesting read or write action.
+
+#[cfg(all(feature = "log", not(feature = "tracing")))]
+use log::debug;
+
+#[cfg(feature = "tracing")]
+use tracing::debug;
+
+use crate::Result;
+use ots_core::l2cap::L2capSocket;
+use std::{
+    net::Shutdown,
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+/// Timeout of receiving data from client
+const RECV_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Data channel of client
+pub struct Channel {
+    socket: L2capSocket,
+    send_mtu: usize,
+    recv_mtu: usize,
+}
+
+impl core::fmt::Debug for Channel {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_struct("Channel")
+            .field("send_mtu", &self.send_mtu)
+            .field("recv_mtu", &self.recv_mtu)
+            .finish()
+    }
+}
+
+impl Channel {
+    /// Create channel using connected L2CAP socket
+    ///
+    /// MTUs are queried from socket.
+    pub fn new(socket: L2capSocket) -> Result<Self> {
+        let send_mtu = socket.send_mtu()?;
+        let recv_mtu = socket.recv_mtu()?;
+        Ok(Self::with_mtu(socket, send_mtu, recv_mtu))
+    }
+
+    /// Create channel using connected socket with known MTUs
+    ///
+    /// Useful for sockets which are not L2CAP one.
+    pub fn with_mtu(socket: L2capSocket, send_mtu: usize, recv_mtu: usize) -> Self {
+        Self {
+            socket,
+            send_mtu: send_mtu.max(1),
+            recv_mtu: recv_mtu.max(1),
+        }
+    }
+
+    /// Send data to client splitting it to MTU sized packets
+    ///
+    /// Channel is owned by transfer, so each transfer uses new channel.
+    pub(crate) fn send(self, data: Vec<u8>) -> Result<Transfer> {
+        let socket = self.socket.try_clone()?;
+        let mtu = self.send_mtu;
+        let thread = thread::spawn(move || {
+            for sdu in data.chunks(mtu) {
+                if let Err(_error) = socket.send_with_flags(sdu, libc::MSG_NOSIGNAL) {
+                    debug!("Send error: {_error}");
+                    break;
+                }
+            }
+        });
+        Ok(Transfer::new(self.socket, thread))
+    }
+
+    /// Receive up to length bytes from client
+    ///
+    /// Each received packet is passed to handler with offset relative to
+    /// transfer start. Receiving stops when length is reached, client closed
+    /// channel or handler returned `false`. Then number of handled bytes
+    /// is passed to completion handler.
+    pub(crate) fn recv(
+        self,
+        length: usize,
+        mut handler: impl FnMut(usize, &[u8]) -> bool + Send + 'static,
+        done: impl FnOnce(usize) + Send + 'static,
+    ) -> Result<Transfer> {
+        let socket = self.socket.try_clone()?;
+        socket.set_read_timeout(Some(RECV_TIMEOUT))?;
+        let mtu = self.recv_mtu;
+        let thread = thread::spawn(move || {
+            let mut sdu = vec![0u8; mtu];
+            let mut offset = 0;
+            while offset < length {
+                match std::io::Read::read(&mut &socket, &mut sdu) {
+                    Ok(0) => break,
+                    Err(_error) => {
+                        debug!("Receive error: {_error}");
+                        break;
+                    }
+                    Ok(len) => {
+                        let len = len.min(length - offset);
+                        if !handler(offset, &sdu[..len]) {
+                            break;
+                        }
+                        offset += len;
+                    }
+                }
+            }
+            done(offset);
+        });
+        Ok(Transfer::new(self.socket, thread))
+    }
+}
+
+/// Running data transfer
+pub(crate) struct Transfer {
+    socket: L2capSocket,
+    thread: JoinHandle<()>,
+}
+
+impl Transfer {
+    fn new(socket: L2capSocket, thread: JoinHandle<()>) -> Self {
+        Self { socket, thread }
+    }
+
+    /// Check that transfer is finished
+    pub fn is_finished(&self) -> bool {
+        self.thread.is_finished()
+    }
+
+    /// Wait for transfer completion
+    pub fn join(self) {
+        let _ = self.thread.join();
+    }
+
+    /// Interrupt transfer by closing channel
+    pub fn abort(self) {
+        let _ = self.socket.shutdown(Shutdown::Both);
+        self.join();
+    }
+}
@@ -0,0 +1,130 @@
+//! Execute handlers
+//!
+//! OACP Execute requests are dispatched by type of current object to
+//! handlers registered in [`ExecuteRegistry`].
+
+use crate::{ActionRc, Metadata, ObjectStore};
+use ots_core::execute::{ExecuteCommand, ExecuteDecode, ExecuteEncode};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Handler of Execute requests
+///
+/// Handler gets store, metadata of executed object and request parameter
+/// and returns response parameter.
+pub trait ExecuteHandler: Send {
+    /// Execute object
+    fn execute(
+        &mut self,
+        store: &mut dyn ObjectStore,
+        object: &Metadata,
+        param: &[u8],
+    ) -> Result<Vec<u8>, ActionRc>;
+}
+
+impl<F> ExecuteHandler for F
+where
+    F: FnMut(&mut dyn ObjectStore, &Metadata, &[u8]) -> Result<Vec<u8>, ActionRc> + Send,
+{
+    fn execute(
+        &mut self,
+        store: &mut dyn ObjectStore,
+        object: &Metadata,
+        param: &[u8],
+    ) -> Result<Vec<u8>, ActionRc> {
+        self(store, object, param)
+    }
+}
+
+/// Handler of typed command
+struct CommandHandler<C, F> {
+    func: F,
+    _command: core::marker::PhantomData<fn() -> C>,
+}
+
+impl<C, F> ExecuteHandler for CommandHandler<C, F>
+where
+    C: ExecuteCommand,
+    F: FnMut(&mut dyn ObjectStore, &Metadata, C::Param) -> Result<C::Response, ActionRc> + Send,
+{
+    fn execute(
+        &mut self,
+        store: &mut dyn ObjectStore,
+        object: &Metadata,
+        param: &[u8],
+    ) -> Result<Vec<u8>, ActionRc> {
+        let param = C::Param::decode(param).map_err(|_| ActionRc::InvalidParameter)?;
+        (self.func)(store, object, param).map(|response| response.encode())
+    }
+}
+
+/// Registry of Execute handlers by object type
+#[derive(Default)]
+pub struct ExecuteRegistry {
+    handlers: HashMap<Uuid, Box<dyn ExecuteHandler>>,
+}
+
+impl core::fmt::Debug for ExecuteRegistry {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_set().entries(self.handlers.keys()).finish()
+    }
+}
+
+impl ExecuteRegistry {
+    /// Create empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register handler for objects of type
+    ///
+    /// Previous handler of the same type is replaced.
+    pub fn register(&mut self, type_: Uuid, handler: impl ExecuteHandler + 'static) {
+        self.handlers.insert(type_, Box::new(handler));
+    }
+
+    /// Register handler of typed command
+    ///
+    /// Request parameter which cannot be decoded is rejected with
+    /// [`ActionRc::InvalidParameter`].
+    pub fn register_command<C: ExecuteCommand + 'static>(
+        &mut self,
+        func: impl FnMut(&mut dyn ObjectStore, &Metadata, C::Param) -> Result<C::Response, ActionRc>
+            + Send
+            + 'static,
+    ) {
+        self.register(
+            C::OBJECT_TYPE,
+            CommandHandler::<C, _> {
+                func,
+                _command: core::marker::PhantomData,
+            },
+        );
+    }
+
+    /// Remove handler for objects of type
+    pub fn unregister(&mut self, type_: &Uuid) {
+        self.handlers.remove(type_);
+    }
+
+    /// Check that objects of type can be executed
+    pub fn contains(&self, type_: &Uuid) -> bool {
+        self.handlers.contains_key(type_)
+    }
+
+    /// Execute object
+    ///
+    /// Objects of types without handler are rejected with
+    /// [`ActionRc::UnsupportedType`].
+    pub fn execute(
+        &mut self,
+        store: &mut dyn ObjectStore,
+        object: &Metadata,
+        param: &[u8],
+    ) -> Result<Vec<u8>, ActionRc> {
+        self.handlers
+            .get_mut(&object.type_)
+            .ok_or(ActionRc::UnsupportedType)?
+            .execute(store, object, param)
+    }
+}
@@ -0,0 +1,125 @@
+#![forbid(future_incompatible)]
+#![deny(bad_style, missing_docs)]
+#![doc = include_str!("../README.md")]
+
+#[cfg(not(any(feature = "log", feature = "tracing")))]
+#[macro_use]
+mod log_stub {
+    macro_rules! debug {
+        ($($t:tt)*) => {};
+    }
+    macro_rules! warn {
+        ($($t:tt)*) => {};
+    }
+}
+
+#[cfg(feature = "bluez")]
+pub mod bluez;
+mod changes;
+mod channel;
+pub mod execute;
+mod lock;
+mod server;
+pub mod store;
+
+pub use changes::{Change, ChangeBus, SubscriptionId};
+pub use channel::Channel;
+pub use execute::{ExecuteHandler, ExecuteRegistry};
+pub use ots_core::{
+    execute::{ExecuteCommand, ExecuteDecode, ExecuteEncode},
+    l2cap::{L2capSocket, MacAddress, SecurityLevel},
+    list::ObjectList,
+    types::{
+        ActionFeature, ActionRc, ChangeFlag, DateTime, Filter, ListFeature, ListRc, Metadata,
+        Property, SortOrder, WriteMode,
+    },
+    Error as CoreError,
+};
+pub use server::{
+    Characteristic, ClientId, Config, Server, CHARACTERISTICS, DIRECTORY_ID, DIRECTORY_TYPE,
+    FILTERS,
+};
+pub use store::{FsConfig, FsStore, MemoryStore, ObjectStore};
+
+/// OTS server result
+pub type Result<T> = core::result::Result<T, Error>;
+
+/// OTS server error
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    /// Input/output error
+    #[error("Input/Output Error: {0}")]
+    Io(#[from] std::io::Error),
+    /// Core error
+    #[error("OTS core error: {0}")]
+    Core(#[from] CoreError),
+    /// D-Bus error
+    #[cfg(feature = "bluez")]
+    #[error("D-Bus error: {0}")]
+    DBus(#[from] dbus::Error),
+    /// Object not found
+    #[error("Object not found")]
+    NotFound,
+    /// Range exceeds object data
+    #[error("Invalid range")]
+    InvalidRange,
+    /// Invalid object name
+    #[error("Invalid name: {0:?}")]
+    InvalidName(String),
+    /// Object name already in use
+    #[error("Name already exists: {0:?}")]
+    NameExists(String),
+    /// Operation is not permitted by object properties
+    #[error("Not permitted")]
+    NotPermitted,
+}
+
+impl Error {
+    /// Get OACP result code which corresponds to error
+    pub fn action_rc(&self) -> ActionRc {
+        match self {
+            Self::NotFound => ActionRc::InvalidObject,
+            Self::InvalidRange | Self::InvalidName(_) | Self::NameExists(_) => {
+                ActionRc::InvalidParameter
+            }
+            Self::NotPermitted => ActionRc::ProcedureNotPermitted,
+            Self::Core(CoreError::ActionError(rc)) => *rc,
+            Self::Io(error) if error.kind() == std::io::ErrorKind::StorageFull => {
+                ActionRc::InsufficientResources
+            }
+            _ => ActionRc::OperationFailed,
+        }
+    }
+}
+
+/// GATT request result
+pub type AttResult<T> = core::result::Result<T, AttError>;
+
+/// ATT error of GATT request
+///
+/// OTS specific application errors are included.
+#[derive(thiserror::Error, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum AttError {
+    /// Request not supported
+    #[error("Request not supported")]
+    RequestNotSupported = 0x06,
+    /// Invalid offset of long read or write
+    #[error("Invalid offset")]
+    InvalidOffset = 0x07,
+    /// Invalid attribute value length
+    #[error("Invalid attribute value length")]
+    InvalidValueLength = 0x0d,
+    /// Written value was rejected
+    #[error("Write request rejected")]
+    WriteRequestRejected = 0x80,
+    /// Current object is not selected
+    #[error("Object not selected")]
+    ObjectNotSelected = 0x81,
+    /// Server cannot serve more clients
+    #[error("Concurrency limit exceeded")]
+    ConcurrencyLimitExceeded = 0x82,
+    /// Object with requested name already exists
+    #[error("Object name already exists")]
+    ObjectNameAlreadyExists = 0x83,
+}
@@ -0,0 +1,57 @@
+//! Object locks
+//!
+//! Object is locked while its data is transferred through channel.
+//! Readers share the lock, writer holds it exclusively. Each client
+//! runs at most one transfer so it holds at most one lock.
+
+use crate::{ActionRc, ClientId};
+use std::collections::HashMap;
+
+/// Kind of object access
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Access {
+    /// Object data is read
+    Read,
+    /// Object data is written or object is deleted
+    Write,
+}
+
+/// Locks held by clients
+#[derive(Debug, Default)]
+pub(crate) struct Locks {
+    held: HashMap<ClientId, (u64, Access)>,
+}
+
+impl Locks {
+    /// Check that object can be accessed
+    pub fn check(&self, id: u64, access: Access) -> Result<(), ActionRc> {
+        let conflict = self.held.values().any(|(locked, held)| {
+            *locked == id && (access == Access::Write || *held == Access::Write)
+        });
+        if conflict {
+            Err(ActionRc::ObjectLocked)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Lock object for client
+    ///
+    /// Previous lock of client is replaced.
+    pub fn acquire(&mut self, client: &ClientId, id: u64, access: Access) -> Result<(), ActionRc> {
+        self.release(client);
+        self.check(id, access)?;
+        self.held.insert(*client, (id, access));
+        Ok(())
+    }
+
+    /// Release lock of client
+    pub fn release(&mut self, client: &ClientId) {
+        self.held.remove(client);
+    }
+
+    /// Check that object is locked by any client
+    pub fn is_locked(&self, id: u64) -> bool {
+        self.held.values().any(|(locked, _)| *locked == id)
+    }
+}
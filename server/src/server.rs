@@ -0,0 +1,983 @@
+//! OTS server engine
+//!
+//! [`Server`] implements OTS procedures on top of [`ObjectStore`] and keeps
+//! state of each connected client. It does not depend on particular GATT
+//! transport: transport passes characteristic reads and writes of clients
+//! and delivers returned indications back.
+
+#[cfg(all(feature = "log", not(feature = "tracing")))]
+use log::{debug, warn};
+
+#[cfg(feature = "tracing")]
+use tracing::{debug, warn};
+
+use crate::{
+    channel::{Channel, Transfer},
+    execute::ExecuteRegistry,
+    lock::{Access, Locks},
+    store::{check_range, ObjectStore},
+    ActionFeature, ActionRc, AttError, AttResult, Change, ChangeBus, ChangeFlag, Error,
+    ListFeature, ListRc, Metadata, Property, SortOrder, WriteMode,
+};
+use ots_core::{
+    ids::characteristic as chr,
+    l2cap::MacAddress,
+    list::ObjectList,
+    types::{
+        self, ActionOp, ActionReq, ActionRes, DirEntries, Filter, ListOp, ListReq, ListRes, Sizes,
+        Ule48,
+    },
+    Error as CoreError,
+};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, MutexGuard},
+};
+use uuid::Uuid;
+
+/// Maximum length of object name in bytes
+const MAX_NAME_LEN: usize = 120;
+
+/// Number of Object List Filter characteristics
+pub const FILTERS: usize = 3;
+
+/// Identifier of Directory Listing object
+pub const DIRECTORY_ID: u64 = 0;
+
+/// Type of Directory Listing object (org.bluetooth.object.directory_listing)
+pub const DIRECTORY_TYPE: Uuid = Uuid::from_u128(0x00002acb_0000_1000_8000_00805f9b34fb);
+
+/// Name of Directory Listing object
+const DIRECTORY_NAME: &str = "Directory";
+
+/// Client identifier
+///
+/// Address of connected device is used to match GATT requests with
+/// data channels of the same client.
+pub type ClientId = MacAddress;
+
+/// Server configuration
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Config {
+    /// Supported object actions
+    pub action_features: ActionFeature,
+    /// Supported object list operations
+    pub list_features: ListFeature,
+    /// Expose Directory Listing object
+    pub directory_listing: bool,
+    /// Reject all modifications of objects
+    ///
+    /// Only reading actions are supported and Object Name and Object
+    /// Properties characteristics are exposed without write access.
+    pub read_only: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            action_features: ActionFeature::all() - ActionFeature::Execute,
+            list_features: ListFeature::all(),
+            directory_listing: true,
+            read_only: false,
+        }
+    }
+}
+
+impl Config {
+    /// Get characteristics exposed with configuration
+    ///
+    /// Same as [`CHARACTERISTICS`] except access of metadata
+    /// characteristics in read-only mode.
+    pub fn characteristics(&self) -> Vec<Characteristic> {
+        CHARACTERISTICS
+            .iter()
+            .map(|characteristic| Characteristic {
+                write: characteristic.write
+                    && !(self.read_only && is_metadata(&characteristic.uuid)),
+                ..*characteristic
+            })
+            .collect()
+    }
+}
+
+/// Check that characteristic holds writable object metadata
+fn is_metadata(uuid: &Uuid) -> bool {
+    matches!(
+        *uuid,
+        chr::object_name
+            | chr::object_first_created
+            | chr::object_last_modified
+            | chr::object_properties
+    )
+}
+
+/// GATT characteristic of OTS service
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Characteristic {
+    /// Characteristic UUID
+    pub uuid: Uuid,
+    /// Value can be read
+    pub read: bool,
+    /// Value can be written
+    pub write: bool,
+    /// Value can be indicated
+    pub indicate: bool,
+    /// Index of characteristic among ones with the same UUID
+    pub instance: usize,
+}
+
+macro_rules! characteristics {
+    ($($name:ident $([$instance:literal])?: $($flag:ident)|*;)*) => {
+        /// Characteristics of OTS service
+        pub const CHARACTERISTICS: &[Characteristic] = &[
+            $(Characteristic {
+                uuid: chr::$name,
+                $($flag: true,)*
+                $(instance: $instance,)?
+                ..Characteristic {
+                    uuid: Uuid::nil(),
+                    read: false,
+                    write: false,
+                    indicate: false,
+                    instance: 0,
+                }
+            },)*
+        ];
+    };
+}
+
+characteristics! {
+    ots_feature: read;
+    object_name: read | write;
+    object_type: read;
+    object_size: read;
+    object_first_created: read | write;
+    object_last_modified: read | write;
+    object_id: read;
+    object_properties: read | write;
+    object_action_control_point: write | indicate;
+    object_list_control_point: write | indicate;
+    object_list_filter[0]: read | write;
+    object_list_filter[1]: read | write;
+    object_list_filter[2]: read | write;
+    object_changed: indicate;
+}
+
+/// State of connected client
+#[derive(Default)]
+struct Session {
+    current: Option<u64>,
+    order: Option<SortOrder>,
+    filters: [Filter; FILTERS],
+    changes: bool,
+    /// Snapshot of Directory Listing object contents
+    directory: Option<Vec<u8>>,
+    channel: Option<Channel>,
+    transfer: Option<Transfer>,
+}
+
+impl Session {
+    fn transfer_running(&self) -> bool {
+        self.transfer
+            .as_ref()
+            .map(|transfer| !transfer.is_finished())
+            .unwrap_or_default()
+    }
+}
+
+struct State<S> {
+    store: S,
+    config: Config,
+    sessions: HashMap<ClientId, Session>,
+    /// Object Changed indications are enabled for new clients
+    changes: bool,
+    locks: Locks,
+    execute: ExecuteRegistry,
+}
+
+impl<S> State<S> {
+    fn session(&mut self, client: &ClientId) -> &mut Session {
+        let changes = self.changes;
+        self.sessions.entry(*client).or_insert_with(|| Session {
+            changes,
+            ..Default::default()
+        })
+    }
+
+    /// Release locks of finished transfers
+    ///
+    /// Transfer also finishes when client closes channel.
+    fn release_finished(&mut self) {
+        for (client, session) in &mut self.sessions {
+            if session.transfer.as_ref().is_some_and(Transfer::is_finished) {
+                if let Some(transfer) = session.transfer.take() {
+                    transfer.join();
+                }
+                self.locks.release(client);
+            }
+        }
+    }
+}
+
+impl<S: ObjectStore> State<S> {
+    /// Get metadata of all objects including Directory Listing
+    fn list(&self) -> Result<Vec<Metadata>, Error> {
+        let mut objects = self.store.list()?;
+        if self.config.directory_listing {
+            let size = encode_directory(&objects).len();
+            objects.insert(0, directory_metadata(size));
+        }
+        Ok(objects)
+    }
+
+    /// Take new snapshot of Directory Listing for client
+    fn refresh_directory(&mut self, client: &ClientId) -> Result<&[u8], Error> {
+        let directory = encode_directory(&self.store.list()?);
+        Ok(self.session(client).directory.insert(directory))
+    }
+
+    /// Get snapshot of Directory Listing taken for client
+    fn directory(&mut self, client: &ClientId) -> Result<&[u8], Error> {
+        if self.session(client).directory.is_none() {
+            self.refresh_directory(client)?;
+        }
+        Ok(self
+            .session(client)
+            .directory
+            .as_deref()
+            .unwrap_or_default())
+    }
+
+    /// Get metadata of object
+    fn metadata(&mut self, client: &ClientId, id: u64) -> Result<Metadata, Error> {
+        if id == DIRECTORY_ID && self.config.directory_listing {
+            let size = self.directory(client)?.len();
+            Ok(directory_metadata(size))
+        } else {
+            self.store.metadata(id)
+        }
+    }
+
+    /// Read range of object data
+    fn read(
+        &mut self,
+        client: &ClientId,
+        id: u64,
+        offset: usize,
+        length: usize,
+    ) -> Result<Vec<u8>, Error> {
+        if id == DIRECTORY_ID && self.config.directory_listing {
+            let directory = self.directory(client)?;
+            check_range(offset, length, directory.len())?;
+            Ok(directory[offset..offset + length].to_vec())
+        } else {
+            self.store.read(id, offset, length)
+        }
+    }
+}
+
+struct Shared<S> {
+    state: Mutex<State<S>>,
+    changes: ChangeBus,
+}
+
+/// OTS server
+///
+/// Clones share the same state.
+pub struct Server<S> {
+    shared: Arc<Shared<S>>,
+}
+
+impl<S> Clone for Server<S> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<S> Server<S> {
+    fn lock(&self) -> MutexGuard<'_, State<S>> {
+        self.shared
+            .state
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+    }
+}
+
+impl<S> core::fmt::Debug for Server<S> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        let state = self.lock();
+        f.debug_struct("Server")
+            .field("config", &state.config)
+            .field("sessions", &state.sessions.len())
+            .finish()
+    }
+}
+
+impl<S: ObjectStore + 'static> Server<S> {
+    /// Create server which serves objects from store
+    ///
+    /// In read-only mode modifying actions and list operations are
+    /// removed from supported features.
+    pub fn new(mut store: S, mut config: Config) -> Self {
+        if config.read_only {
+            config.action_features &=
+                ActionFeature::Read | ActionFeature::CheckSum | ActionFeature::Abort;
+            config.list_features -= ListFeature::ClearMark;
+        }
+        let changes = ChangeBus::default();
+        store.set_change_bus(changes.clone());
+        Self {
+            shared: Arc::new(Shared {
+                state: Mutex::new(State {
+                    store,
+                    config,
+                    sessions: HashMap::new(),
+                    changes: false,
+                    locks: Locks::default(),
+                    execute: ExecuteRegistry::new(),
+                }),
+                changes,
+            }),
+        }
+    }
+
+    /// Get server configuration
+    pub fn config(&self) -> Config {
+        self.lock().config
+    }
+
+    /// Access object store
+    ///
+    /// Server is blocked while function is running.
+    pub fn with_store<R>(&self, func: impl FnOnce(&mut S) -> R) -> R {
+        func(&mut self.lock().store)
+    }
+
+    /// Access registry of Execute handlers
+    ///
+    /// Execute requests are refused unless [`ActionFeature::Execute`]
+    /// is enabled in configuration.
+    pub fn with_execute<R>(&self, func: impl FnOnce(&mut ExecuteRegistry) -> R) -> R {
+        func(&mut self.lock().execute)
+    }
+
+    /// Get identifier of object selected by client
+    pub fn current(&self, client: &ClientId) -> Option<u64> {
+        self.lock()
+            .sessions
+            .get(client)
+            .and_then(|session| session.current)
+    }
+
+    /// Get bus of object changes
+    ///
+    /// Transports subscribe to bus to deliver Object Changed indications.
+    /// Changes made locally should be published to it too.
+    pub fn changes(&self) -> ChangeBus {
+        self.shared.changes.clone()
+    }
+
+    /// Enable or disable Object Changed indications for client
+    pub fn enable_changes(&self, client: &ClientId, enable: bool) {
+        self.lock().session(client).changes = enable;
+    }
+
+    /// Enable or disable Object Changed indications for all clients
+    ///
+    /// Clients which connect later get the same setting. Useful for
+    /// transports which only know whether any client enabled indications.
+    pub fn enable_all_changes(&self, enable: bool) {
+        let mut state = self.lock();
+        state.changes = enable;
+        for session in state.sessions.values_mut() {
+            session.changes = enable;
+        }
+    }
+
+    /// Get clients which should be indicated about change
+    ///
+    /// Client which caused change is not indicated.
+    pub fn recipients(&self, change: &Change) -> Vec<ClientId> {
+        self.lock()
+            .sessions
+            .iter()
+            .filter(|(client, session)| session.changes && change.origin != Some(**client))
+            .map(|(client, _)| *client)
+            .collect()
+    }
+
+    /// Check that object data is transferred to or from any client
+    pub fn is_locked(&self, id: u64) -> bool {
+        let mut state = self.lock();
+        state.release_finished();
+        state.locks.is_locked(id)
+    }
+
+    /// Attach data channel of client
+    ///
+    /// Previous channel of client is closed.
+    pub fn attach_channel(&self, client: &ClientId, channel: Channel) {
+        debug!("Attach channel of {client}: {channel:?}");
+        self.lock().session(client).channel = Some(channel);
+    }
+
+    /// Forget client
+    ///
+    /// Running transfer is interrupted, data channel is closed and
+    /// object lock is released.
+    pub fn disconnect(&self, client: &ClientId) {
+        let session = self.lock().sessions.remove(client);
+        if let Some(transfer) = session.and_then(|session| session.transfer) {
+            transfer.abort();
+        }
+        self.lock().locks.release(client);
+    }
+
+    /// Read characteristic value
+    ///
+    /// The offset is used for long reads.
+    pub fn read(&self, client: &ClientId, uuid: &Uuid, offset: usize) -> AttResult<Vec<u8>> {
+        self.read_instance(client, uuid, 0, offset)
+    }
+
+    /// Read value of characteristic instance
+    ///
+    /// Instance selects one of characteristics with the same UUID
+    /// (see [`Characteristic::instance`]).
+    pub fn read_instance(
+        &self,
+        client: &ClientId,
+        uuid: &Uuid,
+        instance: usize,
+        offset: usize,
+    ) -> AttResult<Vec<u8>> {
+        let mut value = self.read_value(client, uuid, instance)?;
+        if offset > value.len() {
+            return Err(AttError::InvalidOffset);
+        }
+        Ok(value.split_off(offset))
+    }
+
+    fn read_value(&self, client: &ClientId, uuid: &Uuid, instance: usize) -> AttResult<Vec<u8>> {
+        let mut state = self.lock();
+
+        match *uuid {
+            chr::ots_feature => {
+                // clients start with reading features, so they become known
+                // to be indicated about changes
+                state.session(client);
+                let mut raw = Vec::with_capacity(8);
+                raw.extend_from_slice(&<[u8; 4]>::from(state.config.action_features));
+                raw.extend_from_slice(&<[u8; 4]>::from(state.config.list_features));
+                return Ok(raw);
+            }
+            chr::object_list_filter => {
+                let filter = state
+                    .session(client)
+                    .filters
+                    .get(instance)
+                    .ok_or(AttError::RequestNotSupported)?;
+                return Ok(filter.into());
+            }
+            _ => (),
+        }
+
+        let id = state
+            .session(client)
+            .current
+            .ok_or(AttError::ObjectNotSelected)?;
+        if id == DIRECTORY_ID && state.config.directory_listing && *uuid == chr::object_size {
+            // size is read before contents so directory is kept up to date
+            state
+                .refresh_directory(client)
+                .map_err(|_| AttError::ObjectNotSelected)?;
+        }
+        let metadata = state
+            .metadata(client, id)
+            .map_err(|_| AttError::ObjectNotSelected)?;
+
+        fn time(time: &Option<types::DateTime>) -> Vec<u8> {
+            time.as_ref()
+                .map(<[u8; 7]>::from)
+                .unwrap_or_default()
+                .into()
+        }
+
+        Ok(match *uuid {
+            chr::object_id => <[u8; 6]>::from(Ule48::from(id)).into(),
+            chr::object_name => metadata.name.into_bytes(),
+            chr::object_type => types::uuid_to_raw(&metadata.type_),
+            chr::object_size => <[u8; 8]>::from(&Sizes {
+                current: metadata.current_size.unwrap_or_default() as _,
+                allocated: metadata.allocated_size.unwrap_or_default() as _,
+            })
+            .into(),
+            chr::object_first_created => time(&metadata.first_created),
+            chr::object_last_modified => time(&metadata.last_modified),
+            chr::object_properties => <[u8; 4]>::from(metadata.properties).into(),
+            _ => return Err(AttError::RequestNotSupported),
+        })
+    }
+
+    /// Write characteristic value
+    ///
+    /// The offset is used for long writes. Returns value which should be
+    /// indicated to client through the same characteristic.
+    pub fn write(
+        &self,
+        client: &ClientId,
+        uuid: &Uuid,
+        offset: usize,
+        value: &[u8],
+    ) -> AttResult<Option<Vec<u8>>> {
+        self.write_instance(client, uuid, 0, offset, value)
+    }
+
+    /// Write value of characteristic instance
+    ///
+    /// Instance selects one of characteristics with the same UUID
+    /// (see [`Characteristic::instance`]).
+    pub fn write_instance(
+        &self,
+        client: &ClientId,
+        uuid: &Uuid,
+        instance: usize,
+        offset: usize,
+        value: &[u8],
+    ) -> AttResult<Option<Vec<u8>>> {
+        match *uuid {
+            chr::object_action_control_point => {
+                check_value(offset, value)?;
+                Ok(Some(match ActionReq::try_from(value) {
+                    Ok(req) => {
+                        debug!("Action request of {client}: {req:?}");
+                        let res = self.action(client, &req);
+                        ActionRes::encode(req.op(), res.as_ref().map_err(|rc| *rc))
+                    }
+                    Err(error) => {
+                        let rc = match error {
+                            CoreError::BadOpCode { .. } => ActionRc::OperationNotSupported,
+                            _ => ActionRc::InvalidParameter,
+                        };
+                        vec![ActionOp::Response as _, value[0], rc as _]
+                    }
+                }))
+            }
+            chr::object_list_control_point => {
+                check_value(offset, value)?;
+                Ok(Some(match ListReq::try_from(value) {
+                    Ok(req) => {
+                        debug!("List request of {client}: {req:?}");
+                        let res = self.list(client, &req);
+                        ListRes::encode(req.op(), res.as_ref().map_err(|rc| *rc))
+                    }
+                    Err(error) => {
+                        let rc = match error {
+                            CoreError::BadOpCode { .. } => ListRc::OperationNotSupported,
+                            _ => ListRc::InvalidParameter,
+                        };
+                        vec![ListOp::Response as _, value[0], rc as _]
+                    }
+                }))
+            }
+            chr::object_name
+            | chr::object_first_created
+            | chr::object_last_modified
+            | chr::object_properties
+            | chr::object_list_filter => {
+                let value = if offset > 0 {
+                    let mut prefix = self.read_value(client, uuid, instance)?;
+                    if offset > prefix.len() {
+                        return Err(AttError::InvalidOffset);
+                    }
+                    prefix.truncate(offset);
+                    prefix.extend_from_slice(value);
+                    prefix
+                } else {
+                    value.to_vec()
+                };
+                self.write_value(client, uuid, instance, value)?;
+                Ok(None)
+            }
+            _ => Err(AttError::RequestNotSupported),
+        }
+    }
+
+    fn write_value(
+        &self,
+        client: &ClientId,
+        uuid: &Uuid,
+        instance: usize,
+        value: Vec<u8>,
+    ) -> AttResult<()> {
+        let mut state = self.lock();
+
+        if *uuid == chr::object_list_filter {
+            if value.is_empty() {
+                return Err(AttError::InvalidValueLength);
+            }
+            let filter =
+                Filter::try_from(value.as_slice()).map_err(|_| AttError::WriteRequestRejected)?;
+            debug!("Filter {instance} of {client}: {filter:?}");
+            *state
+                .session(client)
+                .filters
+                .get_mut(instance)
+                .ok_or(AttError::RequestNotSupported)? = filter;
+            return Ok(());
+        }
+
+        if state.config.read_only && is_metadata(uuid) {
+            return Err(AttError::WriteRequestRejected);
+        }
+
+        let id = state
+            .session(client)
+            .current
+            .ok_or(AttError::ObjectNotSelected)?;
+        if id == DIRECTORY_ID {
+            return Err(AttError::WriteRequestRejected);
+        }
+
+        let result = match *uuid {
+            chr::object_name => {
+                let name = String::from_utf8(value).map_err(|_| AttError::WriteRequestRejected)?;
+                if name.len() > MAX_NAME_LEN {
+                    return Err(AttError::WriteRequestRejected);
+                }
+                state.store.set_name(id, &name)
+            }
+            chr::object_first_created | chr::object_last_modified => {
+                let time = types::DateTime::try_from(value.as_slice())
+                    .map_err(|_| AttError::InvalidValueLength)?;
+                if *uuid == chr::object_first_created {
+                    state.store.set_first_created(id, &time)
+                } else {
+                    state.store.set_last_modified(id, &time)
+                }
+            }
+            _ => {
+                let raw: [u8; 4] = value
+                    .as_slice()
+                    .try_into()
+                    .map_err(|_| AttError::InvalidValueLength)?;
+                let properties = Property::from_bits(u32::from_le_bytes(raw))
+                    .ok_or(AttError::WriteRequestRejected)?;
+                state.store.set_properties(id, properties)
+            }
+        };
+
+        result.map_err(|error| match error {
+            Error::NameExists(_) => AttError::ObjectNameAlreadyExists,
+            Error::NotFound => AttError::ObjectNotSelected,
+            _ => AttError::WriteRequestRejected,
+        })?;
+        self.publish(Change::client(client, id, ChangeFlag::Metadata));
+        Ok(())
+    }
+
+    fn list(&self, client: &ClientId, req: &ListReq) -> Result<ListRes, ListRc> {
+        let mut state = self.lock();
+
+        let feature = match req {
+            ListReq::GoTo { .. } => Some(ListFeature::GoTo),
+            ListReq::Order { .. } => Some(ListFeature::Order),
+            ListReq::NumberOf => Some(ListFeature::NumberOf),
+            ListReq::ClearMark => Some(ListFeature::ClearMark),
+            _ => None,
+        };
+        if let Some(feature) = feature {
+            if !state.config.list_features.contains(feature) {
+                return Err(ListRc::OperationNotSupported);
+            }
+        }
+
+        let objects = state.list().map_err(|_| ListRc::OperationFailed)?;
+
+        match req {
+            ListReq::Order { order } => {
+                state.session(client).order = Some(*order);
+                return Ok(ListRes::None);
+            }
+            ListReq::ClearMark => {
+                // marks are cleared regardless of filters
+                for object in objects {
+                    if object.properties.contains(Property::Mark) {
+                        let id = object.id.unwrap_or_default();
+                        state
+                            .store
+                            .set_properties(id, object.properties - Property::Mark)
+                            .map_err(|_| ListRc::OperationFailed)?;
+                        self.publish(Change::client(client, id, ChangeFlag::Metadata));
+                    }
+                }
+                return Ok(ListRes::None);
+            }
+            _ => (),
+        }
+
+        let session = state.session(client);
+        let objects = ObjectList::new(objects, &session.filters, session.order);
+
+        let object = match req {
+            ListReq::First => objects.first()?,
+            ListReq::Last => objects.last()?,
+            ListReq::Previous => objects.previous(session.current)?,
+            ListReq::Next => objects.next(session.current)?,
+            ListReq::GoTo { id } => objects.go_to(*id)?,
+            ListReq::NumberOf => {
+                return Ok(ListRes::NumberOf {
+                    count: objects.number_of()?,
+                })
+            }
+            ListReq::Order { .. } | ListReq::ClearMark => unreachable!(),
+        };
+
+        session.current = object.id;
+        if object.id == Some(DIRECTORY_ID) {
+            session.directory = None;
+        }
+        Ok(ListRes::None)
+    }
+
+    fn action(&self, client: &ClientId, req: &ActionReq) -> Result<ActionRes, ActionRc> {
+        let mut state = self.lock();
+        state.release_finished();
+
+        let (feature, property) = match req {
+            ActionReq::Create { .. } => (ActionFeature::Create, None),
+            ActionReq::Delete => (ActionFeature::Delete, Some(Property::Delete)),
+            ActionReq::CheckSum { .. } => (ActionFeature::CheckSum, None),
+            ActionReq::Execute { .. } => (ActionFeature::Execute, Some(Property::Execute)),
+            ActionReq::Read { .. } => (ActionFeature::Read, Some(Property::Read)),
+            ActionReq::Write { .. } => (ActionFeature::Write, Some(Property::Write)),
+            ActionReq::Abort => (ActionFeature::Abort, None),
+        };
+        if !state.config.action_features.contains(feature) {
+            return Err(ActionRc::OperationNotSupported);
+        }
+
+        match req {
+            ActionReq::Create { size, type_ } => {
+                let id = state
+                    .store
+                    .create(*type_, *size)
+                    .map_err(|error| error.action_rc())?;
+                state.session(client).current = Some(id);
+                self.publish(Change::client(client, id, ChangeFlag::Create));
+                return Ok(ActionRes::None);
+            }
+            ActionReq::Abort => {
+                let session = state.session(client);
+                session.channel = None;
+                let transfer = session.transfer.take();
+                // transfer may wait for state to write received data
+                drop(state);
+                if let Some(transfer) = transfer {
+                    transfer.abort();
+                }
+                self.lock().locks.release(client);
+                return Ok(ActionRes::None);
+            }
+            ActionReq::Read { .. } | ActionReq::Write { .. }
+                if state.session(client).transfer_running() =>
+            {
+                return Err(ActionRc::ChannelUnavailable);
+            }
+            _ => (),
+        }
+
+        let id = state
+            .session(client)
+            .current
+            .ok_or(ActionRc::InvalidObject)?;
+        let metadata = state
+            .metadata(client, id)
+            .map_err(|error| error.action_rc())?;
+
+        if let Some(property) = property {
+            if !metadata.properties.contains(property) {
+                return Err(ActionRc::ProcedureNotPermitted);
+            }
+        }
+
+        let size = metadata.current_size.unwrap_or_default();
+
+        match req {
+            ActionReq::Delete => {
+                state.locks.check(id, Access::Write)?;
+                state.store.delete(id).map_err(|error| error.action_rc())?;
+                for session in state.sessions.values_mut() {
+                    if session.current == Some(id) {
+                        session.current = None;
+                    }
+                }
+                self.publish(Change::client(client, id, ChangeFlag::Delete));
+                Ok(ActionRes::None)
+            }
+            ActionReq::CheckSum { offset, length } => {
+                state.locks.check(id, Access::Read)?;
+                let value = if id == DIRECTORY_ID {
+                    state
+                        .read(client, id, *offset, *length)
+                        .map(|data| ots_core::crc::crc32(&data))
+                } else {
+                    state.store.checksum(id, *offset, *length)
+                };
+                Ok(ActionRes::CheckSum {
+                    value: value.map_err(|error| error.action_rc())?,
+                })
+            }
+            ActionReq::Execute { param } => {
+                state.locks.check(id, Access::Write)?;
+                let State { store, execute, .. } = &mut *state;
+                let param = execute.execute(store, &metadata, param)?;
+                Ok(ActionRes::Execute { param })
+            }
+            ActionReq::Read { offset, length } => {
+                state.locks.check(id, Access::Read)?;
+                let data = state
+                    .read(client, id, *offset, *length)
+                    .map_err(|error| error.action_rc())?;
+                let session = state.session(client);
+                // channel is closed when transfer finishes
+                let channel = session.channel.take().ok_or(ActionRc::ChannelUnavailable)?;
+                session.transfer = Some(channel.send(data).map_err(|_error| {
+                    warn!("Unable to start transfer: {_error}");
+                    ActionRc::OperationFailed
+                })?);
+                state.locks.acquire(client, id, Access::Read)?;
+                Ok(ActionRes::None)
+            }
+            ActionReq::Write {
+                offset,
+                length,
+                mode,
+            } => {
+                let (offset, length, mode) = (*offset, *length, *mode);
+                check_write(&metadata, size, offset, length, mode)?;
+                state.locks.check(id, Access::Write)?;
+
+                if length == 0 {
+                    state
+                        .store
+                        .write(id, offset, &[], mode)
+                        .and_then(|_| state.store.flush(id))
+                        .map_err(|error| error.action_rc())?;
+                    self.publish(Change::client(client, id, ChangeFlag::Contents));
+                    return Ok(ActionRes::None);
+                }
+
+                let session = state.session(client);
+                // channel is closed when transfer finishes
+                let channel = session.channel.take().ok_or(ActionRc::ChannelUnavailable)?;
+                let server = self.clone();
+                let origin = *client;
+                let finish = self.clone();
+                let transfer = channel.recv(
+                    length,
+                    move |position, data| {
+                        let mut state = server.lock();
+                        match state.store.write(id, offset + position, data, mode) {
+                            Ok(_) => true,
+                            Err(_error) => {
+                                warn!("Unable to write object {id:#x}: {_error}");
+                                false
+                            }
+                        }
+                    },
+                    move |written| {
+                        if let Err(_error) = finish.lock().store.flush(id) {
+                            warn!("Unable to flush object {id:#x}: {_error}");
+                        }
+                        // contents changed also when write was interrupted
+                        if written > 0 {
+                            finish.publish(Change::client(&origin, id, ChangeFlag::Contents));
+                        }
+                    },
+                );
+                session.transfer = Some(transfer.map_err(|_error| {
+                    warn!("Unable to start transfer: {_error}");
+                    ActionRc::OperationFailed
+                })?);
+                state.locks.acquire(client, id, Access::Write)?;
+                Ok(ActionRes::None)
+            }
+            ActionReq::Create { .. } | ActionReq::Abort => unreachable!(),
+        }
+    }
+
+    fn publish(&self, change: Change) {
+        self.shared.changes.publish(change);
+    }
+}
+
+/// Check written control point value
+fn check_value(offset: usize, value: &[u8]) -> AttResult<()> {
+    if offset > 0 {
+        return Err(AttError::InvalidOffset);
+    }
+    if value.is_empty() {
+        return Err(AttError::InvalidValueLength);
+    }
+    Ok(())
+}
+
+/// Check that write action is permitted by object properties
+fn check_write(
+    metadata: &Metadata,
+    size: usize,
+    offset: usize,
+    length: usize,
+    mode: WriteMode,
+) -> Result<(), ActionRc> {
+    let properties = metadata.properties;
+    let end = offset + length;
+
+    if offset > size {
+        return Err(ActionRc::InvalidParameter);
+    }
+    if end > metadata.allocated_size.unwrap_or_default().max(size)
+        && !properties.contains(Property::Append)
+    {
+        return Err(ActionRc::InvalidParameter);
+    }
+    let truncate = mode.contains(WriteMode::Truncate);
+    if truncate && end < size && !properties.contains(Property::Truncate) {
+        return Err(ActionRc::ProcedureNotPermitted);
+    }
+    // rewriting object from the beginning is not patching
+    if offset < size && !(offset == 0 && truncate) && !properties.contains(Property::Patch) {
+        return Err(ActionRc::ProcedureNotPermitted);
+    }
+    Ok(())
+}
+
+fn directory_metadata(size: usize) -> Metadata {
+    Metadata {
+        id: Some(DIRECTORY_ID),
+        name: DIRECTORY_NAME.into(),
+        type_: DIRECTORY_TYPE,
+        current_size: Some(size),
+        allocated_size: Some(size),
+        first_created: None,
+        last_modified: None,
+        properties: Property::Read,
+    }
+}
+
+/// Encode Directory Listing object contents
+///
+/// Listing starts with record of directory itself.
+fn encode_directory(objects: &[Metadata]) -> Vec<u8> {
+    let records = DirEntries::encode(objects);
+    let mut directory = Vec::new();
+    directory_metadata(0).encode_dir_entry(&mut directory);
+    let size = directory.len() + records.len();
+    directory.clear();
+    directory_metadata(size).encode_dir_entry(&mut directory);
+    directory.extend(records);
+    directory
+}
@@ -0,0 +1,192 @@
+//! Object stores
+//!
+//! The [`ObjectStore`] trait abstracts storage of objects served by OTS server.
+//! Two implementations are provided: [`MemoryStore`] which keeps everything
+//! in memory and [`FsStore`] which maps directory files to objects.
+
+mod fs;
+mod memory;
+
+pub use fs::{FsConfig, FsStore};
+pub use memory::MemoryStore;
+
+use crate::{ChangeBus, DateTime, Error, Metadata, Property, Result, WriteMode};
+use ots_core::crc::crc32;
+use uuid::Uuid;
+
+/// Identifier of first object
+///
+/// Lower identifiers are reserved (zero is used by directory listing object).
+pub const FIRST_OBJECT_ID: u64 = 0x100;
+
+/// Maximum object identifier (48-bit)
+pub const MAX_OBJECT_ID: u64 = (1 << 48) - 1;
+
+/// Unspecified object type (org.bluetooth.object.unspecified)
+pub const UNSPECIFIED_TYPE: Uuid = Uuid::from_u128(0x00002aca_0000_1000_8000_00805f9b34fb);
+
+/// Storage of objects
+///
+/// Objects are listed in natural order (usually order of creation).
+/// Metadata returned by store always has identifier and sizes set.
+///
+/// Store only checks ranges, while permissions given by object properties
+/// are checked by server.
+pub trait ObjectStore: Send {
+    /// Get metadata of all objects in natural order
+    fn list(&self) -> Result<Vec<Metadata>>;
+
+    /// Get metadata of object
+    fn metadata(&self, id: u64) -> Result<Metadata>;
+
+    /// Read range of object data
+    fn read(&self, id: u64, offset: usize, length: usize) -> Result<Vec<u8>>;
+
+    /// Write data to object at offset
+    ///
+    /// Object grows when data exceeds its current size. With
+    /// [`WriteMode::Truncate`] object data after written range is discarded.
+    fn write(&mut self, id: u64, offset: usize, data: &[u8], mode: WriteMode) -> Result<()>;
+
+    /// Complete writing of object data
+    ///
+    /// Called once when write transfer finished or aborted, so store
+    /// may defer syncing of data written by [`ObjectStore::write`].
+    fn flush(&mut self, _id: u64) -> Result<()> {
+        Ok(())
+    }
+
+    /// Create new empty object
+    ///
+    /// Returns identifier of created object.
+    fn create(&mut self, type_: Uuid, size: usize) -> Result<u64>;
+
+    /// Delete object
+    fn delete(&mut self, id: u64) -> Result<()>;
+
+    /// Change object name
+    fn set_name(&mut self, id: u64, name: &str) -> Result<()>;
+
+    /// Change object properties
+    fn set_properties(&mut self, id: u64, properties: Property) -> Result<()>;
+
+    /// Change first created time of object
+    ///
+    /// Rejected unless store keeps times of objects.
+    fn set_first_created(&mut self, _id: u64, _time: &DateTime) -> Result<()> {
+        Err(Error::NotPermitted)
+    }
+
+    /// Change last modified time of object
+    ///
+    /// Rejected unless store keeps times of objects.
+    fn set_last_modified(&mut self, _id: u64, _time: &DateTime) -> Result<()> {
+        Err(Error::NotPermitted)
+    }
+
+    /// Calculate checksum of object data range
+    fn checksum(&self, id: u64, offset: usize, length: usize) -> Result<u32> {
+        Ok(crc32(&self.read(id, offset, length)?))
+    }
+
+    /// Attach bus to publish changes caused by local events
+    ///
+    /// Changes made through trait methods are published by server,
+    /// so store should only publish changes it makes by itself.
+    fn set_change_bus(&mut self, _bus: ChangeBus) {}
+}
+
+impl<S: ObjectStore + ?Sized> ObjectStore for Box<S> {
+    fn list(&self) -> Result<Vec<Metadata>> {
+        (**self).list()
+    }
+
+    fn metadata(&self, id: u64) -> Result<Metadata> {
+        (**self).metadata(id)
+    }
+
+    fn read(&self, id: u64, offset: usize, length: usize) -> Result<Vec<u8>> {
+        (**self).read(id, offset, length)
+    }
+
+    fn write(&mut self, id: u64, offset: usize, data: &[u8], mode: WriteMode) -> Result<()> {
+        (**self).write(id, offset, data, mode)
+    }
+
+    fn flush(&mut self, id: u64) -> Result<()> {
+        (**self).flush(id)
+    }
+
+    fn create(&mut self, type_: Uuid, size: usize) -> Result<u64> {
+        (**self).create(type_, size)
+    }
+
+    fn delete(&mut self, id: u64) -> Result<()> {
+        (**self).delete(id)
+    }
+
+    fn set_name(&mut self, id: u64, name: &str) -> Result<()> {
+        (**self).set_name(id, name)
+    }
+
+    fn set_properties(&mut self, id: u64, properties: Property) -> Result<()> {
+        (**self).set_properties(id, properties)
+    }
+
+    fn set_first_created(&mut self, id: u64, time: &DateTime) -> Result<()> {
+        (**self).set_first_created(id, time)
+    }
+
+    fn set_last_modified(&mut self, id: u64, time: &DateTime) -> Result<()> {
+        (**self).set_last_modified(id, time)
+    }
+
+    fn checksum(&self, id: u64, offset: usize, length: usize) -> Result<u32> {
+        (**self).checksum(id, offset, length)
+    }
+
+    fn set_change_bus(&mut self, bus: ChangeBus) {
+        (**self).set_change_bus(bus)
+    }
+}
+
+/// Check that range fits into size
+pub(crate) fn check_range(offset: usize, length: usize, size: usize) -> Result<()> {
+    if offset
+        .checked_add(length)
+        .filter(|end| *end <= size)
+        .is_some()
+    {
+        Ok(())
+    } else {
+        Err(Error::InvalidRange)
+    }
+}
+
+/// Apply written data to object data
+fn apply_write(data: &mut Vec<u8>, offset: usize, chunk: &[u8], mode: WriteMode) -> Result<()> {
+    if offset > data.len() {
+        return Err(Error::InvalidRange);
+    }
+    let end = offset + chunk.len();
+    if data.len() < end {
+        data.resize(end, 0);
+    }
+    data[offset..end].copy_from_slice(chunk);
+    if mode.contains(WriteMode::Truncate) {
+        data.truncate(end);
+    }
+    Ok(())
+}
+
+/// Allocate next object identifier
+fn next_id(next: &mut u64) -> Result<u64> {
+    if *next > MAX_OBJECT_ID {
+        return Err(Error::Core(ots_core::Error::ActionError(
+            crate::ActionRc::InsufficientResources,
+        )));
+    }
+    let id = *next;
+    *next += 1;
+    Ok(id)
+}
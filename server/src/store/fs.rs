@@ -0,0 +1,475 @@
+#[cfg(all(feature = "log", not(feature = "tracing")))]
+use log::{debug, warn};
+
+#[cfg(feature = "tracing")]
+use tracing::{debug, warn};
+
+use super::{check_range, next_id, ObjectStore, FIRST_OBJECT_ID, UNSPECIFIED_TYPE};
+use crate::{
+    Change, ChangeBus, ChangeFlag, DateTime, Error, Metadata, Property, Result, WriteMode,
+};
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{Read, Seek, SeekFrom, Write},
+    os::unix::fs::FileExt,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+use uuid::Uuid;
+
+/// Header line of index file
+const INDEX_HEADER: &str = "# ots index v1";
+
+/// Filesystem object store configuration
+#[derive(Debug, Clone)]
+pub struct FsConfig {
+    /// Name of index file in store directory
+    ///
+    /// Hidden files (which name starts with dot) are never exposed as objects.
+    pub index: String,
+
+    /// Type of files which are not in index yet
+    pub type_: Uuid,
+
+    /// Properties of files which are not in index yet
+    pub properties: Property,
+
+    /// Do not modify directory
+    ///
+    /// Index is kept in memory only and modifications of objects are
+    /// not permitted.
+    pub read_only: bool,
+}
+
+impl Default for FsConfig {
+    fn default() -> Self {
+        Self {
+            index: ".ots-index".into(),
+            type_: UNSPECIFIED_TYPE,
+            properties: Property::all() - Property::Execute - Property::Mark,
+            read_only: false,
+        }
+    }
+}
+
+/// Indexed object entry
+#[derive(Debug, Clone)]
+struct Entry {
+    id: u64,
+    name: String,
+    type_: Uuid,
+    properties: Property,
+    first_created: Option<DateTime>,
+    last_modified: Option<DateTime>,
+    allocated: usize,
+}
+
+impl Entry {
+    fn to_line(&self) -> String {
+        fn time(time: &Option<DateTime>) -> String {
+            time.as_ref()
+                .map(|time| time.to_string())
+                .unwrap_or_else(|| "-".into())
+        }
+
+        format!(
+            "{:012x}\t{}\t{:08x}\t{}\t{}\t{}\t{}",
+            self.id,
+            self.type_,
+            self.properties.bits(),
+            time(&self.first_created),
+            time(&self.last_modified),
+            self.allocated,
+            self.name,
+        )
+    }
+
+    fn from_line(line: &str) -> Option<Self> {
+        fn time(time: &str) -> Option<Option<DateTime>> {
+            if time == "-" {
+                return Some(None);
+            }
+            let (date, time) = time.split_once(' ')?;
+            let mut date = date.splitn(3, '-');
+            let mut time = time.splitn(3, ':');
+            Some(Some(DateTime {
+                year: date.next()?.parse().ok()?,
+                month: date.next()?.parse().ok()?,
+                day: date.next()?.parse().ok()?,
+                hour: time.next()?.parse().ok()?,
+                minute: time.next()?.parse().ok()?,
+                second: time.next()?.parse().ok()?,
+            }))
+        }
+
+        let mut fields = line.splitn(7, '\t');
+
+        Some(Self {
+            id: u64::from_str_radix(fields.next()?, 16).ok()?,
+            type_: fields.next()?.parse().ok()?,
+            properties: Property::from_bits(u32::from_str_radix(fields.next()?, 16).ok()?)?,
+            first_created: time(fields.next()?)?,
+            last_modified: time(fields.next()?)?,
+            allocated: fields.next()?.parse().ok()?,
+            name: fields.next()?.into(),
+        })
+    }
+}
+
+/// Filesystem object store
+///
+/// Each regular file in directory is exposed as object with file name
+/// as object name. Metadata which cannot be kept by filesystem
+/// (identifier, type, properties and times) is stored in sidecar index file.
+///
+/// Written data is synced and index is saved when write is flushed.
+pub struct FsStore {
+    root: PathBuf,
+    config: FsConfig,
+    entries: Vec<Entry>,
+    next_id: u64,
+    changes: ChangeBus,
+}
+
+impl core::fmt::Debug for FsStore {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_struct("FsStore")
+            .field("root", &self.root)
+            .field("objects", &self.entries.len())
+            .finish()
+    }
+}
+
+impl FsStore {
+    /// Open directory as store
+    ///
+    /// Loads index and synchronizes it with directory contents.
+    pub fn open(root: impl Into<PathBuf>, config: FsConfig) -> Result<Self> {
+        let root = root.into();
+        if !root.is_dir() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("Not a directory: {}", root.display()),
+            )
+            .into());
+        }
+
+        let mut store = Self {
+            root,
+            config,
+            entries: Vec::new(),
+            next_id: FIRST_OBJECT_ID,
+            changes: ChangeBus::default(),
+        };
+
+        store.load()?;
+        store.refresh()?;
+
+        Ok(store)
+    }
+
+    /// Get store directory
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Synchronize index with directory contents
+    ///
+    /// Files which were added or removed locally become added or removed
+    /// objects and are published as changes made by server.
+    /// Returns `true` when objects were changed.
+    pub fn refresh(&mut self) -> Result<bool> {
+        let mut names = Vec::new();
+        for entry in fs::read_dir(&self.root)? {
+            let entry = entry?;
+            if !entry.file_type()?.is_file() {
+                continue;
+            }
+            match entry.file_name().into_string() {
+                Ok(name) if is_valid_name(&name) && !self.is_index(&name) => names.push(name),
+                _ => {
+                    debug!("Skip file {:?}", entry.file_name());
+                }
+            }
+        }
+
+        let mut changes = Vec::new();
+        self.entries.retain(|entry| {
+            let keep = names.contains(&entry.name);
+            if !keep {
+                changes.push(Change::local(entry.id, ChangeFlag::Delete));
+            }
+            keep
+        });
+
+        // keep order stable for new files
+        names.sort();
+        for name in names {
+            if self.entries.iter().any(|entry| entry.name == name) {
+                continue;
+            }
+            let meta = fs::metadata(self.root.join(&name))?;
+            let id = next_id(&mut self.next_id)?;
+            debug!("Add file {name:?} as object {id:#x}");
+            self.entries.push(Entry {
+                id,
+                name,
+                type_: self.config.type_,
+                properties: self.config.properties,
+                first_created: meta
+                    .created()
+                    .or_else(|_| meta.modified())
+                    .ok()
+                    .map(Into::into),
+                last_modified: meta.modified().ok().map(Into::into),
+                allocated: meta.len() as _,
+            });
+            changes.push(Change::local(id, ChangeFlag::Create));
+        }
+
+        if changes.is_empty() {
+            return Ok(false);
+        }
+
+        self.save()?;
+        for change in changes {
+            self.changes.publish(change);
+        }
+        Ok(true)
+    }
+
+    fn index_path(&self) -> PathBuf {
+        self.root.join(&self.config.index)
+    }
+
+    fn temp_path(&self) -> PathBuf {
+        self.root.join(format!("{}.tmp", self.config.index))
+    }
+
+    /// Check that file is index or its temporary copy
+    ///
+    /// Index name may be not hidden.
+    fn is_index(&self, name: &str) -> bool {
+        name.strip_prefix(self.config.index.as_str())
+            .is_some_and(|suffix| suffix.is_empty() || suffix == ".tmp")
+    }
+
+    fn check_writable(&self) -> Result<()> {
+        if self.config.read_only {
+            return Err(Error::NotPermitted);
+        }
+        Ok(())
+    }
+
+    fn load(&mut self) -> Result<()> {
+        let mut text = String::new();
+        match File::open(self.index_path()) {
+            Ok(mut file) => {
+                file.read_to_string(&mut text)?;
+            }
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(error) => return Err(error.into()),
+        }
+
+        for line in text.lines().filter(|line| !line.starts_with('#')) {
+            if let Some(entry) = Entry::from_line(line) {
+                self.next_id = self.next_id.max(entry.id + 1);
+                self.entries.push(entry);
+            } else {
+                warn!("Skip invalid index entry: {line:?}");
+            }
+        }
+
+        Ok(())
+    }
+
+    fn save(&self) -> Result<()> {
+        if self.config.read_only {
+            return Ok(());
+        }
+        let path = self.index_path();
+        let temp = self.temp_path();
+        {
+            let mut file = File::create(&temp)?;
+            writeln!(file, "{INDEX_HEADER}")?;
+            for entry in &self.entries {
+                writeln!(file, "{}", entry.to_line())?;
+            }
+            file.sync_all()?;
+        }
+        fs::rename(temp, path)?;
+        Ok(())
+    }
+
+    fn entry(&self, id: u64) -> Result<&Entry> {
+        self.entries
+            .iter()
+            .find(|entry| entry.id == id)
+            .ok_or(Error::NotFound)
+    }
+
+    fn entry_mut(&mut self, id: u64) -> Result<&mut Entry> {
+        self.entries
+            .iter_mut()
+            .find(|entry| entry.id == id)
+            .ok_or(Error::NotFound)
+    }
+
+    fn path(&self, entry: &Entry) -> PathBuf {
+        self.root.join(&entry.name)
+    }
+
+    fn entry_metadata(&self, entry: &Entry) -> Result<Metadata> {
+        let meta = fs::metadata(self.path(entry))?;
+        let current = meta.len() as usize;
+
+        Ok(Metadata {
+            id: Some(entry.id),
+            name: entry.name.clone(),
+            type_: entry.type_,
+            current_size: Some(current),
+            allocated_size: Some(entry.allocated.max(current)),
+            first_created: entry.first_created.clone(),
+            // local modifications are visible immediately
+            last_modified: meta
+                .modified()
+                .ok()
+                .map(Into::into)
+                .or_else(|| entry.last_modified.clone()),
+            properties: entry.properties,
+        })
+    }
+}
+
+/// Check that file name can be used as object name
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('.')
+        && !name.contains(['/', '\0', '\t', '\n', '\r'])
+        // name length is limited by directory listing format
+        && name.len() <= u8::MAX as usize
+}
+
+impl ObjectStore for FsStore {
+    fn list(&self) -> Result<Vec<Metadata>> {
+        self.entries
+            .iter()
+            .map(|entry| self.entry_metadata(entry))
+            .collect()
+    }
+
+    fn metadata(&self, id: u64) -> Result<Metadata> {
+        self.entry_metadata(self.entry(id)?)
+    }
+
+    fn read(&self, id: u64, offset: usize, length: usize) -> Result<Vec<u8>> {
+        let file = File::open(self.path(self.entry(id)?))?;
+        check_range(offset, length, file.metadata()?.len() as _)?;
+        let mut data = vec![0; length];
+        file.read_exact_at(&mut data, offset as _)?;
+        Ok(data)
+    }
+
+    fn write(&mut self, id: u64, offset: usize, data: &[u8], mode: WriteMode) -> Result<()> {
+        self.check_writable()?;
+        let path = self.path(self.entry(id)?);
+        let mut file = OpenOptions::new().write(true).open(path)?;
+        let size = file.metadata()?.len() as usize;
+        if offset > size {
+            return Err(Error::InvalidRange);
+        }
+        file.seek(SeekFrom::Start(offset as _))?;
+        file.write_all(data)?;
+        let end = offset + data.len();
+        if mode.contains(WriteMode::Truncate) {
+            file.set_len(end as _)?;
+        }
+
+        let entry = self.entry_mut(id)?;
+        entry.allocated = entry.allocated.max(end);
+        entry.last_modified = Some(SystemTime::now().into());
+        Ok(())
+    }
+
+    fn flush(&mut self, id: u64) -> Result<()> {
+        self.check_writable()?;
+        File::open(self.path(self.entry(id)?))?.sync_data()?;
+        self.save()
+    }
+
+    fn create(&mut self, type_: Uuid, size: usize) -> Result<u64> {
+        self.check_writable()?;
+        let id = next_id(&mut self.next_id)?;
+        let name = format!("object-{id:012x}");
+        File::options()
+            .write(true)
+            .create_new(true)
+            .open(self.root.join(&name))?;
+        let now = DateTime::from(SystemTime::now());
+        self.entries.push(Entry {
+            id,
+            name,
+            type_,
+            properties: self.config.properties,
+            first_created: Some(now.clone()),
+            last_modified: Some(now),
+            allocated: size,
+        });
+        self.save()?;
+        Ok(id)
+    }
+
+    fn delete(&mut self, id: u64) -> Result<()> {
+        self.check_writable()?;
+        let path = self.path(self.entry(id)?);
+        fs::remove_file(path)?;
+        self.entries.retain(|entry| entry.id != id);
+        self.save()
+    }
+
+    fn set_name(&mut self, id: u64, name: &str) -> Result<()> {
+        self.check_writable()?;
+        if !is_valid_name(name) || self.is_index(name) {
+            return Err(Error::InvalidName(name.into()));
+        }
+        if self
+            .entries
+            .iter()
+            .any(|entry| entry.name == name && entry.id != id)
+            || self.root.join(name).exists() && self.entry(id)?.name != name
+        {
+            return Err(Error::NameExists(name.into()));
+        }
+        let from = self.path(self.entry(id)?);
+        fs::rename(from, self.root.join(name))?;
+        self.entry_mut(id)?.name = name.into();
+        self.save()
+    }
+
+    fn set_properties(&mut self, id: u64, properties: Property) -> Result<()> {
+        self.check_writable()?;
+        self.entry_mut(id)?.properties = properties;
+        self.save()
+    }
+
+    fn set_first_created(&mut self, id: u64, time: &DateTime) -> Result<()> {
+        self.check_writable()?;
+        self.entry_mut(id)?.first_created = Some(time.clone());
+        self.save()
+    }
+
+    fn set_last_modified(&mut self, id: u64, time: &DateTime) -> Result<()> {
+        self.check_writable()?;
+        // modification time of file takes precedence
+        let file = File::options()
+            .write(true)
+            .open(self.path(self.entry(id)?))?;
+        file.set_modified(time.into())?;
+        self.entry_mut(id)?.last_modified = Some(time.clone());
+        self.save()
+    }
+
+    fn set_change_bus(&mut self, bus: ChangeBus) {
+        self.changes = bus;
+    }
+}
@@ -0,0 +1,194 @@
+use super::{apply_write, check_range, next_id, ObjectStore, FIRST_OBJECT_ID};
+use crate::{
+    Change, ChangeBus, ChangeFlag, DateTime, Error, Metadata, Property, Result, WriteMode,
+};
+use std::time::SystemTime;
+use uuid::Uuid;
+
+struct Object {
+    metadata: Metadata,
+    data: Vec<u8>,
+}
+
+impl Object {
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            current_size: Some(self.data.len()),
+            allocated_size: Some(
+                self.metadata
+                    .allocated_size
+                    .unwrap_or_default()
+                    .max(self.data.len()),
+            ),
+            ..self.metadata.clone()
+        }
+    }
+
+    fn touch(&mut self) {
+        self.metadata.last_modified = Some(DateTime::from(SystemTime::now()));
+    }
+}
+
+/// In-memory object store
+pub struct MemoryStore {
+    objects: Vec<Object>,
+    next_id: u64,
+    changes: ChangeBus,
+}
+
+impl core::fmt::Debug for MemoryStore {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_struct("MemoryStore")
+            .field("objects", &self.objects.len())
+            .finish()
+    }
+}
+
+impl Default for MemoryStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MemoryStore {
+    /// Create empty store
+    pub fn new() -> Self {
+        Self {
+            objects: Vec::new(),
+            next_id: FIRST_OBJECT_ID,
+            changes: ChangeBus::default(),
+        }
+    }
+
+    /// Add object with data
+    ///
+    /// Returns identifier of added object. Creation is published
+    /// as change made by server.
+    pub fn insert(
+        &mut self,
+        name: impl Into<String>,
+        type_: Uuid,
+        properties: Property,
+        data: impl Into<Vec<u8>>,
+    ) -> Result<u64> {
+        let id = self.add(name.into(), type_, properties, data.into())?;
+        self.changes.publish(Change::local(id, ChangeFlag::Create));
+        Ok(id)
+    }
+
+    fn add(
+        &mut self,
+        name: String,
+        type_: Uuid,
+        properties: Property,
+        data: Vec<u8>,
+    ) -> Result<u64> {
+        let id = next_id(&mut self.next_id)?;
+        let now = DateTime::from(SystemTime::now());
+        self.objects.push(Object {
+            metadata: Metadata {
+                id: Some(id),
+                name,
+                type_,
+                current_size: Some(data.len()),
+                allocated_size: Some(data.len()),
+                first_created: Some(now.clone()),
+                last_modified: Some(now),
+                properties,
+            },
+            data,
+        });
+        Ok(id)
+    }
+
+    fn object(&self, id: u64) -> Result<&Object> {
+        self.objects
+            .iter()
+            .find(|object| object.metadata.id == Some(id))
+            .ok_or(Error::NotFound)
+    }
+
+    fn object_mut(&mut self, id: u64) -> Result<&mut Object> {
+        self.objects
+            .iter_mut()
+            .find(|object| object.metadata.id == Some(id))
+            .ok_or(Error::NotFound)
+    }
+}
+
+impl ObjectStore for MemoryStore {
+    fn list(&self) -> Result<Vec<Metadata>> {
+        Ok(self.objects.iter().map(Object::metadata).collect())
+    }
+
+    fn metadata(&self, id: u64) -> Result<Metadata> {
+        self.object(id).map(Object::metadata)
+    }
+
+    fn read(&self, id: u64, offset: usize, length: usize) -> Result<Vec<u8>> {
+        let object = self.object(id)?;
+        check_range(offset, length, object.data.len())?;
+        Ok(object.data[offset..offset + length].to_vec())
+    }
+
+    fn write(&mut self, id: u64, offset: usize, data: &[u8], mode: WriteMode) -> Result<()> {
+        let object = self.object_mut(id)?;
+        apply_write(&mut object.data, offset, data, mode)?;
+        object.touch();
+        Ok(())
+    }
+
+    fn create(&mut self, type_: Uuid, size: usize) -> Result<u64> {
+        let id = self.add(
+            String::new(),
+            type_,
+            Property::all() - Property::Execute - Property::Mark,
+            Vec::new(),
+        )?;
+        self.object_mut(id)?.metadata.allocated_size = Some(size);
+        Ok(id)
+    }
+
+    fn delete(&mut self, id: u64) -> Result<()> {
+        let index = self
+            .objects
+            .iter()
+            .position(|object| object.metadata.id == Some(id))
+            .ok_or(Error::NotFound)?;
+        self.objects.remove(index);
+        Ok(())
+    }
+
+    fn set_name(&mut self, id: u64, name: &str) -> Result<()> {
+        if self
+            .objects
+            .iter()
+            .any(|object| object.metadata.name == name && object.metadata.id != Some(id))
+        {
+            return Err(Error::NameExists(name.into()));
+        }
+        let object = self.object_mut(id)?;
+        object.metadata.name = name.into();
+        object.touch();
+        Ok(())
+    }
+
+    fn set_properties(&mut self, id: u64, properties: Property) -> Result<()> {
+        self.object_mut(id)?.metadata.properties = properties;
+        Ok(())
+    }
+
+    fn set_first_created(&mut self, id: u64, time: &DateTime) -> Result<()> {
+        self.object_mut(id)?.metadata.first_created = Some(time.clone());
+        Ok(())
+    }
+
+    fn set_last_modified(&mut self, id: u64, time: &DateTime) -> Result<()> {
+        self.object_mut(id)?.metadata.last_modified = Some(time.clone());
+        Ok(())
+    }
+
+    fn set_change_bus(&mut self, bus: ChangeBus) {
+        self.changes = bus;
+    }
+}
@@ -0,0 +1,312 @@
+//! GATT application tests against stand-in BlueZ service on private bus
+//!
+//! Tests are skipped when `dbus-daemon` is not available.
+
+#![cfg(feature = "bluez")]
+
+use dbus::{
+    arg::{PropMap, RefArg, Variant},
+    channel::{Channel, MatchingReceiver},
+    message::{MatchRule, SignalArgs},
+    nonblock::{
+        stdintf::org_freedesktop_dbus::{ObjectManager, PropertiesPropertiesChanged},
+        Proxy, SyncConnection,
+    },
+    Message, Path,
+};
+use dbus_crossroads::Crossroads;
+use futures_util::{Stream, StreamExt};
+use ots_core::{
+    ids::characteristic as chr,
+    types::{ActionRc, ActionReq, ListReq},
+};
+use ots_server::{
+    bluez::{Application, BluezConfig},
+    store::UNSPECIFIED_TYPE,
+    Config, MemoryStore, Property, Server,
+};
+use std::{
+    io::{BufRead, BufReader},
+    process::{Child, Command, Stdio},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+const DEVICE: &str = "/org/bluez/hci0/dev_00_11_22_33_44_55";
+const OTHER_DEVICE: &str = "/org/bluez/hci0/dev_66_77_88_99_AA_BB";
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Private message bus
+struct Bus {
+    daemon: Child,
+    address: String,
+}
+
+impl Bus {
+    fn start() -> Option<Self> {
+        let mut daemon = Command::new("dbus-daemon")
+            .args(["--session", "--nofork", "--print-address"])
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(|error| eprintln!("Skip test: unable to start dbus-daemon: {error}"))
+            .ok()?;
+        let mut address = String::new();
+        BufReader::new(daemon.stdout.as_mut()?)
+            .read_line(&mut address)
+            .ok()?;
+        Some(Self {
+            daemon,
+            address: address.trim().into(),
+        })
+    }
+
+    fn connect(&self) -> Arc<SyncConnection> {
+        let mut channel = Channel::open_private(&self.address).unwrap();
+        channel.register().unwrap();
+        let (resource, conn) = dbus_tokio::connection::from_channel(channel).unwrap();
+        tokio::spawn(resource);
+        conn
+    }
+}
+
+impl Drop for Bus {
+    fn drop(&mut self) {
+        let _ = self.daemon.kill();
+        let _ = self.daemon.wait();
+    }
+}
+
+/// Registered applications of stand-in BlueZ
+type Registry = Arc<Mutex<Vec<(String, Path<'static>)>>>;
+
+/// Serve `org.bluez.GattManager1` of `hci0` adapter
+async fn bluez(conn: &SyncConnection) -> Registry {
+    conn.request_name("org.bluez", false, true, true)
+        .await
+        .unwrap();
+
+    let registry = Registry::default();
+    let mut cr = Crossroads::new();
+    let iface = cr.register("org.bluez.GattManager1", |b| {
+        b.method(
+            "RegisterApplication",
+            ("application", "options"),
+            (),
+            |ctx, registry: &mut Registry, (path, _): (Path<'static>, PropMap)| {
+                let sender = ctx.message().sender().unwrap().to_string();
+                registry.lock().unwrap().push((sender, path));
+                Ok(())
+            },
+        );
+        b.method(
+            "UnregisterApplication",
+            ("application",),
+            (),
+            |_, registry: &mut Registry, (path,): (Path<'static>,)| {
+                registry.lock().unwrap().retain(|(_, app)| *app != path);
+                Ok(())
+            },
+        );
+    });
+    let advertising_iface = cr.register("org.bluez.LEAdvertisingManager1", |b| {
+        b.method(
+            "RegisterAdvertisement",
+            ("advertisement", "options"),
+            (),
+            |ctx, registry: &mut Registry, (path, _): (Path<'static>, PropMap)| {
+                let sender = ctx.message().sender().unwrap().to_string();
+                registry.lock().unwrap().push((sender, path));
+                Ok(())
+            },
+        );
+        b.method(
+            "UnregisterAdvertisement",
+            ("advertisement",),
+            (),
+            |_, registry: &mut Registry, (path,): (Path<'static>,)| {
+                registry.lock().unwrap().retain(|(_, adv)| *adv != path);
+                Ok(())
+            },
+        );
+    });
+    cr.insert(
+        "/org/bluez/hci0",
+        &[iface, advertising_iface],
+        registry.clone(),
+    );
+    conn.start_receive(
+        MatchRule::new_method_call(),
+        Box::new(move |msg, conn| {
+            let _ = cr.handle_message(msg, conn);
+            true
+        }),
+    );
+
+    registry
+}
+
+fn options(device: &str) -> PropMap {
+    let mut options = PropMap::new();
+    options.insert(
+        "device".into(),
+        Variant(Box::new(Path::from(device.to_string())) as Box<dyn RefArg>),
+    );
+    options
+}
+
+/// Receive next indicated value
+async fn indicated(
+    values: &mut (impl Stream<Item = (Message, PropertiesPropertiesChanged)> + Unpin),
+) -> (Path<'static>, Vec<u8>) {
+    let (msg, changed) = tokio::time::timeout(TIMEOUT, values.next())
+        .await
+        .unwrap()
+        .unwrap();
+    let value = changed.changed_properties["Value"]
+        .0
+        .as_iter()
+        .unwrap()
+        .map(|byte| byte.as_u64().unwrap() as u8)
+        .collect();
+    (msg.path().unwrap().into_static(), value)
+}
+
+#[tokio::test]
+async fn gatt_application() {
+    let Some(bus) = Bus::start() else {
+        return;
+    };
+
+    let bluez_conn = bus.connect();
+    let registry = bluez(&bluez_conn).await;
+
+    let mut store = MemoryStore::new();
+    store
+        .insert("log.txt", UNSPECIFIED_TYPE, Property::Read, "data")
+        .unwrap();
+    let server = Server::new(
+        store,
+        Config {
+            directory_listing: false,
+            ..Config::default()
+        },
+    );
+    let app = Application::register_with(
+        bus.connect(),
+        server,
+        BluezConfig {
+            psm: None,
+            advertise: Some("ots-test".into()),
+            client_changes: true,
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+
+    let (sender, path) = registry.lock().unwrap()[0].clone();
+    let (_, advertisement) = registry.lock().unwrap()[1].clone();
+    let objects = Proxy::new(&sender, &path, TIMEOUT, &*bluez_conn)
+        .get_managed_objects()
+        .await
+        .unwrap();
+
+    let mut chrs = Vec::new();
+    let mut services = 0;
+    for (path, ifaces) in objects {
+        if let Some(props) = ifaces.get("org.bluez.LEAdvertisement1") {
+            assert_eq!(path, advertisement);
+            assert_eq!(props["LocalName"].0.as_str(), Some("ots-test"));
+        }
+        if let Some(props) = ifaces.get("org.bluez.GattService1") {
+            assert_eq!(
+                props["UUID"].0.as_str(),
+                Some("00001825-0000-1000-8000-00805f9b34fb")
+            );
+            services += 1;
+        }
+        if let Some(props) = ifaces.get("org.bluez.GattCharacteristic1") {
+            let uuid: uuid::Uuid = props["UUID"].0.as_str().unwrap().parse().unwrap();
+            chrs.push((uuid, path));
+        }
+    }
+    assert_eq!(services, 1);
+    assert_eq!(chrs.len(), 14);
+
+    let chr = |uuid| {
+        let path = chrs.iter().find(|(id, _)| *id == uuid).unwrap().1.clone();
+        Proxy::new(sender.clone(), path, TIMEOUT, bluez_conn.clone())
+    };
+    let read_from = |uuid, device| async move {
+        chr(uuid)
+            .method_call::<(Vec<u8>,), _, _, _>(
+                "org.bluez.GattCharacteristic1",
+                "ReadValue",
+                (options(device),),
+            )
+            .await
+            .map(|(value,)| value)
+    };
+    let read = |uuid| read_from(uuid, DEVICE);
+    let write = |uuid, value: Vec<u8>| async move {
+        chr(uuid)
+            .method_call::<(), _, _, _>(
+                "org.bluez.GattCharacteristic1",
+                "WriteValue",
+                (value, options(DEVICE)),
+            )
+            .await
+    };
+
+    let (indications, mut values) = bluez_conn
+        .add_match(PropertiesPropertiesChanged::match_rule(None, None).static_clone())
+        .await
+        .unwrap()
+        .stream::<PropertiesPropertiesChanged>();
+    assert_eq!(read(chr::ots_feature).await.unwrap().len(), 8);
+
+    let error = read(chr::object_name).await.unwrap_err();
+    assert_eq!(error.name(), Some("org.bluez.Error.Failed"));
+    assert_eq!(error.message(), Some("0x81"));
+
+    write(chr::object_list_control_point, Vec::from(&ListReq::First))
+        .await
+        .unwrap();
+    let (path, value) = indicated(&mut values).await;
+    assert_eq!(*path, *chr(chr::object_list_control_point).path);
+    assert_eq!(value, [0x70, 0x01, 0x01]);
+
+    assert_eq!(read(chr::object_name).await.unwrap(), b"log.txt");
+
+    // change is not indicated to device which caused it
+    chr(chr::object_changed)
+        .method_call::<(), _, _, _>("org.bluez.GattCharacteristic1", "StartNotify", ())
+        .await
+        .unwrap();
+    write(chr::object_name, b"app.log".to_vec()).await.unwrap();
+
+    write(
+        chr::object_action_control_point,
+        Vec::from(&ActionReq::Delete),
+    )
+    .await
+    .unwrap();
+    let (path, value) = indicated(&mut values).await;
+    assert_eq!(*path, *chr(chr::object_action_control_point).path);
+    assert_eq!(value, [0x60, 0x02, ActionRc::ProcedureNotPermitted as u8]);
+
+    // but it is indicated when other device is connected, to the device
+    // which caused it as well
+    read_from(chr::ots_feature, OTHER_DEVICE).await.unwrap();
+    write(chr::object_name, b"log.txt".to_vec()).await.unwrap();
+    let (path, value) = indicated(&mut values).await;
+    assert_eq!(*path, *chr(chr::object_changed).path);
+    assert_eq!(value, [0x05, 0x00, 0x01, 0, 0, 0, 0]);
+
+    let error = write(chr::object_type, vec![0]).await.unwrap_err();
+    assert_eq!(error.name(), Some("org.bluez.Error.NotSupported"));
+
+    bluez_conn.remove_match(indications.token()).await.unwrap();
+    app.unregister().await.unwrap();
+    assert!(registry.lock().unwrap().is_empty());
+}
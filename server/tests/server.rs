@@ -0,0 +1,800 @@
+use ots_core::{
+    ids::characteristic as chr,
+    types::{ActionReq, ActionRes, DirEntries, ListReq, ListRes, Sizes},
+};
+use ots_server::{
+    store::{FIRST_OBJECT_ID, UNSPECIFIED_TYPE},
+    ActionFeature, ActionRc, AttError, Change, ChangeFlag, Channel, ClientId, Config, CoreError,
+    ExecuteCommand, Filter, FsConfig, FsStore, ListRc, MemoryStore, ObjectStore, Property, Server,
+    SortOrder, WriteMode, DIRECTORY_ID, DIRECTORY_TYPE,
+};
+use socket2::Socket;
+use std::{io::Read, os::fd::FromRawFd, time::Duration};
+use uuid::Uuid;
+
+const MTU: usize = 16;
+
+/// Config of server without Directory Listing object
+fn config() -> Config {
+    Config {
+        directory_listing: false,
+        ..Config::default()
+    }
+}
+
+fn server() -> Server<MemoryStore> {
+    server_with(config())
+}
+
+fn server_with(config: Config) -> Server<MemoryStore> {
+    let mut store = MemoryStore::new();
+    let properties = Property::all() - Property::Execute - Property::Mark;
+    store
+        .insert("beta", UNSPECIFIED_TYPE, properties, "second object")
+        .unwrap();
+    store
+        .insert("alpha", UNSPECIFIED_TYPE, properties, "first")
+        .unwrap();
+    store
+        .insert(
+            "gamma",
+            UNSPECIFIED_TYPE,
+            Property::Read,
+            (0..=255).collect::<Vec<u8>>(),
+        )
+        .unwrap();
+    Server::new(store, config)
+}
+
+fn client(id: u8) -> ClientId {
+    ClientId::new(0, 0, 0, 0, 0, id)
+}
+
+fn list(
+    server: &Server<MemoryStore>,
+    client: &ClientId,
+    req: ListReq,
+) -> ots_core::Result<ListRes> {
+    let res = server
+        .write(client, &chr::object_list_control_point, 0, &Vec::from(&req))
+        .unwrap()
+        .unwrap();
+    ListRes::try_from(res.as_slice())
+}
+
+fn action(
+    server: &Server<MemoryStore>,
+    client: &ClientId,
+    req: ActionReq,
+) -> ots_core::Result<ActionRes> {
+    let res = server
+        .write(
+            client,
+            &chr::object_action_control_point,
+            0,
+            &Vec::from(&req),
+        )
+        .unwrap()
+        .unwrap();
+    ActionRes::try_from(res.as_slice())
+}
+
+fn name(server: &Server<MemoryStore>, client: &ClientId) -> String {
+    String::from_utf8(server.read(client, &chr::object_name, 0).unwrap()).unwrap()
+}
+
+/// Attach socket pair channel and return client side
+fn channel(server: &Server<MemoryStore>, client: &ClientId) -> Socket {
+    let (local, remote) = socket_pair();
+    server.attach_channel(client, Channel::with_mtu(local.into(), MTU, MTU));
+    remote
+}
+
+fn socket_pair() -> (Socket, Socket) {
+    let mut fds = [0; 2];
+    assert_eq!(
+        unsafe {
+            libc::socketpair(
+                libc::AF_UNIX,
+                libc::SOCK_SEQPACKET | libc::SOCK_CLOEXEC,
+                0,
+                fds.as_mut_ptr(),
+            )
+        },
+        0
+    );
+    unsafe { (Socket::from_raw_fd(fds[0]), Socket::from_raw_fd(fds[1])) }
+}
+
+fn wait_data(server: &Server<MemoryStore>, id: u64, data: &[u8]) {
+    for _ in 0..100 {
+        let len = server.with_store(|store| store.metadata(id).unwrap().current_size.unwrap());
+        if len == data.len() && server.with_store(|store| store.read(id, 0, len).unwrap()) == data {
+            return;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    panic!("Data of object {id:#x} was not written");
+}
+
+#[test]
+fn list_navigation() {
+    let server = server();
+    let (a, b) = (client(1), client(2));
+
+    assert_eq!(
+        list(&server, &a, ListReq::NumberOf).unwrap(),
+        ListRes::NumberOf { count: 3 }
+    );
+    assert_eq!(
+        server.read(&a, &chr::object_name, 0),
+        Err(AttError::ObjectNotSelected)
+    );
+
+    list(&server, &a, ListReq::First).unwrap();
+    assert_eq!(name(&server, &a), "beta");
+    assert!(matches!(
+        list(&server, &a, ListReq::Previous),
+        Err(CoreError::ListError(ListRc::OutOfBounds))
+    ));
+    list(&server, &a, ListReq::Next).unwrap();
+    assert_eq!(name(&server, &a), "alpha");
+
+    // each client has own current object and order
+    list(
+        &server,
+        &b,
+        ListReq::Order {
+            order: SortOrder::NameAsc,
+        },
+    )
+    .unwrap();
+    list(&server, &b, ListReq::Last).unwrap();
+    assert_eq!(name(&server, &b), "gamma");
+    assert_eq!(name(&server, &a), "alpha");
+
+    assert!(matches!(
+        list(&server, &a, ListReq::GoTo { id: 1 }),
+        Err(CoreError::ListError(ListRc::ObjectIdNotFound))
+    ));
+    list(
+        &server,
+        &a,
+        ListReq::GoTo {
+            id: FIRST_OBJECT_ID + 2,
+        },
+    )
+    .unwrap();
+    assert_eq!(server.current(&a), Some(FIRST_OBJECT_ID + 2));
+
+    let empty = Server::new(MemoryStore::new(), config());
+    assert!(matches!(
+        list(&empty, &a, ListReq::First),
+        Err(CoreError::ListError(ListRc::NoObject))
+    ));
+}
+
+#[test]
+fn list_filters() {
+    let server = server();
+    let (a, b) = (client(1), client(2));
+    let filter = |instance, filter: &Filter| {
+        server
+            .write_instance(
+                &a,
+                &chr::object_list_filter,
+                instance,
+                0,
+                &Vec::from(filter),
+            )
+            .unwrap();
+    };
+
+    assert_eq!(
+        server.read_instance(&a, &chr::object_list_filter, 2, 0),
+        Ok(vec![0])
+    );
+    filter(0, &Filter::NameContains("a".into()));
+    filter(2, &Filter::CurrentSize { min: 0, max: 100 });
+    assert_eq!(
+        list(&server, &a, ListReq::NumberOf).unwrap(),
+        ListRes::NumberOf { count: 2 }
+    );
+    // filters are per client
+    assert_eq!(
+        list(&server, &b, ListReq::NumberOf).unwrap(),
+        ListRes::NumberOf { count: 3 }
+    );
+
+    list(&server, &a, ListReq::Last).unwrap();
+    assert_eq!(name(&server, &a), "alpha");
+    assert!(matches!(
+        list(
+            &server,
+            &a,
+            ListReq::GoTo {
+                id: FIRST_OBJECT_ID + 2
+            }
+        ),
+        Err(CoreError::ListError(ListRc::ObjectIdNotFound))
+    ));
+
+    filter(1, &Filter::NameIs("delta".into()));
+    assert!(matches!(
+        list(&server, &a, ListReq::First),
+        Err(CoreError::ListError(ListRc::NoObject))
+    ));
+    assert_eq!(
+        server.read_instance(&a, &chr::object_list_filter, 1, 0),
+        Ok(Vec::from(&Filter::NameIs("delta".into())))
+    );
+
+    assert_eq!(
+        server.write(&a, &chr::object_list_filter, 0, &[0x0b]),
+        Err(AttError::WriteRequestRejected)
+    );
+    assert_eq!(
+        server.write_instance(&a, &chr::object_list_filter, 3, 0, &[0]),
+        Err(AttError::RequestNotSupported)
+    );
+}
+
+#[test]
+fn metadata() {
+    let server = server();
+    let a = client(1);
+
+    list(&server, &a, ListReq::Last).unwrap();
+    assert_eq!(
+        server.read(&a, &chr::object_id, 0).unwrap(),
+        [0x02, 0x01, 0, 0, 0, 0]
+    );
+    assert_eq!(
+        server.read(&a, &chr::object_size, 0).unwrap(),
+        [0, 1, 0, 0, 0, 1, 0, 0]
+    );
+    assert_eq!(
+        server.read(&a, &chr::object_name, 2).unwrap(),
+        b"mma".as_slice()
+    );
+    assert_eq!(
+        server.read(&a, &chr::object_name, 6),
+        Err(AttError::InvalidOffset)
+    );
+    assert_eq!(
+        server.read(&a, &chr::object_action_control_point, 0),
+        Err(AttError::RequestNotSupported)
+    );
+
+    server.write(&a, &chr::object_name, 0, b"del").unwrap();
+    server.write(&a, &chr::object_name, 3, b"ta").unwrap();
+    assert_eq!(name(&server, &a), "delta");
+    assert_eq!(
+        server.write(&a, &chr::object_name, 0, b"alpha"),
+        Err(AttError::ObjectNameAlreadyExists)
+    );
+
+    let time = [0xe8, 0x07, 2, 29, 23, 59, 59];
+    server
+        .write(&a, &chr::object_first_created, 0, &time)
+        .unwrap();
+    server
+        .write(&a, &chr::object_last_modified, 0, &time)
+        .unwrap();
+    assert_eq!(
+        server.read(&a, &chr::object_first_created, 0).unwrap(),
+        time
+    );
+    assert_eq!(
+        server.read(&a, &chr::object_last_modified, 0).unwrap(),
+        time
+    );
+    assert_eq!(
+        server.write(&a, &chr::object_last_modified, 0, &time[..5]),
+        Err(AttError::InvalidValueLength)
+    );
+}
+
+#[test]
+fn directory_listing() {
+    let server = server_with(Config::default());
+    let a = client(1);
+
+    assert_eq!(
+        list(&server, &a, ListReq::NumberOf).unwrap(),
+        ListRes::NumberOf { count: 4 }
+    );
+    list(&server, &a, ListReq::GoTo { id: DIRECTORY_ID }).unwrap();
+    assert_eq!(name(&server, &a), "Directory");
+
+    let size = |server: &Server<MemoryStore>| {
+        let sizes = server.read(&a, &chr::object_size, 0).unwrap();
+        Sizes::from(&<[u8; 8]>::try_from(sizes.as_slice()).unwrap()).current as usize
+    };
+    let len = size(&server);
+
+    let mut socket = channel(&server, &a);
+    action(
+        &server,
+        &a,
+        ActionReq::Read {
+            offset: 0,
+            length: len,
+        },
+    )
+    .unwrap();
+    let mut contents = Vec::new();
+    while contents.len() < len {
+        let mut data = [0; MTU];
+        let len = socket.read(&mut data).unwrap();
+        contents.extend_from_slice(&data[..len]);
+    }
+
+    let objects = DirEntries::from(contents.as_slice())
+        .collect::<ots_core::Result<Vec<_>>>()
+        .unwrap();
+    let names = objects
+        .iter()
+        .map(|object| object.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["Directory", "beta", "alpha", "gamma"]);
+    assert_eq!(objects[0].id, Some(DIRECTORY_ID));
+    assert_eq!(objects[0].type_, DIRECTORY_TYPE);
+    assert_eq!(objects[0].current_size, Some(len));
+    assert_eq!(objects[3].current_size, Some(256));
+
+    assert_eq!(
+        server.write(&a, &chr::object_name, 0, b"root"),
+        Err(AttError::WriteRequestRejected)
+    );
+    assert!(matches!(
+        action(&server, &a, ActionReq::Delete),
+        Err(CoreError::ActionError(ActionRc::ProcedureNotPermitted))
+    ));
+
+    // listing follows changes of store after size is read again
+    server.with_store(|store| store.delete(FIRST_OBJECT_ID).unwrap());
+    assert!(size(&server) < len);
+}
+
+#[test]
+fn transfers() {
+    let server = server();
+    let a = client(1);
+
+    list(&server, &a, ListReq::First).unwrap();
+
+    let mut socket = channel(&server, &a);
+    action(
+        &server,
+        &a,
+        ActionReq::Read {
+            offset: 7,
+            length: 6,
+        },
+    )
+    .unwrap();
+    let mut data = [0; MTU];
+    let len = socket.read(&mut data).unwrap();
+    assert_eq!(&data[..len], b"object");
+
+    let socket = channel(&server, &a);
+    action(
+        &server,
+        &a,
+        ActionReq::Write {
+            offset: 7,
+            length: 21,
+            mode: WriteMode::Truncate,
+        },
+    )
+    .unwrap();
+    socket.send(b"OBJECT WITH LONG").unwrap();
+    socket.send(b" TAIL").unwrap();
+    wait_data(&server, FIRST_OBJECT_ID, b"second OBJECT WITH LONG TAIL");
+
+    assert_eq!(
+        action(
+            &server,
+            &a,
+            ActionReq::CheckSum {
+                offset: 0,
+                length: 6
+            }
+        )
+        .unwrap(),
+        ActionRes::CheckSum {
+            value: ots_core::crc::crc32(b"second")
+        }
+    );
+}
+
+#[test]
+fn sequential_reads() {
+    let server = server();
+    let a = client(1);
+    let read = ActionReq::Read {
+        offset: 0,
+        length: 5,
+    };
+
+    list(&server, &a, ListReq::First).unwrap();
+    list(&server, &a, ListReq::Next).unwrap();
+    for _ in 0..2 {
+        // channel of previous transfer is not reused and request is not
+        // delayed until new one is attached
+        assert!(matches!(
+            action(&server, &a, read.clone()),
+            Err(CoreError::ActionError(ActionRc::ChannelUnavailable))
+        ));
+        let (local, mut socket) = socket_pair();
+        socket
+            .set_read_timeout(Some(Duration::from_secs(2)))
+            .unwrap();
+        server.attach_channel(&a, Channel::with_mtu(local.into(), MTU, MTU));
+        action(&server, &a, read.clone()).unwrap();
+
+        let mut data = [0; MTU];
+        let len = socket.read(&mut data).unwrap();
+        assert_eq!(&data[..len], b"first");
+        drop(socket);
+        for _ in 0..100 {
+            if !server.is_locked(FIRST_OBJECT_ID + 1) {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+}
+
+#[test]
+fn object_locks() {
+    let server = server();
+    let (a, b) = (client(1), client(2));
+    let id = server.with_store(|store| {
+        store
+            .insert("large", UNSPECIFIED_TYPE, Property::all(), vec![0; 1 << 20])
+            .unwrap()
+    });
+    let read = ActionReq::Read {
+        offset: 0,
+        length: 1 << 20,
+    };
+    let write = ActionReq::Write {
+        offset: 0,
+        length: 8,
+        mode: WriteMode::empty(),
+    };
+
+    // reading is shared, deleting and writing are refused
+    list(&server, &a, ListReq::GoTo { id }).unwrap();
+    list(&server, &b, ListReq::GoTo { id }).unwrap();
+    let _socket_a = channel(&server, &a);
+    action(&server, &a, read.clone()).unwrap();
+    assert!(server.is_locked(id));
+    let _socket_b = channel(&server, &b);
+    action(&server, &b, read.clone()).unwrap();
+    let c = client(3);
+    list(&server, &c, ListReq::GoTo { id }).unwrap();
+    let _socket_c = channel(&server, &c);
+    for req in [ActionReq::Delete, write.clone()] {
+        assert!(matches!(
+            action(&server, &c, req),
+            Err(CoreError::ActionError(ActionRc::ObjectLocked))
+        ));
+    }
+    action(&server, &a, ActionReq::Abort).unwrap();
+    server.disconnect(&b);
+    assert!(!server.is_locked(id));
+
+    // writing is exclusive until client closes channel
+    let socket = channel(&server, &a);
+    action(&server, &a, write).unwrap();
+    socket.send(b"data").unwrap();
+    let _socket_b = channel(&server, &b);
+    list(&server, &b, ListReq::GoTo { id }).unwrap();
+    for req in [read, ActionReq::Delete] {
+        assert!(matches!(
+            action(&server, &b, req),
+            Err(CoreError::ActionError(ActionRc::ObjectLocked))
+        ));
+    }
+    drop(socket);
+    for _ in 0..100 {
+        if !server.is_locked(id) {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    action(&server, &b, ActionReq::Delete).unwrap();
+}
+
+#[test]
+fn object_changes() {
+    let server = server();
+    let (a, b, c) = (client(1), client(2), client(3));
+    let (_, changes) = server.changes().subscribe();
+    server.enable_changes(&a, true);
+    server.enable_changes(&b, true);
+    // c does not enable indications
+    list(&server, &c, ListReq::First).unwrap();
+
+    action(
+        &server,
+        &a,
+        ActionReq::Create {
+            size: 4,
+            type_: UNSPECIFIED_TYPE,
+        },
+    )
+    .unwrap();
+    let id = server.current(&a).unwrap();
+    let change = changes.try_recv().unwrap();
+    assert_eq!(change, Change::client(&a, id, ChangeFlag::Create));
+    assert_eq!(change.value(), [0x09, 0x03, 0x01, 0, 0, 0, 0]);
+    assert_eq!(server.recipients(&change), [b]);
+
+    server.write(&a, &chr::object_name, 0, b"new").unwrap();
+    assert_eq!(
+        changes.try_recv().unwrap(),
+        Change::client(&a, id, ChangeFlag::Metadata)
+    );
+
+    // local changes are indicated to all clients
+    let local = server.with_store(|store| {
+        store
+            .insert("local", UNSPECIFIED_TYPE, Property::Read, "data")
+            .unwrap()
+    });
+    let change = changes.try_recv().unwrap();
+    assert_eq!(change, Change::local(local, ChangeFlag::Create));
+    let mut recipients = server.recipients(&change);
+    recipients.sort();
+    assert_eq!(recipients, [a, b]);
+
+    // interrupted write changes contents too
+    let socket = channel(&server, &a);
+    action(
+        &server,
+        &a,
+        ActionReq::Write {
+            offset: 0,
+            length: 4,
+            mode: WriteMode::empty(),
+        },
+    )
+    .unwrap();
+    socket.send(b"da").unwrap();
+    drop(socket);
+    assert_eq!(
+        changes.recv_timeout(Duration::from_secs(1)).unwrap(),
+        Change::client(&a, id, ChangeFlag::Contents)
+    );
+
+    server.enable_changes(&b, false);
+    action(&server, &a, ActionReq::Delete).unwrap();
+    let change = changes.try_recv().unwrap();
+    assert_eq!(change.flags, ChangeFlag::Client | ChangeFlag::Delete);
+    assert!(server.recipients(&change).is_empty());
+    assert!(changes.try_recv().is_err());
+}
+
+#[test]
+fn all_changes() {
+    let server = server();
+    let (a, b) = (client(1), client(2));
+    let change = Change::client(&a, FIRST_OBJECT_ID, ChangeFlag::Contents);
+
+    // only client which caused change is known
+    list(&server, &a, ListReq::First).unwrap();
+    server.enable_all_changes(true);
+    assert!(server.recipients(&change).is_empty());
+
+    // clients connected later are indicated too
+    server.read(&b, &chr::ots_feature, 0).unwrap();
+    assert_eq!(server.recipients(&change), [b]);
+    server.disconnect(&b);
+    let c = client(3);
+    list(&server, &c, ListReq::First).unwrap();
+    assert_eq!(server.recipients(&change), [c]);
+
+    server.enable_all_changes(false);
+    assert!(server.recipients(&change).is_empty());
+}
+
+/// Keep first bytes of object
+struct Shrink;
+
+impl ExecuteCommand for Shrink {
+    const OBJECT_TYPE: Uuid = Uuid::from_u128(0x1234);
+    type Param = u16;
+    type Response = u32;
+}
+
+#[test]
+fn execute_handlers() {
+    let mut store = MemoryStore::new();
+    let properties = Property::Read | Property::Execute;
+    let log = store
+        .insert("log", Shrink::OBJECT_TYPE, properties, "lines of log")
+        .unwrap();
+    let other = store
+        .insert("other", UNSPECIFIED_TYPE, properties, "data")
+        .unwrap();
+    let server = Server::new(
+        store,
+        Config {
+            action_features: ActionFeature::all(),
+            ..config()
+        },
+    );
+    let a = client(1);
+    let execute = |param: &[u8]| {
+        action(
+            &server,
+            &a,
+            ActionReq::Execute {
+                param: param.into(),
+            },
+        )
+    };
+
+    server.with_execute(|registry| {
+        registry.register_command::<Shrink>(|store, object, length| {
+            let id = object.id.unwrap();
+            store
+                .write(id, length as _, &[], WriteMode::Truncate)
+                .map_err(|error| error.action_rc())?;
+            Ok(object.current_size.unwrap() as u32 - length as u32)
+        })
+    });
+
+    list(&server, &a, ListReq::GoTo { id: log }).unwrap();
+    assert_eq!(
+        execute(&[5, 0]).unwrap(),
+        ActionRes::Execute {
+            param: vec![7, 0, 0, 0]
+        }
+    );
+    assert_eq!(
+        server.with_store(|store| store.read(log, 0, 5).unwrap()),
+        b"lines"
+    );
+    assert!(matches!(
+        execute(&[5]),
+        Err(CoreError::ActionError(ActionRc::InvalidParameter))
+    ));
+
+    list(&server, &a, ListReq::GoTo { id: other }).unwrap();
+    assert!(matches!(
+        execute(&[]),
+        Err(CoreError::ActionError(ActionRc::UnsupportedType))
+    ));
+}
+
+#[test]
+fn action_errors() {
+    let server = server();
+    let a = client(1);
+
+    assert!(matches!(
+        action(&server, &a, ActionReq::Delete),
+        Err(CoreError::ActionError(ActionRc::InvalidObject))
+    ));
+
+    list(&server, &a, ListReq::Last).unwrap();
+    assert!(matches!(
+        action(&server, &a, ActionReq::Delete),
+        Err(CoreError::ActionError(ActionRc::ProcedureNotPermitted))
+    ));
+    assert!(matches!(
+        action(
+            &server,
+            &a,
+            ActionReq::CheckSum {
+                offset: 250,
+                length: 10
+            }
+        ),
+        Err(CoreError::ActionError(ActionRc::InvalidParameter))
+    ));
+    assert!(matches!(
+        action(&server, &a, ActionReq::Execute { param: Vec::new() }),
+        Err(CoreError::ActionError(ActionRc::OperationNotSupported))
+    ));
+
+    list(&server, &a, ListReq::First).unwrap();
+    assert!(matches!(
+        action(
+            &server,
+            &a,
+            ActionReq::Read {
+                offset: 0,
+                length: 1
+            }
+        ),
+        Err(CoreError::ActionError(ActionRc::ChannelUnavailable))
+    ));
+
+    // unknown op code
+    assert_eq!(
+        server
+            .write(&a, &chr::object_action_control_point, 0, &[0x55])
+            .unwrap(),
+        Some(vec![0x60, 0x55, ActionRc::OperationNotSupported as u8])
+    );
+    assert_eq!(
+        server.write(&a, &chr::object_action_control_point, 0, &[]),
+        Err(AttError::InvalidValueLength)
+    );
+}
+
+#[test]
+fn create_and_delete() {
+    let server = server();
+    let (a, b) = (client(1), client(2));
+    let type_ = Uuid::from_u128(0x1234);
+
+    action(&server, &a, ActionReq::Create { size: 10, type_ }).unwrap();
+    let id = server.current(&a).unwrap();
+    assert_eq!(
+        server.read(&a, &chr::object_type, 0).unwrap(),
+        ots_core::types::uuid_to_raw(&type_)
+    );
+
+    list(&server, &b, ListReq::GoTo { id }).unwrap();
+    action(&server, &a, ActionReq::Delete).unwrap();
+    assert_eq!(server.current(&a), None);
+    assert_eq!(server.current(&b), None);
+    assert!(server.with_store(|store| store.metadata(id)).is_err());
+}
+
+#[test]
+fn read_only_store() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("a.txt"), "a").unwrap();
+    let store = FsStore::open(dir.path(), FsConfig::default()).unwrap();
+    let config = Config {
+        read_only: true,
+        ..config()
+    };
+    assert!(config
+        .characteristics()
+        .iter()
+        .filter(|chr| {
+            [
+                chr::object_name,
+                chr::object_properties,
+                chr::object_first_created,
+                chr::object_last_modified,
+            ]
+            .contains(&chr.uuid)
+        })
+        .all(|chr| chr.read && !chr.write));
+
+    let server = Server::new(store, config);
+    let a = client(1);
+    assert_eq!(
+        server.config().action_features,
+        ActionFeature::Read | ActionFeature::CheckSum | ActionFeature::Abort
+    );
+
+    server
+        .write(
+            &a,
+            &chr::object_list_control_point,
+            0,
+            &Vec::from(&ListReq::First),
+        )
+        .unwrap();
+    assert_eq!(
+        server.write(&a, &chr::object_name, 0, b"b.txt"),
+        Err(AttError::WriteRequestRejected)
+    );
+    assert_eq!(
+        server.write(&a, &chr::object_properties, 0, &0u32.to_le_bytes()),
+        Err(AttError::WriteRequestRejected)
+    );
+    assert!(dir.path().join("a.txt").exists());
+    assert!(!dir.path().join("b.txt").exists());
+}
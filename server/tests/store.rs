@@ -0,0 +1,152 @@
+use ots_server::{
+    store::{FIRST_OBJECT_ID, UNSPECIFIED_TYPE},
+    DateTime, Error, FsConfig, FsStore, MemoryStore, ObjectStore, Property, WriteMode,
+};
+
+fn exercise(store: &mut impl ObjectStore) {
+    let id = store.create(UNSPECIFIED_TYPE, 16).unwrap();
+    store.set_name(id, "data.bin").unwrap();
+    store
+        .write(id, 0, b"hello world", WriteMode::empty())
+        .unwrap();
+    store.write(id, 6, b"there", WriteMode::empty()).unwrap();
+    assert_eq!(store.read(id, 0, 11).unwrap(), b"hello there");
+    store.write(id, 5, b"!", WriteMode::Truncate).unwrap();
+
+    let metadata = store.metadata(id).unwrap();
+    assert_eq!(metadata.name, "data.bin");
+    assert_eq!(metadata.current_size, Some(6));
+    assert_eq!(metadata.allocated_size, Some(16));
+    assert_eq!(
+        store.checksum(id, 0, 6).unwrap(),
+        ots_core::crc::crc32(b"hello!")
+    );
+
+    assert!(matches!(store.read(id, 4, 3), Err(Error::InvalidRange)));
+    assert!(matches!(
+        store.write(id, 7, b"x", WriteMode::empty()),
+        Err(Error::InvalidRange)
+    ));
+
+    let other = store.create(UNSPECIFIED_TYPE, 0).unwrap();
+    assert!(matches!(
+        store.set_name(other, "data.bin"),
+        Err(Error::NameExists(_))
+    ));
+    store.delete(other).unwrap();
+    assert!(matches!(store.metadata(other), Err(Error::NotFound)));
+    assert_eq!(store.list().unwrap().len(), 1);
+}
+
+#[test]
+fn memory_store() {
+    let mut store = MemoryStore::new();
+    exercise(&mut store);
+}
+
+#[test]
+fn fs_store() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut store = FsStore::open(dir.path(), FsConfig::default()).unwrap();
+    exercise(&mut store);
+    assert_eq!(
+        std::fs::read(dir.path().join("data.bin")).unwrap(),
+        b"hello!"
+    );
+}
+
+#[test]
+fn fs_store_index() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("a.txt"), "a").unwrap();
+    std::fs::write(dir.path().join(".hidden"), "h").unwrap();
+    std::fs::create_dir(dir.path().join("subdir")).unwrap();
+
+    let mut store = FsStore::open(dir.path(), FsConfig::default()).unwrap();
+    let list = store.list().unwrap();
+    assert_eq!(list.len(), 1);
+    assert_eq!(list[0].id, Some(FIRST_OBJECT_ID));
+    assert_eq!(list[0].name, "a.txt");
+    store
+        .set_properties(FIRST_OBJECT_ID, Property::Read)
+        .unwrap();
+    let time = DateTime::from(&[0xe8, 0x07, 2, 29, 23, 59, 59]);
+    store.set_first_created(FIRST_OBJECT_ID, &time).unwrap();
+    store.set_last_modified(FIRST_OBJECT_ID, &time).unwrap();
+    drop(store);
+
+    // identifiers and properties survive reopening
+    std::fs::write(dir.path().join("b.txt"), "b").unwrap();
+    let mut store = FsStore::open(dir.path(), FsConfig::default()).unwrap();
+    let list = store.list().unwrap();
+    assert_eq!(list.len(), 2);
+    assert_eq!(list[0].properties, Property::Read);
+    assert_eq!(list[0].first_created, Some(time.clone()));
+    assert_eq!(list[0].last_modified, Some(time));
+    assert_eq!(list[1].id, Some(FIRST_OBJECT_ID + 1));
+
+    std::fs::remove_file(dir.path().join("a.txt")).unwrap();
+    assert!(store.refresh().unwrap());
+    assert!(!store.refresh().unwrap());
+    assert!(matches!(
+        store.metadata(FIRST_OBJECT_ID),
+        Err(Error::NotFound)
+    ));
+    assert!(matches!(
+        store.set_name(FIRST_OBJECT_ID + 1, ".ots-index"),
+        Err(Error::InvalidName(_))
+    ));
+}
+
+#[test]
+fn fs_store_visible_index() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("a.txt"), "a").unwrap();
+    let config = FsConfig {
+        index: "index.txt".into(),
+        ..Default::default()
+    };
+
+    let mut store = FsStore::open(dir.path(), config.clone()).unwrap();
+    let id = store.create(UNSPECIFIED_TYPE, 0).unwrap();
+    store.write(id, 0, b"data", WriteMode::empty()).unwrap();
+    store.flush(id).unwrap();
+    assert!(dir.path().join("index.txt").exists());
+    assert!(!store.refresh().unwrap());
+    drop(store);
+
+    let store = FsStore::open(dir.path(), config).unwrap();
+    let names: Vec<_> = store
+        .list()
+        .unwrap()
+        .into_iter()
+        .map(|meta| meta.name)
+        .collect();
+    assert_eq!(names, ["a.txt", "object-000000000101"]);
+}
+
+#[test]
+fn fs_store_read_only() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("a.txt"), "a").unwrap();
+
+    let mut store = FsStore::open(
+        dir.path(),
+        FsConfig {
+            read_only: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(store.read(FIRST_OBJECT_ID, 0, 1).unwrap(), b"a");
+    assert!(matches!(
+        store.write(FIRST_OBJECT_ID, 0, b"b", WriteMode::empty()),
+        Err(Error::NotPermitted)
+    ));
+    assert!(matches!(
+        store.create(UNSPECIFIED_TYPE, 0),
+        Err(Error::NotPermitted)
+    ));
+    assert!(!dir.path().join(".ots-index").exists());
+    assert_eq!(std::fs::read(dir.path().join("a.txt")).unwrap(), b"a");
+}
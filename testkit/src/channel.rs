@@ -0,0 +1,117 @@
+//! Emulated object data channel
+//!
+//! Unix sequential packet socket pair is used instead of L2CAP channel.
+
+use bluez_async_ots::{L2capStream, Result};
+use socket2::Socket;
+use std::{
+    io::Error,
+    net::Shutdown,
+    os::fd::FromRawFd,
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+/// Timeout of receiving data from client
+const RECV_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Create connected pair of server socket and client stream
+pub fn pair(mtu: usize) -> Result<(Server, L2capStream)> {
+    let (server, client) = socket_pair()?;
+
+    Ok((Server { socket: server }, stream(client, mtu, mtu)?))
+}
+
+/// Create connected pair of sequential packet sockets
+pub fn socket_pair() -> Result<(Socket, Socket)> {
+    let mut fds = [0; 2];
+
+    if unsafe {
+        libc::socketpair(
+            libc::AF_UNIX,
+            libc::SOCK_SEQPACKET | libc::SOCK_CLOEXEC,
+            0,
+            fds.as_mut_ptr(),
+        )
+    } < 0
+    {
+        return Err(Error::last_os_error().into());
+    }
+
+    Ok(unsafe { (Socket::from_raw_fd(fds[0]), Socket::from_raw_fd(fds[1])) })
+}
+
+/// Create client stream using socket
+pub fn stream(socket: Socket, send_mtu: usize, recv_mtu: usize) -> Result<L2capStream> {
+    let socket = ots_core::l2cap::L2capSocket::from(socket);
+    Ok(L2capStream::with_mtu(socket.into(), send_mtu, recv_mtu)?)
+}
+
+/// Server side of data channel
+pub struct Server {
+    socket: Socket,
+}
+
+impl Server {
+    /// Send data to client splitting it to MTU sized packets
+    pub fn send(self, data: Vec<u8>, mtu: usize) -> Result<Transfer> {
+        let socket = self.socket.try_clone()?;
+        let thread = thread::spawn(move || {
+            for sdu in data.chunks(mtu) {
+                if self
+                    .socket
+                    .send_with_flags(sdu, libc::MSG_NOSIGNAL)
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        });
+        Ok(Transfer { socket, thread })
+    }
+
+    /// Receive up to length bytes from client
+    ///
+    /// Received data is passed to handler when length is reached or
+    /// client closed channel.
+    pub fn recv(
+        self,
+        length: usize,
+        mtu: usize,
+        handler: impl FnOnce(Vec<u8>) + Send + 'static,
+    ) -> Result<Transfer> {
+        let socket = self.socket.try_clone()?;
+        self.socket.set_read_timeout(Some(RECV_TIMEOUT))?;
+        let thread = thread::spawn(move || {
+            let mut data = Vec::with_capacity(length);
+            let mut sdu = vec![0u8; mtu];
+            while data.len() < length {
+                match std::io::Read::read(&mut &self.socket, &mut sdu) {
+                    Ok(0) | Err(_) => break,
+                    Ok(len) => data.extend_from_slice(&sdu[..len.min(length - data.len())]),
+                }
+            }
+            handler(data);
+        });
+        Ok(Transfer { socket, thread })
+    }
+}
+
+/// Running data transfer
+pub struct Transfer {
+    socket: Socket,
+    thread: JoinHandle<()>,
+}
+
+impl Transfer {
+    /// Wait for transfer completion
+    pub fn join(self) {
+        let _ = self.thread.join();
+    }
+
+    /// Interrupt transfer by closing channel
+    pub fn abort(self) {
+        let _ = self.socket.shutdown(Shutdown::Both);
+        self.join();
+    }
+}
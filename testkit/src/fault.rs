@@ -0,0 +1,344 @@
+//! Fault injection
+//!
+//! The [`FaultyGatt`] wraps any [`Gatt`] transport and injects faults into
+//! control point responses and object data channels as prescribed by
+//! [`FaultScript`].
+
+use crate::channel;
+use bluez_async_ots::{Gatt, L2capStream, Result};
+use futures_channel::mpsc;
+use futures_util::stream::{self, BoxStream, StreamExt};
+use ots_core::ids::characteristic as chr;
+use socket2::Socket;
+use std::{
+    collections::{HashMap, VecDeque},
+    net::Shutdown,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex, MutexGuard,
+    },
+    thread,
+    time::Duration,
+};
+use uuid::Uuid;
+
+/// Fault of control point response
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ResponseFault {
+    /// Deliver response after delay
+    Delay(Duration),
+    /// Do not deliver response at all
+    Drop,
+    /// Flip bits of random response byte
+    Corrupt,
+    /// Deliver response after the next one
+    Reorder,
+    /// Replace result code of response
+    ResultCode(u8),
+}
+
+/// Script of faults to inject
+///
+/// Control point responses and opened data channels consume script steps
+/// in order. When steps are exhausted faults are chosen randomly using
+/// configured probability. All random decisions are made by generator
+/// initialized with seed so the same script reproduces the same faults.
+#[derive(Debug, Clone)]
+pub struct FaultScript {
+    rng: Rng,
+    responses: VecDeque<Option<ResponseFault>>,
+    channels: VecDeque<Option<usize>>,
+    random: Vec<ResponseFault>,
+    percent: u8,
+}
+
+impl FaultScript {
+    /// Create empty script with seed
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: Rng::new(seed),
+            responses: VecDeque::new(),
+            channels: VecDeque::new(),
+            random: Vec::new(),
+            percent: 0,
+        }
+    }
+
+    /// Pass next response as is
+    pub fn pass(mut self) -> Self {
+        self.responses.push_back(None);
+        self
+    }
+
+    /// Inject fault into next response
+    pub fn fault(mut self, fault: ResponseFault) -> Self {
+        self.responses.push_back(Some(fault));
+        self
+    }
+
+    /// Pass next data channel as is
+    pub fn pass_channel(mut self) -> Self {
+        self.channels.push_back(None);
+        self
+    }
+
+    /// Cut next data channel after number of transferred bytes
+    pub fn cut(mut self, offset: usize) -> Self {
+        self.channels.push_back(Some(offset));
+        self
+    }
+
+    /// Inject random faults into responses which are not covered by steps
+    ///
+    /// The percent is a probability of fault per response.
+    pub fn random(mut self, percent: u8, faults: impl IntoIterator<Item = ResponseFault>) -> Self {
+        self.percent = percent.min(100);
+        self.random = faults.into_iter().collect();
+        self
+    }
+
+    fn next_response(&mut self) -> Option<ResponseFault> {
+        if let Some(step) = self.responses.pop_front() {
+            return step;
+        }
+        if self.random.is_empty() || self.rng.below(100) >= self.percent as usize {
+            return None;
+        }
+        Some(self.random[self.rng.below(self.random.len())])
+    }
+
+    fn next_channel(&mut self) -> Option<usize> {
+        self.channels.pop_front().flatten()
+    }
+}
+
+/// Xorshift pseudo-random generator
+#[derive(Debug, Clone)]
+struct Rng {
+    state: u64,
+}
+
+/// Constant to mix seed with
+const GOLDEN: u64 = 0x9e3779b97f4a7c15;
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        let state = seed ^ GOLDEN;
+        // zero state is not allowed, because generator yields only zeros then
+        Self {
+            state: if state == 0 { GOLDEN } else { state },
+        }
+    }
+
+    fn next(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+
+    fn below(&mut self, max: usize) -> usize {
+        (self.next() % max as u64) as usize
+    }
+}
+
+struct State {
+    script: FaultScript,
+    held: HashMap<Uuid, Vec<u8>>,
+    injected: Vec<ResponseFault>,
+}
+
+impl State {
+    fn process(
+        &mut self,
+        uuid: &Uuid,
+        mut value: Vec<u8>,
+        delayed: &mpsc::UnboundedSender<Vec<u8>>,
+    ) -> Vec<Vec<u8>> {
+        let fault = self.script.next_response();
+        let mut out = Vec::with_capacity(2);
+
+        // previously held response goes first
+        if let Some(held) = self.held.remove(uuid) {
+            out.push(held);
+        }
+
+        if let Some(fault) = fault {
+            self.injected.push(fault);
+
+            match fault {
+                ResponseFault::Delay(delay) => {
+                    let delayed = delayed.clone();
+                    thread::spawn(move || {
+                        thread::sleep(delay);
+                        let _ = delayed.unbounded_send(value);
+                    });
+                    return out;
+                }
+                ResponseFault::Drop => return out,
+                ResponseFault::Corrupt => {
+                    if !value.is_empty() {
+                        let index = self.script.rng.below(value.len());
+                        value[index] ^= (self.script.rng.below(255) + 1) as u8;
+                    }
+                }
+                ResponseFault::Reorder => {
+                    self.held.insert(*uuid, value);
+                    return out;
+                }
+                ResponseFault::ResultCode(code) => {
+                    if value.len() > 2 {
+                        value[2] = code;
+                    }
+                }
+            }
+        }
+
+        out.push(value);
+        out
+    }
+}
+
+/// Fault-injecting GATT transport
+///
+/// Faults are injected into control point responses and into object
+/// data channels while all other operations are passed to inner transport.
+#[derive(Clone)]
+pub struct FaultyGatt<G> {
+    inner: Arc<G>,
+    state: Arc<Mutex<State>>,
+}
+
+impl<G: Gatt> core::fmt::Debug for FaultyGatt<G> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_struct("FaultyGatt")
+            .field("injected", &self.lock().injected)
+            .finish()
+    }
+}
+
+impl<G: Gatt> FaultyGatt<G> {
+    /// Wrap transport to inject faults using script
+    pub fn new(inner: G, script: FaultScript) -> Self {
+        Self {
+            inner: Arc::new(inner),
+            state: Arc::new(Mutex::new(State {
+                script,
+                held: HashMap::new(),
+                injected: Vec::new(),
+            })),
+        }
+    }
+
+    /// Get inner transport
+    pub fn inner(&self) -> &G {
+        &self.inner
+    }
+
+    /// Get faults which were injected into responses so far
+    pub fn injected(&self) -> Vec<ResponseFault> {
+        self.lock().injected.clone()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|error| error.into_inner())
+    }
+}
+
+fn is_control_point(uuid: &Uuid) -> bool {
+    *uuid == chr::object_action_control_point || *uuid == chr::object_list_control_point
+}
+
+impl<G: Gatt + 'static> Gatt for FaultyGatt<G> {
+    fn has_characteristic(&self, uuid: &Uuid) -> bool {
+        self.inner.has_characteristic(uuid)
+    }
+
+    async fn read_characteristic(&self, uuid: &Uuid) -> Result<Vec<u8>> {
+        self.inner.read_characteristic(uuid).await
+    }
+
+    async fn write_characteristic(&self, uuid: &Uuid, value: Vec<u8>) -> Result<()> {
+        self.inner.write_characteristic(uuid, value).await
+    }
+
+    async fn subscribe(&self, uuid: &Uuid) -> Result<BoxStream<'static, Vec<u8>>> {
+        let values = self.inner.subscribe(uuid).await?;
+
+        if !is_control_point(uuid) {
+            return Ok(values);
+        }
+
+        let (delayed, delayed_values) = mpsc::unbounded();
+        let state = self.state.clone();
+        let uuid = *uuid;
+
+        let values = values.flat_map(move |value| {
+            let mut state = state.lock().unwrap_or_else(|error| error.into_inner());
+            stream::iter(state.process(&uuid, value, &delayed))
+        });
+
+        Ok(stream::select(values, delayed_values).boxed())
+    }
+
+    async fn unsubscribe(&self, uuid: &Uuid) -> Result<()> {
+        self.inner.unsubscribe(uuid).await
+    }
+
+    async fn open_channel(&self) -> Result<L2capStream> {
+        let stream = self.inner.open_channel().await?;
+
+        let Some(offset) = self.lock().script.next_channel() else {
+            return Ok(stream);
+        };
+
+        let (send_mtu, recv_mtu) = (stream.max_send_sdu(), stream.max_recv_sdu());
+        let inner: Socket = stream.try_clone()?;
+        drop(stream);
+        inner.set_nonblocking(false)?;
+
+        let (outer, client) = channel::socket_pair()?;
+        let transferred = Arc::new(AtomicUsize::new(0));
+        let mtu = send_mtu.max(recv_mtu);
+
+        for (from, to) in [(inner.try_clone()?, outer.try_clone()?), (outer, inner)] {
+            let transferred = transferred.clone();
+            thread::spawn(move || proxy(from, to, mtu, offset, transferred));
+        }
+
+        channel::stream(client, send_mtu, recv_mtu)
+    }
+}
+
+/// Forward packets until channel closed or cut offset reached
+fn proxy(from: Socket, to: Socket, mtu: usize, offset: usize, transferred: Arc<AtomicUsize>) {
+    let mut sdu = vec![0u8; mtu];
+
+    loop {
+        let len = match std::io::Read::read(&mut &from, &mut sdu) {
+            Ok(0) | Err(_) => {
+                let _ = to.shutdown(Shutdown::Write);
+                return;
+            }
+            Ok(len) => len,
+        };
+
+        let before = transferred.fetch_add(len, Ordering::SeqCst);
+        let allowed = offset.saturating_sub(before).min(len);
+
+        if allowed > 0
+            && to
+                .send_with_flags(&sdu[..allowed], libc::MSG_NOSIGNAL)
+                .is_err()
+        {
+            return;
+        }
+
+        if allowed < len {
+            // cut channel in both directions
+            let _ = to.shutdown(Shutdown::Both);
+            let _ = from.shutdown(Shutdown::Both);
+            return;
+        }
+    }
+}
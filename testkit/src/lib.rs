@@ -0,0 +1,628 @@
+#![forbid(future_incompatible)]
+#![deny(bad_style, missing_docs)]
+#![doc = include_str!("../README.md")]
+
+mod channel;
+pub mod fault;
+
+use bluez_async_ots::{
+    ActionFeature, ActionRc, DateTime, Error, Gatt, L2capStream, ListFeature, ListRc, Metadata,
+    OtsClient, Property, Result, SortOrder, WriteMode,
+};
+use futures_channel::mpsc;
+use futures_util::stream::{BoxStream, StreamExt};
+use ots_core::{
+    crc::crc32,
+    ids::characteristic as chr,
+    types::{
+        self, ActionOp, ActionReq, ActionRes, ChangeFlag, ListReq, ListRes, ObjectChanged, Sizes,
+        Ule48,
+    },
+};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, MutexGuard},
+};
+use uuid::Uuid;
+
+/// Identifier of first object
+///
+/// Identifiers below are reserved (zero is used for directory listing object).
+pub const FIRST_OBJECT_ID: u64 = 0x100;
+
+/// Default data channel MTU
+pub const DEFAULT_MTU: usize = 64;
+
+/// Execute action handler
+///
+/// Gets selected object and parameter and returns response parameter.
+pub type ExecuteHandler =
+    dyn Fn(&mut MockObject, &[u8]) -> core::result::Result<Vec<u8>, ActionRc> + Send + Sync;
+
+/// Object of mock peripheral
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MockObject {
+    /// Object identifier
+    ///
+    /// Assigned by peripheral when object is added.
+    pub id: u64,
+    /// Object name
+    pub name: String,
+    /// Object type
+    pub type_: Uuid,
+    /// Object data
+    pub data: Vec<u8>,
+    /// Allocated size
+    pub allocated: usize,
+    /// First created time
+    pub first_created: Option<DateTime>,
+    /// Last modified time
+    pub last_modified: Option<DateTime>,
+    /// Object properties
+    pub properties: Property,
+}
+
+impl MockObject {
+    /// Create object with data
+    ///
+    /// Allocated size equals to data length and all properties except
+    /// [`Property::Execute`] and [`Property::Mark`] are set.
+    pub fn new(name: impl Into<String>, type_: Uuid, data: impl Into<Vec<u8>>) -> Self {
+        let data = data.into();
+        Self {
+            id: 0,
+            name: name.into(),
+            type_,
+            allocated: data.len(),
+            data,
+            first_created: None,
+            last_modified: None,
+            properties: Property::all() - Property::Execute - Property::Mark,
+        }
+    }
+
+    /// Set allocated size
+    pub fn with_allocated(mut self, allocated: usize) -> Self {
+        self.allocated = allocated.max(self.data.len());
+        self
+    }
+
+    /// Set properties
+    pub fn with_properties(mut self, properties: Property) -> Self {
+        self.properties = properties;
+        self
+    }
+
+    /// Set first created and last modified time
+    pub fn with_times(
+        mut self,
+        first_created: Option<DateTime>,
+        last_modified: Option<DateTime>,
+    ) -> Self {
+        self.first_created = first_created;
+        self.last_modified = last_modified;
+        self
+    }
+
+    /// Get object metadata
+    pub fn metadata(&self) -> Metadata {
+        Metadata {
+            id: Some(self.id),
+            name: self.name.clone(),
+            type_: self.type_,
+            current_size: Some(self.data.len()),
+            allocated_size: Some(self.allocated),
+            first_created: self.first_created.clone(),
+            last_modified: self.last_modified.clone(),
+            properties: self.properties,
+        }
+    }
+}
+
+struct State {
+    action_features: ActionFeature,
+    list_features: ListFeature,
+    objects: Vec<MockObject>,
+    current: Option<u64>,
+    next_id: u64,
+    mtu: usize,
+    execute: Option<Arc<ExecuteHandler>>,
+    subscribers: HashMap<Uuid, Vec<mpsc::UnboundedSender<Vec<u8>>>>,
+    channel: Option<channel::Server>,
+    transfer: Option<channel::Transfer>,
+}
+
+impl State {
+    fn index(&self) -> Option<usize> {
+        let id = self.current?;
+        self.objects.iter().position(|object| object.id == id)
+    }
+
+    fn object(&self) -> Result<&MockObject> {
+        self.index()
+            .map(|index| &self.objects[index])
+            .ok_or(Error::NotFound)
+    }
+
+    fn notify(&mut self, uuid: &Uuid, value: Vec<u8>) {
+        if let Some(subscribers) = self.subscribers.get_mut(uuid) {
+            subscribers.retain(|sender| sender.unbounded_send(value.clone()).is_ok());
+        }
+    }
+}
+
+/// In-memory OTS peripheral
+///
+/// Implements [`Gatt`] transport with all OTS characteristics and handles
+/// list and action control point procedures over stored objects.
+/// Clones share the same state so peripheral can be inspected while
+/// client uses it.
+#[derive(Clone)]
+pub struct MockPeripheral {
+    state: Arc<Mutex<State>>,
+}
+
+impl core::fmt::Debug for MockPeripheral {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        let state = self.lock();
+        f.debug_struct("MockPeripheral")
+            .field("action_features", &state.action_features)
+            .field("list_features", &state.list_features)
+            .field("objects", &state.objects.len())
+            .field("current", &state.current)
+            .finish()
+    }
+}
+
+impl Default for MockPeripheral {
+    fn default() -> Self {
+        Self::new(ActionFeature::all(), ListFeature::all())
+    }
+}
+
+impl MockPeripheral {
+    /// Create peripheral without objects
+    pub fn new(action_features: ActionFeature, list_features: ListFeature) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(State {
+                action_features,
+                list_features,
+                objects: Vec::new(),
+                current: None,
+                next_id: FIRST_OBJECT_ID,
+                mtu: DEFAULT_MTU,
+                execute: None,
+                subscribers: HashMap::new(),
+                channel: None,
+                transfer: None,
+            })),
+        }
+    }
+
+    /// Create OTS client which uses this peripheral
+    pub async fn client(&self) -> Result<OtsClient<Self>> {
+        OtsClient::with_gatt(self.clone()).await
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|error| error.into_inner())
+    }
+
+    /// Wait for data transfer completion
+    ///
+    /// Written data is applied to object when transfer completed.
+    /// GATT operations waits it implicitly.
+    pub fn settle(&self) {
+        let transfer = self.lock().transfer.take();
+        if let Some(transfer) = transfer {
+            transfer.join();
+        }
+    }
+
+    /// Add object
+    ///
+    /// Returns the identifier assigned to object.
+    pub fn add_object(&self, mut object: MockObject) -> u64 {
+        let mut state = self.lock();
+        object.id = state.next_id;
+        state.next_id += 1;
+        let id = object.id;
+        state.objects.push(object);
+        id
+    }
+
+    /// Get object by identifier
+    pub fn object(&self, id: u64) -> Option<MockObject> {
+        self.settle();
+        self.lock()
+            .objects
+            .iter()
+            .find(|object| object.id == id)
+            .cloned()
+    }
+
+    /// Get all objects in list order
+    pub fn objects(&self) -> Vec<MockObject> {
+        self.settle();
+        self.lock().objects.clone()
+    }
+
+    /// Get identifier of selected object
+    pub fn current(&self) -> Option<u64> {
+        self.lock().current
+    }
+
+    /// Set data channel MTU
+    pub fn set_mtu(&self, mtu: usize) {
+        self.lock().mtu = mtu.max(1);
+    }
+
+    /// Set execute action handler
+    ///
+    /// Without handler execute action responds with empty parameter.
+    pub fn set_execute(
+        &self,
+        handler: impl Fn(&mut MockObject, &[u8]) -> core::result::Result<Vec<u8>, ActionRc>
+            + Send
+            + Sync
+            + 'static,
+    ) {
+        self.lock().execute = Some(Arc::new(handler));
+    }
+
+    /// Indicate object change to subscribed clients
+    pub fn indicate_change(&self, id: u64, flags: ChangeFlag) {
+        let value = <[u8; 7]>::from(&ObjectChanged { flags, id });
+        self.lock().notify(&chr::object_changed, value.into());
+    }
+
+    fn abort(&self) {
+        let transfer = self.lock().transfer.take();
+        if let Some(transfer) = transfer {
+            transfer.abort();
+        }
+    }
+
+    fn read_value(&self, uuid: &Uuid) -> Result<Vec<u8>> {
+        let state = self.lock();
+
+        if *uuid == chr::ots_feature {
+            let mut raw = Vec::with_capacity(8);
+            raw.extend_from_slice(&<[u8; 4]>::from(state.action_features));
+            raw.extend_from_slice(&<[u8; 4]>::from(state.list_features));
+            return Ok(raw);
+        }
+
+        let object = state.object()?;
+
+        Ok(match *uuid {
+            chr::object_id => <[u8; 6]>::from(Ule48::from(object.id)).into(),
+            chr::object_name => object.name.as_bytes().into(),
+            chr::object_type => types::uuid_to_raw(&object.type_),
+            chr::object_size => <[u8; 8]>::from(&Sizes {
+                current: object.data.len() as _,
+                allocated: object.allocated as _,
+            })
+            .into(),
+            chr::object_first_created => object
+                .first_created
+                .as_ref()
+                .map(<[u8; 7]>::from)
+                .unwrap_or_default()
+                .into(),
+            chr::object_last_modified => object
+                .last_modified
+                .as_ref()
+                .map(<[u8; 7]>::from)
+                .unwrap_or_default()
+                .into(),
+            chr::object_properties => <[u8; 4]>::from(object.properties).into(),
+            _ => return Err(Error::NotSupported),
+        })
+    }
+
+    fn write_value(&self, uuid: &Uuid, value: &[u8]) -> Result<()> {
+        match *uuid {
+            chr::object_action_control_point => {
+                let req = ActionReq::try_from(value)?;
+                let res = self.action(&req);
+                self.lock().notify(
+                    uuid,
+                    ActionRes::encode(req.op(), res.as_ref().map_err(|rc| *rc)),
+                );
+            }
+            chr::object_list_control_point => {
+                let req = ListReq::try_from(value)?;
+                let res = self.list(&req);
+                self.lock().notify(
+                    uuid,
+                    ListRes::encode(req.op(), res.as_ref().map_err(|rc| *rc)),
+                );
+            }
+            chr::object_name => {
+                let name = core::str::from_utf8(value)?;
+                let mut state = self.lock();
+                let index = state.index().ok_or(Error::NotFound)?;
+                state.objects[index].name = name.into();
+            }
+            chr::object_first_created | chr::object_last_modified => {
+                let time = DateTime::try_from(value)?;
+                let mut state = self.lock();
+                let index = state.index().ok_or(Error::NotFound)?;
+                let object = &mut state.objects[index];
+                if *uuid == chr::object_first_created {
+                    object.first_created = Some(time);
+                } else {
+                    object.last_modified = Some(time);
+                }
+            }
+            chr::object_properties => {
+                let properties = Property::try_from(value)?;
+                let mut state = self.lock();
+                let index = state.index().ok_or(Error::NotFound)?;
+                state.objects[index].properties = properties;
+            }
+            _ => return Err(Error::NotSupported),
+        }
+        Ok(())
+    }
+
+    fn list(&self, req: &ListReq) -> core::result::Result<ListRes, ListRc> {
+        let mut state = self.lock();
+        let state = &mut *state;
+
+        let feature = match req {
+            ListReq::GoTo { .. } => Some(ListFeature::GoTo),
+            ListReq::Order { .. } => Some(ListFeature::Order),
+            ListReq::NumberOf => Some(ListFeature::NumberOf),
+            ListReq::ClearMark => Some(ListFeature::ClearMark),
+            _ => None,
+        };
+        if let Some(feature) = feature {
+            if !state.list_features.contains(feature) {
+                return Err(ListRc::OperationNotSupported);
+            }
+        }
+
+        let count = state.objects.len();
+
+        let index = match req {
+            ListReq::First | ListReq::Last if count == 0 => return Err(ListRc::NoObject),
+            ListReq::First => 0,
+            ListReq::Last => count - 1,
+            ListReq::Previous | ListReq::Next => {
+                let index = state.index().ok_or(ListRc::OperationFailed)?;
+                match req {
+                    ListReq::Previous if index == 0 => return Err(ListRc::OutOfBounds),
+                    ListReq::Previous => index - 1,
+                    _ if index + 1 >= count => return Err(ListRc::OutOfBounds),
+                    _ => index + 1,
+                }
+            }
+            ListReq::GoTo { id } => state
+                .objects
+                .iter()
+                .position(|object| object.id == *id)
+                .ok_or(ListRc::ObjectIdNotFound)?,
+            ListReq::Order { order } => {
+                sort_objects(&mut state.objects, *order);
+                return Ok(ListRes::None);
+            }
+            ListReq::NumberOf => return Ok(ListRes::NumberOf { count: count as _ }),
+            ListReq::ClearMark => {
+                for object in &mut state.objects {
+                    object.properties.remove(Property::Mark);
+                }
+                return Ok(ListRes::None);
+            }
+        };
+
+        state.current = Some(state.objects[index].id);
+        Ok(ListRes::None)
+    }
+
+    fn action(&self, req: &ActionReq) -> core::result::Result<ActionRes, ActionRc> {
+        let mut state = self.lock();
+        let state = &mut *state;
+
+        let (feature, property) = match req {
+            ActionReq::Create { .. } => (ActionFeature::Create, None),
+            ActionReq::Delete => (ActionFeature::Delete, Some(Property::Delete)),
+            ActionReq::CheckSum { .. } => (ActionFeature::CheckSum, None),
+            ActionReq::Execute { .. } => (ActionFeature::Execute, Some(Property::Execute)),
+            ActionReq::Read { .. } => (ActionFeature::Read, Some(Property::Read)),
+            ActionReq::Write { .. } => (ActionFeature::Write, Some(Property::Write)),
+            ActionReq::Abort => (ActionFeature::Abort, None),
+        };
+        if !state.action_features.contains(feature) {
+            return Err(ActionRc::OperationNotSupported);
+        }
+
+        if let ActionReq::Create { size, type_ } = req {
+            let mut object = MockObject::new("", *type_, Vec::new()).with_allocated(*size);
+            object.id = state.next_id;
+            state.next_id += 1;
+            state.current = Some(object.id);
+            state.objects.push(object);
+            return Ok(ActionRes::None);
+        }
+
+        if let ActionReq::Abort = req {
+            state.channel = None;
+            return Ok(ActionRes::None);
+        }
+
+        let index = state.index().ok_or(ActionRc::InvalidObject)?;
+        let object = &mut state.objects[index];
+
+        if let Some(property) = property {
+            if !object.properties.contains(property) {
+                return Err(ActionRc::ProcedureNotPermitted);
+            }
+        }
+
+        let check_range = |offset: usize, length: usize, size: usize| {
+            if offset
+                .checked_add(length)
+                .filter(|end| *end <= size)
+                .is_some()
+            {
+                Ok(())
+            } else {
+                Err(ActionRc::InvalidParameter)
+            }
+        };
+
+        match req {
+            ActionReq::Delete => {
+                state.objects.remove(index);
+                state.current = None;
+                Ok(ActionRes::None)
+            }
+            ActionReq::CheckSum { offset, length } => {
+                check_range(*offset, *length, object.data.len())?;
+                Ok(ActionRes::CheckSum {
+                    value: crc32(&object.data[*offset..*offset + *length]),
+                })
+            }
+            ActionReq::Execute { param } => {
+                let param = if let Some(execute) = &state.execute {
+                    execute(object, param)?
+                } else {
+                    Vec::new()
+                };
+                Ok(ActionRes::Execute { param })
+            }
+            ActionReq::Read { offset, length } => {
+                check_range(*offset, *length, object.data.len())?;
+                let channel = state.channel.take().ok_or(ActionRc::ChannelUnavailable)?;
+                let data = object.data[*offset..*offset + *length].to_vec();
+                state.transfer = Some(
+                    channel
+                        .send(data, state.mtu)
+                        .map_err(|_| ActionRc::OperationFailed)?,
+                );
+                Ok(ActionRes::None)
+            }
+            ActionReq::Write {
+                offset,
+                length,
+                mode,
+            } => {
+                let size = object.data.len();
+                if *offset > size {
+                    return Err(ActionRc::InvalidParameter);
+                }
+                let end = *offset + *length;
+                if end > object.allocated && !object.properties.contains(Property::Append) {
+                    return Err(ActionRc::InvalidParameter);
+                }
+                if end < size {
+                    let required = if mode.contains(WriteMode::Truncate) {
+                        Property::Truncate
+                    } else {
+                        Property::Patch
+                    };
+                    if !object.properties.contains(required) {
+                        return Err(ActionRc::ProcedureNotPermitted);
+                    }
+                }
+                let channel = state.channel.take().ok_or(ActionRc::ChannelUnavailable)?;
+                let (id, offset, mode) = (object.id, *offset, *mode);
+                let peripheral = self.clone();
+                let transfer = channel.recv(*length, state.mtu, move |data| {
+                    let mut state = peripheral.lock();
+                    if let Some(object) = state.objects.iter_mut().find(|object| object.id == id) {
+                        let end = offset + data.len();
+                        if object.data.len() < end {
+                            object.data.resize(end, 0);
+                        }
+                        object.data[offset..end].copy_from_slice(&data);
+                        if mode.contains(WriteMode::Truncate) {
+                            object.data.truncate(end);
+                        }
+                        object.allocated = object.allocated.max(object.data.len());
+                    }
+                });
+                state.transfer = Some(transfer.map_err(|_| ActionRc::OperationFailed)?);
+                Ok(ActionRes::None)
+            }
+            ActionReq::Create { .. } | ActionReq::Abort => unreachable!(),
+        }
+    }
+}
+
+fn sort_objects(objects: &mut [MockObject], order: SortOrder) {
+    use core::cmp::Reverse;
+    use SortOrder::*;
+
+    match order {
+        NameAsc => objects.sort_by(|a, b| a.name.cmp(&b.name)),
+        TypeAsc => objects.sort_by_key(|object| object.type_),
+        CurSizeAsc => objects.sort_by_key(|object| object.data.len()),
+        CrtTimeAsc => objects.sort_by(|a, b| a.first_created.cmp(&b.first_created)),
+        ModTimeAsc => objects.sort_by(|a, b| a.last_modified.cmp(&b.last_modified)),
+        NameDesc => objects.sort_by(|a, b| b.name.cmp(&a.name)),
+        TypeDesc => objects.sort_by_key(|object| Reverse(object.type_)),
+        CurSizeDesc => objects.sort_by_key(|object| Reverse(object.data.len())),
+        CrtTimeDesc => objects.sort_by(|a, b| b.first_created.cmp(&a.first_created)),
+        ModTimeDesc => objects.sort_by(|a, b| b.last_modified.cmp(&a.last_modified)),
+    }
+}
+
+impl Gatt for MockPeripheral {
+    fn has_characteristic(&self, uuid: &Uuid) -> bool {
+        [
+            chr::ots_feature,
+            chr::object_action_control_point,
+            chr::object_list_control_point,
+            chr::object_id,
+            chr::object_name,
+            chr::object_type,
+            chr::object_size,
+            chr::object_properties,
+            chr::object_first_created,
+            chr::object_last_modified,
+            chr::object_changed,
+        ]
+        .contains(uuid)
+    }
+
+    async fn read_characteristic(&self, uuid: &Uuid) -> Result<Vec<u8>> {
+        self.settle();
+        self.read_value(uuid)
+    }
+
+    async fn write_characteristic(&self, uuid: &Uuid, value: Vec<u8>) -> Result<()> {
+        if *uuid == chr::object_action_control_point && value == [ActionOp::Abort as u8] {
+            // do not wait for transfer which is to be aborted
+            self.abort();
+        } else {
+            self.settle();
+        }
+        self.write_value(uuid, &value)
+    }
+
+    async fn subscribe(&self, uuid: &Uuid) -> Result<BoxStream<'static, Vec<u8>>> {
+        let (sender, receiver) = mpsc::unbounded();
+        self.lock()
+            .subscribers
+            .entry(*uuid)
+            .or_default()
+            .push(sender);
+        Ok(receiver.boxed())
+    }
+
+    async fn unsubscribe(&self, uuid: &Uuid) -> Result<()> {
+        self.lock().subscribers.remove(uuid);
+        Ok(())
+    }
+
+    async fn open_channel(&self) -> Result<L2capStream> {
+        self.settle();
+        let mut state = self.lock();
+        let (server, client) = channel::pair(state.mtu)?;
+        state.channel = Some(server);
+        Ok(client)
+    }
+}
@@ -0,0 +1,49 @@
+use bluez_async_ots::{blocking::OtsClient, ActionRc, CoreError, Error, WriteMode};
+use ots_testkit::{MockObject, MockPeripheral};
+use uuid::Uuid;
+
+const UNSPECIFIED: Uuid = Uuid::from_u128(0x00002aca_0000_1000_8000_00805f9b34fb);
+
+fn rejected(result: Result<usize, Error>) -> bool {
+    matches!(
+        result,
+        Err(Error::Core(CoreError::ActionError(
+            ActionRc::InvalidParameter
+        )))
+    )
+}
+
+#[test]
+fn read_write() {
+    let data: Vec<u8> = (0..=255).collect();
+    let peripheral = MockPeripheral::default();
+    let id = peripheral.add_object(MockObject::new("data", UNSPECIFIED, data.clone()));
+    let ots = OtsClient::with_gatt(peripheral.clone()).unwrap();
+
+    assert_eq!(ots.number_of().unwrap(), 1);
+    ots.first().unwrap();
+    assert_eq!(ots.metadata().unwrap().name, "data");
+
+    assert_eq!(ots.read(0, None).unwrap(), data);
+    let mut buffer = [0; 16];
+    assert_eq!(ots.read_to(250, &mut buffer).unwrap(), 6);
+    assert_eq!(buffer[..6], data[250..]);
+    // offset beyond the end of data is rejected by server
+    assert!(rejected(ots.read_to(300, &mut buffer)));
+
+    // data beyond allocated size is not written
+    assert_eq!(
+        ots.write(200, &[0xaa; 100], WriteMode::Truncate).unwrap(),
+        56
+    );
+    assert!(rejected(ots.write(300, &[0xaa; 4], WriteMode::empty())));
+    peripheral.settle();
+    let object = peripheral.object(id).unwrap();
+    assert_eq!(object.data[..200], data[..200]);
+    assert_eq!(object.data[200..], [0xaa; 56]);
+
+    assert_eq!(
+        ots.check_sum(0, 16).unwrap(),
+        ots_core::crc::crc32(&data[..16])
+    );
+}
@@ -0,0 +1,260 @@
+use bluez_async_ots::{
+    ActionFeature, ActionRc, ChangeFlag, CoreError, DateTime, Error, ExecuteCommand, ListFeature,
+    ListRc, ObjectChanged, Property, SortOrder, WriteMode,
+};
+use futures_util::StreamExt;
+use ots_testkit::{MockObject, MockPeripheral, FIRST_OBJECT_ID};
+use uuid::Uuid;
+
+const UNSPECIFIED: Uuid = Uuid::from_u128(0x00002aca_0000_1000_8000_00805f9b34fb);
+
+fn time(year: u16, month: u8, day: u8) -> DateTime {
+    DateTime {
+        year,
+        month,
+        day,
+        hour: 12,
+        minute: 0,
+        second: 0,
+    }
+}
+
+fn peripheral() -> MockPeripheral {
+    let peripheral = MockPeripheral::default();
+    peripheral.add_object(MockObject::new("beta", UNSPECIFIED, "second object"));
+    peripheral.add_object(MockObject::new("alpha", UNSPECIFIED, "first"));
+    peripheral.add_object(
+        MockObject::new("gamma", UNSPECIFIED, (0..=255).collect::<Vec<u8>>())
+            .with_properties(Property::Read | Property::Execute)
+            .with_times(Some(time(2024, 1, 20)), Some(time(2024, 2, 1))),
+    );
+    peripheral
+}
+
+#[tokio::test]
+async fn features() {
+    let ots = MockPeripheral::new(ActionFeature::Read, ListFeature::empty())
+        .client()
+        .await
+        .unwrap();
+
+    assert_eq!(ots.action_features(), &ActionFeature::Read);
+    assert_eq!(ots.list_features(), &ListFeature::empty());
+    assert!(matches!(ots.number_of().await, Err(Error::NotSupported)));
+}
+
+#[tokio::test]
+async fn list_navigation() {
+    let peripheral = peripheral();
+    let ots = peripheral.client().await.unwrap();
+
+    assert_eq!(ots.number_of().await.unwrap(), 3);
+
+    ots.first().await.unwrap();
+    assert_eq!(ots.id().await.unwrap(), Some(FIRST_OBJECT_ID));
+    assert_eq!(ots.name().await.unwrap(), "beta");
+    assert!(!ots.previous().await.unwrap());
+
+    assert!(ots.next().await.unwrap());
+    assert_eq!(ots.name().await.unwrap(), "alpha");
+    assert!(ots.next().await.unwrap());
+    assert!(!ots.next().await.unwrap());
+
+    ots.order(SortOrder::NameAsc).await.unwrap();
+    ots.first().await.unwrap();
+    assert_eq!(ots.name().await.unwrap(), "alpha");
+
+    assert!(ots.go_to(FIRST_OBJECT_ID + 2).await.unwrap());
+    let metadata = ots.metadata().await.unwrap();
+    assert_eq!(
+        metadata,
+        peripheral.object(FIRST_OBJECT_ID + 2).unwrap().metadata()
+    );
+    assert!(!ots.go_to(0).await.unwrap());
+}
+
+#[tokio::test]
+async fn read_data() {
+    let peripheral = peripheral();
+    peripheral.set_mtu(23);
+    let ots = peripheral.client().await.unwrap();
+
+    ots.go_to(FIRST_OBJECT_ID + 2).await.unwrap();
+
+    let data = ots.read(0, None).await.unwrap();
+    assert_eq!(data, (0..=255).collect::<Vec<u8>>());
+
+    let data = ots.read(100, Some(50)).await.unwrap();
+    assert_eq!(data, (100..150).collect::<Vec<u8>>());
+
+    assert_eq!(
+        ots.check_sum(0, 256).await.unwrap(),
+        ots_core::crc::crc32(&data_of(&peripheral, FIRST_OBJECT_ID + 2))
+    );
+}
+
+#[tokio::test]
+async fn write_data() {
+    let peripheral = peripheral();
+    let ots = peripheral.client().await.unwrap();
+
+    ots.first().await.unwrap();
+    assert_eq!(
+        ots.write(7, b"OBJECT", WriteMode::empty()).await.unwrap(),
+        6
+    );
+    assert_eq!(data_of(&peripheral, FIRST_OBJECT_ID), b"second OBJECT");
+
+    ots.write(0, b"new", WriteMode::Truncate).await.unwrap();
+    assert_eq!(data_of(&peripheral, FIRST_OBJECT_ID), b"new");
+    assert_eq!(ots.read(0, None).await.unwrap(), b"new");
+
+    ots.set_name("renamed").await.unwrap();
+    assert_eq!(ots.name().await.unwrap(), "renamed");
+    assert_eq!(peripheral.object(FIRST_OBJECT_ID).unwrap().name, "renamed");
+}
+
+#[tokio::test]
+async fn offset_beyond_end() {
+    let peripheral = peripheral();
+    let ots = peripheral.client().await.unwrap();
+    let rejected = |result| {
+        matches!(
+            result,
+            Err(Error::Core(CoreError::ActionError(
+                ActionRc::InvalidParameter
+            )))
+        )
+    };
+
+    ots.first().await.unwrap();
+    let mut buffer = [0; 4];
+    assert!(rejected(ots.read_to(20, &mut buffer).await));
+    assert!(rejected(ots.write(20, b"data", WriteMode::empty()).await));
+    assert!(rejected(
+        ots.write_stream(20, None, WriteMode::empty())
+            .await
+            .map(|_| 0)
+    ));
+    assert_eq!(data_of(&peripheral, FIRST_OBJECT_ID), b"second object");
+}
+
+#[tokio::test]
+async fn set_metadata() {
+    let peripheral = peripheral();
+    let ots = peripheral.client().await.unwrap();
+
+    ots.first().await.unwrap();
+    ots.set_first_created(&time(2023, 5, 1)).await.unwrap();
+    ots.set_last_modified(&time(2023, 6, 2)).await.unwrap();
+    ots.set_properties(Property::Read | Property::Delete)
+        .await
+        .unwrap();
+
+    let meta = ots.metadata().await.unwrap();
+    assert_eq!(meta.first_created, Some(time(2023, 5, 1)));
+    assert_eq!(meta.last_modified, Some(time(2023, 6, 2)));
+    assert_eq!(meta.properties, Property::Read | Property::Delete);
+}
+
+#[tokio::test]
+async fn create_execute_delete() {
+    let peripheral = peripheral();
+    peripheral.set_execute(|object, param| {
+        let mut res = object.name.as_bytes().to_vec();
+        res.extend_from_slice(param);
+        Ok(res)
+    });
+    let ots = peripheral.client().await.unwrap();
+
+    ots.go_to(FIRST_OBJECT_ID + 2).await.unwrap();
+    assert_eq!(ots.execute(b"!".to_vec()).await.unwrap(), b"gamma!");
+    assert!(matches!(
+        ots.delete().await,
+        Err(Error::Core(CoreError::ActionError(
+            ActionRc::ProcedureNotPermitted
+        )))
+    ));
+
+    ots.create(16, UNSPECIFIED).await.unwrap();
+    assert_eq!(ots.id().await.unwrap(), Some(FIRST_OBJECT_ID + 3));
+    assert_eq!(ots.size().await.unwrap().allocated, 16);
+    assert_eq!(ots.number_of().await.unwrap(), 4);
+
+    ots.delete().await.unwrap();
+    assert_eq!(ots.number_of().await.unwrap(), 3);
+    assert!(matches!(
+        ots.previous().await,
+        Err(Error::Core(CoreError::ListError(ListRc::OperationFailed)))
+    ));
+}
+
+/// Keep last lines of log
+struct Rotate;
+
+impl ExecuteCommand for Rotate {
+    const OBJECT_TYPE: Uuid = UNSPECIFIED;
+    type Param = u32;
+    type Response = String;
+}
+
+#[tokio::test]
+async fn typed_execute() {
+    let peripheral = peripheral();
+    peripheral.add_object(MockObject::new(
+        "firmware",
+        Uuid::from_u128(0x1234),
+        Vec::new(),
+    ));
+    peripheral.set_execute(|object, param| {
+        let lines = u32::from_le_bytes(param.try_into().map_err(|_| ActionRc::InvalidParameter)?);
+        Ok(format!("{}: {lines}", object.name).into_bytes())
+    });
+    let ots = peripheral.client().await.unwrap();
+
+    ots.go_to(FIRST_OBJECT_ID + 2).await.unwrap();
+    assert_eq!(
+        ots.execute_typed::<Rotate>(&100).await.unwrap(),
+        "gamma: 100"
+    );
+
+    ots.go_to(FIRST_OBJECT_ID + 3).await.unwrap();
+    assert!(matches!(
+        ots.execute_typed::<Rotate>(&100).await,
+        Err(Error::Core(CoreError::ActionError(
+            ActionRc::UnsupportedType
+        )))
+    ));
+}
+
+#[tokio::test]
+async fn object_changed() {
+    let peripheral = peripheral();
+    let ots = peripheral.client().await.unwrap();
+
+    let mut changes = ots.subscribe_changes().await.unwrap();
+    peripheral.indicate_change(FIRST_OBJECT_ID, ChangeFlag::Contents);
+    peripheral.indicate_change(FIRST_OBJECT_ID + 1, ChangeFlag::Client | ChangeFlag::Delete);
+
+    assert_eq!(
+        changes.next().await,
+        Some(ObjectChanged {
+            flags: ChangeFlag::Contents,
+            id: FIRST_OBJECT_ID,
+        })
+    );
+    assert_eq!(
+        changes.next().await,
+        Some(ObjectChanged {
+            flags: ChangeFlag::Client | ChangeFlag::Delete,
+            id: FIRST_OBJECT_ID + 1,
+        })
+    );
+
+    ots.unsubscribe_changes().await.unwrap();
+    assert_eq!(changes.next().await, None);
+}
+
+fn data_of(peripheral: &MockPeripheral, id: u64) -> Vec<u8> {
+    peripheral.object(id).unwrap().data
+}
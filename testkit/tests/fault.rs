@@ -0,0 +1,119 @@
+use bluez_async_ots::{ActionRc, CoreError, Error, OtsClient, WriteMode};
+use ots_testkit::{
+    fault::{FaultScript, FaultyGatt, ResponseFault},
+    MockObject, MockPeripheral, FIRST_OBJECT_ID,
+};
+use std::time::Duration;
+use uuid::Uuid;
+
+const UNSPECIFIED: Uuid = Uuid::from_u128(0x00002aca_0000_1000_8000_00805f9b34fb);
+
+async fn client(script: FaultScript) -> (MockPeripheral, OtsClient<FaultyGatt<MockPeripheral>>) {
+    let peripheral = MockPeripheral::default();
+    peripheral.add_object(MockObject::new(
+        "data",
+        UNSPECIFIED,
+        (0..100).collect::<Vec<u8>>(),
+    ));
+    peripheral.add_object(MockObject::new("empty", UNSPECIFIED, ""));
+    let ots = OtsClient::with_gatt(FaultyGatt::new(peripheral.clone(), script))
+        .await
+        .unwrap();
+    (peripheral, ots)
+}
+
+#[tokio::test]
+async fn dropped_and_late_responses() {
+    let (_, ots) = client(
+        FaultScript::new(1)
+            .fault(ResponseFault::Drop)
+            .fault(ResponseFault::Delay(Duration::from_millis(1500)))
+            .fault(ResponseFault::Delay(Duration::from_millis(10))),
+    )
+    .await;
+
+    assert!(matches!(ots.first().await, Err(Error::NoResponse)));
+    assert!(matches!(ots.first().await, Err(Error::NoResponse)));
+    // retry succeeds in time
+    ots.first().await.unwrap();
+    assert_eq!(ots.id().await.unwrap(), Some(FIRST_OBJECT_ID));
+}
+
+#[tokio::test]
+async fn unexpected_result_codes() {
+    let (_, ots) = client(
+        FaultScript::new(2)
+            .pass()
+            .fault(ResponseFault::ResultCode(ActionRc::ObjectLocked as _))
+            .fault(ResponseFault::ResultCode(0xff)),
+    )
+    .await;
+
+    ots.first().await.unwrap();
+    assert!(matches!(
+        ots.check_sum(0, 10).await,
+        Err(Error::Core(CoreError::ActionError(ActionRc::ObjectLocked)))
+    ));
+    assert!(matches!(
+        ots.check_sum(0, 10).await,
+        Err(Error::Core(CoreError::BadOpCode { code: 0xff, .. }))
+    ));
+}
+
+#[tokio::test]
+async fn reordered_responses() {
+    let (_, ots) = client(FaultScript::new(3).fault(ResponseFault::Reorder)).await;
+
+    // response to first request is held
+    assert!(matches!(ots.first().await, Err(Error::NoResponse)));
+    // stale response to previous request is skipped
+    assert!(ots.number_of().await.is_ok());
+}
+
+#[tokio::test]
+async fn seeded_random_faults() {
+    async fn run(seed: u64) -> Vec<ResponseFault> {
+        let faults = [ResponseFault::Corrupt, ResponseFault::ResultCode(0x0a)];
+        let (_, ots) = client(FaultScript::new(seed).random(50, faults)).await;
+        for _ in 0..8 {
+            let _ = ots.number_of().await;
+        }
+        ots.gatt().injected()
+    }
+
+    let injected = run(42).await;
+    assert!(!injected.is_empty());
+    assert_eq!(injected, run(42).await);
+
+    // seed which turns into zero state of generator
+    let injected = run(0x9e3779b97f4a7c15).await;
+    assert!(!injected.is_empty());
+    assert_ne!(injected, [ResponseFault::Corrupt; 8]);
+}
+
+#[tokio::test]
+async fn truncated_read_and_abort() {
+    let (_, ots) = client(FaultScript::new(4).cut(30).pass_channel()).await;
+
+    ots.first().await.unwrap();
+    assert!(matches!(
+        ots.read(0, None).await,
+        Err(Error::Io(error)) if error.kind() == std::io::ErrorKind::UnexpectedEof
+    ));
+    ots.abort().await.unwrap();
+    assert_eq!(
+        ots.read(0, None).await.unwrap(),
+        (0..100).collect::<Vec<u8>>()
+    );
+}
+
+#[tokio::test]
+async fn truncated_write() {
+    let (peripheral, ots) = client(FaultScript::new(5).cut(70)).await;
+
+    ots.first().await.unwrap();
+    let _ = ots.write(0, &[0xaa; 100], WriteMode::Truncate).await;
+
+    let data = peripheral.object(FIRST_OBJECT_ID).unwrap().data;
+    assert_eq!(data, [0xaa; 70]);
+}
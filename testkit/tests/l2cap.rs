@@ -0,0 +1,36 @@
+use bluez_async_ots::L2capStream;
+use futures_util::{SinkExt, StreamExt};
+use socket2::Socket;
+use std::os::fd::FromRawFd;
+
+fn stream_pair(mtu: usize) -> (L2capStream, L2capStream) {
+    let mut fds = [0; 2];
+    assert_eq!(
+        unsafe { libc::socketpair(libc::AF_UNIX, libc::SOCK_SEQPACKET, 0, fds.as_mut_ptr()) },
+        0
+    );
+    let stream = |fd| {
+        let socket = ots_core::l2cap::L2capSocket::from(unsafe { Socket::from_raw_fd(fd) });
+        L2capStream::with_mtu(socket.into(), mtu, mtu).unwrap()
+    };
+    (stream(fds[0]), stream(fds[1]))
+}
+
+#[tokio::test]
+async fn sdu_stream() {
+    let (mut sender, mut receiver) = stream_pair(8);
+    assert_eq!(sender.max_send_sdu(), 8);
+    assert_eq!(receiver.max_recv_sdu(), 8);
+
+    sender.send(b"first".to_vec()).await.unwrap();
+    sender.send(b"the second".to_vec()).await.unwrap_err();
+    sender.send(b"12345678".to_vec()).await.unwrap();
+    sender.send_sdu(b"3rd").await.unwrap();
+    sender.close().await.unwrap();
+
+    // boundaries of SDUs are preserved
+    assert_eq!(receiver.next().await.unwrap().unwrap(), b"first");
+    assert_eq!(receiver.next().await.unwrap().unwrap(), b"12345678");
+    assert_eq!(receiver.next().await.unwrap().unwrap(), b"3rd");
+    assert!(receiver.next().await.is_none());
+}